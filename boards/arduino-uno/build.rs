@@ -1,4 +1,23 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 fn main() {
     // Set the MCU for AVR compilation
     println!("cargo:rustc-env=AVR_CPU=atmega328p");
-}
\ No newline at end of file
+
+    // Build time for rtc::build_time(), overridable for reproducible builds
+    // Declaring the env var replaces the default "any file changed" trigger,
+    // so keep the timestamp fresh when the sources change
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=build.rs");
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0)
+        });
+    println!("cargo:rustc-env=OSSIDATA_BUILD_TIMESTAMP={timestamp}");
+}
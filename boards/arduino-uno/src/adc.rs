@@ -5,6 +5,7 @@
 //! Internal 1.1V, or external AREF pin.
 
 use core::ptr::{read_volatile, write_volatile};
+use crate::power::{self, PowerPeripheral};
 
 // ADC registers
 const ADMUX: *mut u8 = 0x7C as *mut u8;   // ADC Multiplexer Selection Register
@@ -25,6 +26,20 @@ const ADPS2: u8 = 2;  // ADC Prescaler Select bit 2
 const ADPS1: u8 = 1;  // ADC Prescaler Select bit 1
 const ADPS0: u8 = 0;  // ADC Prescaler Select bit 0
 
+// Maximum ADC clock for full 10-bit resolution
+const ADC_MAX_CLOCK: u32 = 200_000;
+
+/// Select the smallest ADC prescaler that keeps the ADC clock within range
+fn adc_prescaler_bits(f_cpu: u32) -> u8 {
+    // ADPS2:0 = 1..7 selects division factors 2..128
+    for bits in 1..7u8 {
+        if f_cpu >> bits <= ADC_MAX_CLOCK {
+            return bits;
+        }
+    }
+    (1 << ADPS2) | (1 << ADPS1) | (1 << ADPS0)
+}
+
 /// ADC voltage reference options
 #[derive(Clone, Copy)]
 pub enum AdcReference {
@@ -49,10 +64,13 @@ impl Adc {
 
     /// Initialize the ADC with a specific voltage reference
     pub fn with_reference(reference: AdcReference) -> Self {
+        power::acquire(PowerPeripheral::Adc);
+
         unsafe {
-            // Enable ADC and set prescaler to 128 (125 KHz for 16 MHz clock)
+            // Enable ADC and set the prescaler for an ADC clock of at most 200 kHz
+            // (128 -> 125 KHz for 16 MHz clock, 64 -> 125 KHz for 8 MHz)
             // This gives good balance between speed and accuracy
-            write_volatile(ADCSRA, (1 << ADEN) | (1 << ADPS2) | (1 << ADPS1) | (1 << ADPS0));
+            write_volatile(ADCSRA, (1 << ADEN) | adc_prescaler_bits(crate::clock::cpu_frequency()));
         }
 
         let mut adc = Adc { reference };
@@ -146,4 +164,16 @@ impl Adc {
         // Use u32 to avoid overflow
         ((reading as u32 * max_voltage as u32) / 1023) as u16
     }
+
+    /// Disable the ADC
+    ///
+    /// The ADC is powered down once no other `Adc` instance is alive.
+    pub fn end(self) {}
+}
+
+impl Drop for Adc {
+    fn drop(&mut self) {
+        // Powering down clears ADEN first
+        power::release(PowerPeripheral::Adc, || {});
+    }
 }
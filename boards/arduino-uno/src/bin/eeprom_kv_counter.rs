@@ -0,0 +1,147 @@
+//! Wear-leveled persistent counter
+//!
+//! Same counter as `eeprom_counter`, kept in a `KvStore` instead of two fixed
+//! EEPROM cells. Each time the Arduino resets, the counter increments.
+//!
+//! Hardware setup:
+//! - Connect a button between D2 and GND (optional - for manual increment)
+//! - Built-in LED on D13 will blink with each increment
+//! - Serial monitor at 9600 baud shows counter value
+//!
+//! The counter is stored in a wear-leveled key-value store in EEPROM bytes
+//! 256-511, clear of the `eeprom_counter` cells. Every update appends a new
+//! record instead of rewriting the same two cells, so the EEPROM lasts far
+//! longer than with fixed addresses.
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Delay, Eeprom, KvStore, attach_interrupt, ExternalInterrupt, InterruptMode};
+use panic_halt as _;
+use core::cell::Cell;
+use critical_section::Mutex;
+
+// EEPROM region used by the key-value store
+const STORE_START: u16 = 256;
+const STORE_LEN: u16 = 256;
+
+// Key of the counter in the store
+const COUNTER_KEY: u8 = 0;
+
+// Button press flag (set by interrupt)
+static BUTTON_PRESSED: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
+
+// Interrupt handler for button press
+fn button_handler() {
+    critical_section::with(|cs| {
+        BUTTON_PRESSED.borrow(cs).set(true);
+    });
+}
+
+#[avr_device::entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+    let mut led = peripherals.pins.d13.into_output();
+    let mut store = KvStore::mount(Eeprom::new(), STORE_START, STORE_LEN).unwrap();
+
+    // Configure D2 as input with pull-up for button
+    let _button = peripherals.pins.d2.into_pull_up_input();
+
+    serial.println("Key-Value Counter Demo");
+    serial.println("----------------------");
+    serial.println("");
+
+    // Read current counter from EEPROM
+    let mut counter: u16 = store.get(COUNTER_KEY).unwrap_or(None).unwrap_or(0);
+
+    serial.write_str("Startup count: ");
+    print_number(&mut serial, counter);
+    serial.println("");
+    serial.println("");
+
+    // Increment counter for this boot
+    counter = counter.wrapping_add(1);
+
+    // Write new counter value to EEPROM
+    store.set(COUNTER_KEY, &counter).ok();
+
+    serial.write_str("New count: ");
+    print_number(&mut serial, counter);
+    serial.println("");
+    serial.println("");
+
+    // Blink LED to show increment
+    for _ in 0..3 {
+        led.set_high();
+        delay.delay_ms(100);
+        led.set_low();
+        delay.delay_ms(100);
+    }
+
+    serial.println("Press button on D2 to increment counter");
+    serial.println("(or reset Arduino to increment on startup)");
+    serial.println("");
+
+    // Attach interrupt for button
+    attach_interrupt(ExternalInterrupt::Int0, InterruptMode::Falling, button_handler);
+
+    loop {
+        // Check if button was pressed
+        let pressed = critical_section::with(|cs| {
+            let was_pressed = BUTTON_PRESSED.borrow(cs).get();
+            if was_pressed {
+                BUTTON_PRESSED.borrow(cs).set(false);
+                true
+            } else {
+                false
+            }
+        });
+
+        if pressed {
+            // Increment counter
+            counter = counter.wrapping_add(1);
+
+            // Write to EEPROM
+            store.set(COUNTER_KEY, &counter).ok();
+
+            // Show new count
+            serial.write_str("Button pressed! New count: ");
+            print_number(&mut serial, counter);
+            serial.println("");
+
+            // Blink LED
+            led.set_high();
+            delay.delay_ms(100);
+            led.set_low();
+
+            // Debounce delay
+            delay.delay_ms(200);
+        }
+
+        delay.delay_ms(50);
+    }
+}
+
+// Helper function to print a number
+fn print_number(serial: &mut Serial, num: u16) {
+    if num == 0 {
+        serial.write_byte(b'0');
+        return;
+    }
+
+    let mut n = num;
+    let mut digits = [0u8; 10];
+    let mut count = 0;
+
+    while n > 0 {
+        digits[count] = (n % 10) as u8;
+        n /= 10;
+        count += 1;
+    }
+
+    for i in (0..count).rev() {
+        serial.write_byte(b'0' + digits[i]);
+    }
+}
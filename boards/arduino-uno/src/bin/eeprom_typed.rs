@@ -0,0 +1,82 @@
+//! Typed EEPROM values example
+//!
+//! This example stores whole values and structs in EEPROM with `get`/`put`,
+//! and keeps calibration data in versioned `EepromCell`s that detect
+//! missing or stale contents.
+//!
+//! Hardware: No external hardware required
+//! Serial monitor at 9600 baud shows test results
+//!
+//! The values live at address 60 and from address 100 on, clear of the bytes
+//! used by `eeprom_test`.
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Delay, Eeprom, EepromData, eeprom_cells};
+use panic_halt as _;
+
+// Calibration data stored as a whole struct
+#[derive(Clone, Copy, PartialEq)]
+#[repr(C)]
+struct Calibration {
+    offset: i16,
+    gain: u16,
+}
+
+unsafe impl EepromData for Calibration {}
+
+eeprom_cells! {
+    static CALIBRATION: EepromCell<Calibration, 100> = version 1;
+    static SAMPLES: EepromCell<[u8; 4], 108> = version 1;
+}
+
+#[avr_device::entry]
+fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+    let eeprom = Eeprom::new();
+
+    serial.println("Typed EEPROM Test");
+    serial.println("-----------------");
+    serial.println("");
+
+    delay.delay_ms(100);
+
+    // Test 1: Typed get/put
+    serial.println("Test 1: Typed get/put");
+    eeprom.put(60, &0xBEEFu16);
+    if eeprom.get::<u16>(60) == Some(0xBEEF) {
+        serial.println("  u16 round trip - PASS");
+    } else {
+        serial.println("  u16 round trip - FAIL");
+    }
+
+    serial.println("");
+    delay.delay_ms(100);
+
+    // Test 2: Versioned cells
+    serial.println("Test 2: EepromCell");
+    let calibration = Calibration { offset: -12, gain: 1024 };
+    CALIBRATION.store(&calibration);
+    if CALIBRATION.load() == Some(calibration) {
+        serial.println("  Calibration round trip - PASS");
+    } else {
+        serial.println("  Calibration round trip - FAIL");
+    }
+
+    SAMPLES.clear();
+    if SAMPLES.load().is_none() {
+        serial.println("  Cleared cell is invalid - PASS");
+    } else {
+        serial.println("  Cleared cell is invalid - FAIL");
+    }
+
+    serial.println("");
+    serial.println("All tests complete!");
+
+    loop {
+        delay.delay_ms(1000);
+    }
+}
@@ -0,0 +1,54 @@
+//! LCD on a direct 4-bit parallel bus with custom characters and scrolling
+//!
+//! Hardware setup (Arduino LiquidCrystal wiring):
+//! - LCD RS -> D12, EN -> D11, RW -> GND
+//! - LCD D4 -> D5, D5 -> D4, D6 -> D3, D7 -> D2
+//! - Contrast potentiometer on V0
+//!
+//! The display will show:
+//! Line 1: A heart and a smiley from CGRAM
+//! Line 2: An uptime counter, followed by a marquee scrolling the display
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Lcd, LcdGeometry, LcdParallel, Delay, millis};
+use panic_halt as _;
+
+const HEART: [u8; 8] = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+const SMILEY: [u8; 8] = [0x00, 0x0A, 0x0A, 0x00, 0x11, 0x0E, 0x00, 0x00];
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut delay = Delay::new();
+
+    let bus = LcdParallel::four_bit(12, 11, [5, 4, 3, 2]);
+    let mut lcd = Lcd::with_transport(bus).with_geometry(LcdGeometry::Lcd16x2);
+
+    // The parallel bus cannot fail
+    let _ = lcd.init();
+    let _ = lcd.create_char(0, HEART);
+    let _ = lcd.create_char(1, SMILEY);
+
+    loop {
+        let _ = lcd.clear();
+        let _ = lcd.write_custom(0);
+        let _ = lcd.write_str(" Ossidata ");
+        let _ = lcd.write_custom(1);
+
+        for _ in 0..5 {
+            let _ = lcd.set_cursor(1, 0);
+            let _ = ufmt::uwrite!(lcd, "Up {} s   ", millis() / 1000);
+            delay.delay_ms(1000);
+        }
+
+        // Scroll the text off to the right and back
+        for _ in 0..16 {
+            let _ = lcd.scroll_display_right();
+            delay.delay_ms(150);
+        }
+        let _ = lcd.home();
+        delay.delay_ms(500);
+    }
+}
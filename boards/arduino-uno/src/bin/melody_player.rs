@@ -0,0 +1,80 @@
+//! Background melody player example
+//!
+//! Plays an RTTTL ringtone and a note array from flash while the main loop
+//! keeps blinking the LED, then loops the ringtone faster and faster.
+//!
+//! Hardware setup:
+//! - Connect a piezo buzzer or speaker between pin 11 and GND
+//! - A current-limiting resistor (100-330 ohms) is recommended
+//! - Serial monitor at 9600 baud shows melody progress
+
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::*;
+use panic_halt as _;
+
+const BUZZER_PIN: u8 = 11;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+    let mut led = peripherals.pins.d13.into_output();
+
+    serial.write_str("\r\n=== Melody Player ===\r\n");
+
+    let ringtone = match rtttl!("Tetris:d=4,o=5,b=160:e6,8b,8c6,8d6,16e6,16d6,8c6,8b,a,8a,8c6,e6,8d6,8c6,b,8b,8c6,d6,e6,c6,a,2a") {
+        Ok(melody) => melody,
+        Err(_) => {
+            serial.write_str("Invalid RTTTL\r\n");
+            loop {
+                delay.delay_ms(1000);
+            }
+        }
+    };
+
+    // Twinkle twinkle little star, first line
+    let twinkle = melody![
+        Note::new(note_frequency(60), Note::QUARTER),
+        Note::new(note_frequency(60), Note::QUARTER),
+        Note::new(note_frequency(67), Note::QUARTER),
+        Note::new(note_frequency(67), Note::QUARTER),
+        Note::new(note_frequency(69), Note::QUARTER),
+        Note::new(note_frequency(69), Note::QUARTER),
+        Note::new(note_frequency(67), Note::HALF),
+        Note::rest(Note::HALF),
+    ];
+
+    serial.write_str("Playing RTTTL\r\n");
+    if !play_melody(BUZZER_PIN, ringtone) {
+        serial.write_str("No tone timer free\r\n");
+    }
+    while is_melody_playing() {
+        led.toggle();
+        delay.delay_ms(100);
+    }
+
+    serial.write_str("Playing notes\r\n");
+    if !play_melody(BUZZER_PIN, twinkle.with_tempo(100)) {
+        serial.write_str("No tone timer free\r\n");
+    }
+    while is_melody_playing() {
+        led.toggle();
+        delay.delay_ms(250);
+    }
+
+    serial.write_str("Looping with rising tempo\r\n");
+    let mut tempo = ringtone.tempo();
+    if !loop_melody(BUZZER_PIN, ringtone) {
+        serial.write_str("No tone timer free\r\n");
+    }
+    loop {
+        delay.delay_ms(2000);
+        tempo = if tempo >= 320 { ringtone.tempo() } else { tempo + 20 };
+        set_melody_tempo(tempo);
+        let _ = ufmt::uwriteln!(serial, "Tempo {}\r", tempo);
+    }
+}
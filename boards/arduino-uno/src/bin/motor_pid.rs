@@ -0,0 +1,79 @@
+//! DC motor with encoder feedback
+//!
+//! Runs a gear motor at a regulated speed, then holds it at a fixed position,
+//! both with a PID loop closed over the quadrature encoder every 10 ms.
+//!
+//! Hardware setup (TB6612FNG, an L298N is wired the same way):
+//! - PWMA to pin 9, AIN1 to pin 7, AIN2 to pin 8, STBY to 5V
+//! - Encoder channel A to pin 2, channel B to pin 3
+//! - Motor supply to VM, logic supply to 5V, common GND
+//! - Open serial monitor at 9600 baud
+//!
+//! If the motor runs away instead of settling, swap the encoder channels.
+
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::*;
+use panic_halt as _;
+
+/// Control loop period in milliseconds
+const PERIOD_MS: u32 = 10;
+
+/// Target speed in encoder counts per period
+const TARGET_SPEED: i32 = 20;
+
+/// Target position in encoder counts
+const TARGET_POSITION: i32 = 0;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let pins = peripherals.pins;
+    let mut serial = Serial::new(9600);
+
+    let mut motor = Motor::pwm_dir(
+        pins.d9.into_output().into_pwm(PwmFrequency::Freq31kHz),
+        pins.d7.into_output(),
+        pins.d8.into_output(),
+    );
+    let mut encoder = Encoder::new(2, 3);
+
+    serial.write_str("\r\n=== Motor PID ===\r\n");
+
+    // Speed loop: mostly integral, the output is held between updates
+    let mut speed_pid = Pid::new(PidGains::new(4 * GAIN_ONE, 2 * GAIN_ONE, 0), -255, 255);
+    // Position loop: proportional with damping
+    let mut position_pid = Pid::new(PidGains::new(3 * GAIN_ONE, 0, 8 * GAIN_ONE), -255, 255);
+
+    let mut next = millis();
+    let mut ticks: u16 = 0;
+    loop {
+        // Run for 3 s, hold for 3 s
+        let holding = ticks >= 300;
+
+        let output = if holding {
+            position_pid.update(TARGET_POSITION, encoder.position())
+        } else {
+            speed_pid.update(TARGET_SPEED, encoder.take_delta())
+        };
+        motor.set_speed(output as i16);
+
+        if ticks % 50 == 0 {
+            let _ = ufmt::uwriteln!(serial, "pos {} out {}\r", encoder.position(), output);
+        }
+
+        ticks += 1;
+        if ticks == 600 {
+            ticks = 0;
+            speed_pid.reset();
+            encoder.take_delta();
+        } else if ticks == 300 {
+            position_pid.reset();
+        }
+
+        next = next.wrapping_add(PERIOD_MS);
+        while (millis().wrapping_sub(next) as i32) < 0 {}
+    }
+}
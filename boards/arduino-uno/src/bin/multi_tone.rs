@@ -0,0 +1,62 @@
+//! Several tones at once
+//!
+//! Plays a two-voice interval on two buzzers, adds a quiet chirp on a third
+//! pin, then fades the chirp in and out with the tone volume.
+//!
+//! Hardware setup:
+//! - Piezo buzzers between pin 11 and GND, pin 9 and GND, pin 4 and GND
+//! - A current-limiting resistor (100-330 ohms) per buzzer is recommended
+//! - Serial monitor at 9600 baud shows test progress
+//!
+//! Pins 11 and 9 are toggled by Timer2 and Timer1 in hardware; pin 4 runs on
+//! Timer0 next to `millis()`.
+
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::*;
+use panic_halt as _;
+
+const LOW_PIN: u8 = 11;
+const HIGH_PIN: u8 = 9;
+const CHIRP_PIN: u8 = 4;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+
+    serial.write_str("\r\n=== Multi Tone ===\r\n");
+
+    loop {
+        serial.write_str("Fifth: A4 + E5\r\n");
+        tone(LOW_PIN, 440);
+        tone(HIGH_PIN, 659);
+        delay.delay_ms(1000);
+
+        serial.write_str("Chirp on a third pin\r\n");
+        tone_with_volume(CHIRP_PIN, 2000, 40);
+        delay.delay_ms(1000);
+
+        serial.write_str("Fading the chirp\r\n");
+        for volume in (0..=MAX_VOLUME).step_by(15).chain((0..=MAX_VOLUME).rev().step_by(15)) {
+            set_tone_volume(CHIRP_PIN, volume);
+            delay.delay_ms(40);
+        }
+
+        // All timers are busy, so a fourth pin gets no tone
+        tone(2, 1000);
+
+        no_tone(CHIRP_PIN);
+        no_tone(HIGH_PIN);
+        delay.delay_ms(500);
+        no_tone(LOW_PIN);
+
+        serial.write_str("Short beeps\r\n");
+        tone_duration(LOW_PIN, 880, 150);
+        tone_duration(HIGH_PIN, 1320, 300);
+        delay.delay_ms(1500);
+    }
+}
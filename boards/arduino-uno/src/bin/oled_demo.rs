@@ -0,0 +1,69 @@
+//! SSD1306 OLED example
+//!
+//! Demonstrates both drawing modes of the OLED driver:
+//! 1. Text terminal with the built-in 5x7 font
+//! 2. Page-buffered graphics through embedded-graphics
+//!
+//! Hardware setup:
+//! - Connect a 128x64 SSD1306 I2C module to A4 (SDA) and A5 (SCL)
+//! - Most modules use address 0x3C (0x3D with the address jumper set)
+//! - For an SH1106 module, change `OledController::Ssd1306` below
+//!
+//! The display alternates every two seconds between an uptime counter
+//! and a bouncing square inside a frame.
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, I2c, Oled, OledController, OledI2c, OLED_I2C_ADDRESS, Delay, millis, F};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::primitives::Rectangle;
+use panic_halt as _;
+
+const SQUARE: u32 = 12;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut delay = Delay::new();
+
+    let interface = OledI2c::new(I2c::new(), OLED_I2C_ADDRESS);
+    let mut oled = Oled::new(interface, OledController::Ssd1306);
+
+    if oled.init().is_err() {
+        // Display not connected or wrong address (check with i2c_scanner)
+        loop {
+            delay.delay_ms(1000);
+        }
+    }
+
+    let mut x: i32 = 0;
+    let mut dx: i32 = 4;
+
+    loop {
+        // Text mode
+        let _ = oled.clear();
+        let _ = oled.write_flash_str(&F!("Ossidata OLED demo\n\n"));
+        let _ = ufmt::uwriteln!(oled, "Uptime: {} s", millis() / 1000);
+        delay.delay_ms(2000);
+
+        // Graphics mode: redraw the whole scene for each frame
+        let start = millis();
+        while millis().wrapping_sub(start) < 2000 {
+            let _ = oled.draw(|page| {
+                let frame = Rectangle::new(Point::zero(), Size::new(128, 64));
+                let inside = Rectangle::new(Point::new(1, 1), Size::new(126, 62));
+                let square = Rectangle::new(Point::new(x, 26), Size::new(SQUARE, SQUARE));
+                let _ = page.fill_solid(&frame, BinaryColor::On);
+                let _ = page.fill_solid(&inside, BinaryColor::Off);
+                let _ = page.fill_solid(&square, BinaryColor::On);
+            });
+
+            x += dx;
+            if x <= 2 || x >= 126 - SQUARE as i32 {
+                dx = -dx;
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! 8-bit PCM sound playback
+//!
+//! Plays a sound effect from flash, loops a short hum, then streams a
+//! sweeping triangle wave generated in the main loop, all through PWM on
+//! pin 11 without a DAC.
+//!
+//! Hardware setup:
+//! - Connect a piezo buzzer between pin 11 and GND, or
+//! - Filter pin 11 with 1 kohm in series and 33 nF to GND, then feed an
+//!   amplifier (LM386, PAM8403) driving a small speaker
+//! - Serial monitor at 9600 baud shows progress
+//!
+//! Recorded sounds work the same way: convert them to raw unsigned 8-bit
+//! mono, e.g. `sox hello.wav -r 8000 -c 1 -b 8 -e unsigned hello.raw`, and use
+//! `pcm_clip!(8000, include_bytes!("hello.raw"))`.
+
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::*;
+use panic_halt as _;
+
+const SAMPLE_RATE: u16 = 8000;
+
+/// "Coin" effect: two square wave notes with a fading volume, 0.25 s
+const COIN: [u8; 2000] = {
+    let mut samples = [PCM_SILENCE; 2000];
+    let mut i = 0;
+    while i < samples.len() {
+        // B5 for 60 ms, then E6, as half periods in samples
+        let half_period = if i < 480 { 4 } else { 3 };
+        let amplitude = 127 - (i as u32 * 127 / samples.len() as u32) as u8;
+        samples[i] = if (i / half_period) % 2 == 0 { 128 + amplitude } else { 128 - amplitude };
+        i += 1;
+    }
+    samples
+};
+
+/// 100 Hz hum with a buzzy harmonic, one period
+const HUM: [u8; 80] = {
+    let mut samples = [PCM_SILENCE; 80];
+    let mut i = 0;
+    while i < samples.len() {
+        // Triangle at 100 Hz plus a quieter square at 400 Hz
+        let triangle = if i < 40 { i * 3 } else { (80 - i) * 3 };
+        let square = if (i / 10) % 2 == 0 { 20 } else { 0 };
+        samples[i] = (68 + triangle + square) as u8;
+        i += 1;
+    }
+    samples
+};
+
+#[avr_device::entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+
+    let pin = peripherals.pins.d11.into_output().into_pwm(PwmFrequency::Freq31kHz);
+    let mut player = PcmPlayer::new(pin);
+
+    let coin = pcm_clip!(SAMPLE_RATE, &COIN);
+    let hum = pcm_clip!(SAMPLE_RATE, &HUM);
+
+    serial.write_str("\r\n=== PCM Player ===\r\n");
+
+    loop {
+        serial.write_str("Coin\r\n");
+        for _ in 0..3 {
+            player.play(coin).unwrap();
+            while player.is_playing() {}
+            delay.delay_ms(150);
+        }
+
+        serial.write_str("Looping hum\r\n");
+        player.play_looping(hum).unwrap();
+        delay.delay_ms(1500);
+        player.stop();
+        delay.delay_ms(300);
+
+        serial.write_str("Streaming sweep\r\n");
+        player.stream(SAMPLE_RATE).unwrap();
+        let start = millis();
+        let mut phase: u16 = 0;
+        let mut step: u16 = 200;
+        while millis().wrapping_sub(start) < 2000 {
+            player.fill(|| {
+                phase = phase.wrapping_add(step);
+                // Triangle from the top 9 bits of the phase
+                let ramp = (phase >> 7) as u8;
+                let sample = if phase & 0x8000 == 0 { ramp } else { 255 - ramp };
+                step = if step >= 4000 { 200 } else { step + 1 };
+                Some(sample)
+            });
+        }
+        player.stop();
+        delay.delay_ms(1000);
+    }
+}
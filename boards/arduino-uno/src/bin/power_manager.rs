@@ -0,0 +1,69 @@
+//! Power management example
+//!
+//! This example shows how drivers power their peripherals through the Power
+//! Reduction Register (PRR):
+//! 1. Creating an `Adc` powers the ADC up, dropping it powers it down
+//! 2. `PowerManager::power_down_unused()` switches off every peripheral
+//!    without a live driver
+//! 3. Disabling the digital input buffer of an analog-only pin
+//!
+//! Hardware: Arduino Uno, optional potentiometer on A0
+//! Serial Monitor: 9600 baud
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Delay, Adc, PowerManager, PowerPeripheral};
+use panic_halt as _;
+
+// A0 as a digital pin number
+const A0: u8 = 14;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+
+    serial.println("");
+    serial.println("==================");
+    serial.println("Power Manager Test");
+    serial.println("==================");
+    serial.println("");
+
+    print_state(&mut serial, "At startup");
+
+    // The ADC is powered while a driver exists
+    {
+        let mut adc = Adc::new();
+        print_state(&mut serial, "With an Adc");
+        serial.write_str("  A0 reading: ");
+        serial.println_uint(adc.read_a0() as u32, 10);
+    }
+    print_state(&mut serial, "Adc dropped");
+
+    // Serial and millis() keep USART0 and Timer0; everything else goes off
+    PowerManager::power_down_unused();
+    print_state(&mut serial, "After power_down_unused()");
+
+    // A0 only carries an analog signal, so its digital buffer can go too
+    PowerManager::disable_digital_input(A0);
+    if !PowerManager::digital_input_enabled(A0) {
+        serial.println("A0 digital input buffer disabled");
+    }
+
+    loop {
+        delay.delay_ms(1000);
+    }
+}
+
+// Print the raw PRR value and the ADC/USART0 state
+fn print_state(serial: &mut Serial, label: &str) {
+    serial.write_str(label);
+    serial.write_str(": PRR = 0x");
+    serial.println_uint(PowerManager::state() as u32, 16);
+    serial.write_str("  ADC ");
+    serial.println(if PowerManager::is_powered(PowerPeripheral::Adc) { "on" } else { "off" });
+    serial.write_str("  USART0 users: ");
+    serial.println_uint(PowerManager::users(PowerPeripheral::Usart0) as u32, 10);
+}
@@ -0,0 +1,74 @@
+//! DS3231 alarm wake-up example
+//!
+//! Sleeps in power-down mode and lets the DS3231 wake the board once a
+//! minute via its INT/SQW pin, then prints the time and temperature.
+//!
+//! Hardware setup:
+//! - DS3231 SDA -> A4, SCL -> A5, VCC -> 5V, GND -> GND
+//! - DS3231 INT/SQW -> D2 (INT0)
+//! - Open serial monitor at 9600 baud
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, I2c, DateTime, Rtc, DS3231, Alarm2Mode, Ds3231Alarm, ExternalInterrupt, SleepMode};
+use panic_halt as _;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+
+    serial.println("DS3231 Alarm Test");
+    serial.println("-----------------");
+
+    let mut rtc = DS3231::new(I2c::new());
+    if rtc.begin().is_err() {
+        serial.println("ERROR: RTC not found!");
+        loop {
+            serial.flush();
+        }
+    }
+
+    if rtc.lost_power() == Ok(true) {
+        serial.println("RTC lost power - setting time");
+        let _ = rtc.adjust(&DateTime::new(2025, 1, 5, 12, 0, 0));
+    }
+
+    // Alarm 2 fires at second 00 of every minute
+    let _ = rtc.set_alarm2(&DateTime::new(2025, 1, 1, 0, 0, 0), Alarm2Mode::EveryMinute);
+
+    loop {
+        serial.println("Sleeping until the next minute...");
+        serial.flush();
+
+        match rtc.sleep_until_alarm(ExternalInterrupt::Int0, SleepMode::PowerDown) {
+            Ok(Ds3231Alarm::Alarm2) => serial.write_str("Alarm 2 at "),
+            Ok(Ds3231Alarm::Alarm1) => serial.write_str("Alarm 1 at "),
+            Err(_) => {
+                serial.println("Alarm error");
+                continue;
+            }
+        }
+
+        if let Ok(now) = rtc.now() {
+            print_two_digits(&mut serial, now.hour());
+            serial.write_str(":");
+            print_two_digits(&mut serial, now.minute());
+            serial.write_str(":");
+            print_two_digits(&mut serial, now.second());
+        }
+
+        if let Ok(quarters) = rtc.temperature_quarters() {
+            let sign = if quarters < 0 { "-" } else { "" };
+            let quarters = quarters.unsigned_abs();
+            let _ = ufmt::uwrite!(serial, ", {}{}.{} C", sign, quarters / 4, (quarters % 4) * 25);
+        }
+        serial.println("");
+    }
+}
+
+fn print_two_digits(serial: &mut Serial, n: u8) {
+    serial.write_byte(b'0' + n / 10);
+    serial.write_byte(b'0' + n % 10);
+}
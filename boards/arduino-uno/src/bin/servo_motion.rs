@@ -0,0 +1,56 @@
+//! Servo motion profile example
+//!
+//! Moves two servos on D9 and D10 with background motion profiles while the
+//! main loop keeps blinking the LED. With only D9 and D10 attached, Timer1
+//! generates the pulses in hardware, so the servos hold still without jitter.
+//!
+//! Hardware setup:
+//! - Connect servo 1 signal wire to pin 9
+//! - Connect servo 2 signal wire to pin 10
+//! - Connect all servo power wires to 5V (or an external supply)
+//! - Connect all servo ground wires to GND
+//! - Open serial monitor at 9600 baud
+
+#![no_std]
+#![no_main]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::*;
+use panic_halt as _;
+
+#[avr_device::entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+    let mut led = peripherals.pins.d13.into_output();
+
+    serial.write_str("\r\n=== Servo Motion Profiles ===\r\n");
+
+    let mut pan = Servo::new();
+    let mut tilt = Servo::new();
+    pan.attach(9);
+    tilt.attach(10);
+
+    if pan.is_hardware_pwm() {
+        serial.write_str("Using Timer1 hardware PWM\r\n");
+    }
+
+    let mut forward = true;
+    loop {
+        let (pan_target, tilt_target) = if forward { (180, 45) } else { (0, 135) };
+
+        // Constant 90 deg/s for pan, ease in and out for tilt
+        pan.move_to(pan_target, 90);
+        tilt.move_to_with_acceleration(tilt_target, 120, 240);
+
+        while pan.is_moving() || tilt.is_moving() {
+            led.toggle();
+            delay.delay_ms(100);
+        }
+
+        let _ = ufmt::uwriteln!(serial, "pan {} tilt {}\r", pan.read(), tilt.read());
+        forward = !forward;
+        delay.delay_ms(500);
+    }
+}
@@ -0,0 +1,66 @@
+//! Shared I2C bus example
+//!
+//! One I2C bus drives both an LCD backpack and a DS3231 RTC. Each driver
+//! gets its own proxy from `SharedI2c::device()`.
+//!
+//! Hardware setup:
+//! - LCD I2C backpack and DS3231 on A4 (SDA) and A5 (SCL)
+//! - Update LCD_ADDRESS with your backpack's address from i2c_scanner
+//!
+//! The display will show:
+//! Line 1: The date from the RTC
+//! Line 2: The time from the RTC
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, I2c, Lcd, Rtc, SharedI2c, DS3231, Delay, build_time};
+use panic_halt as _;
+
+const LCD_ADDRESS: u8 = 0x27;  // Common addresses: 0x27 or 0x3F
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut delay = Delay::new();
+
+    let bus = SharedI2c::new(I2c::new());
+    let mut lcd = Lcd::new(bus.device(), LCD_ADDRESS);
+    let mut rtc = DS3231::new(bus.device());
+
+    let _ = lcd.init();
+    let _ = lcd.backlight_on();
+
+    if rtc.begin().is_err() {
+        let _ = lcd.write_str("RTC not found");
+        loop {
+            delay.delay_ms(1000);
+        }
+    }
+
+    if rtc.lost_power() == Ok(true) {
+        let _ = rtc.adjust(&build_time());
+    }
+
+    loop {
+        if let Ok(now) = rtc.now() {
+            let _ = lcd.set_cursor(0, 0);
+            let _ = ufmt::uwrite!(lcd, "{}-{}-{}", now.year(), TwoDigits(now.month()), TwoDigits(now.day()));
+            let _ = lcd.set_cursor(1, 0);
+            let _ = ufmt::uwrite!(lcd, "{}:{}:{}", TwoDigits(now.hour()), TwoDigits(now.minute()), TwoDigits(now.second()));
+        }
+        delay.delay_ms(1000);
+    }
+}
+
+/// Zero-padded two-digit number
+struct TwoDigits(u8);
+
+impl ufmt::uDisplay for TwoDigits {
+    fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+        if self.0 < 10 {
+            f.write_str("0")?;
+        }
+        ufmt::uwrite!(f, "{}", self.0)
+    }
+}
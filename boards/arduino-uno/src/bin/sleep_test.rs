@@ -89,12 +89,23 @@ pub extern "C" fn main() -> ! {
         // Clear button flag
         BUTTON_PRESSED.store(false, Ordering::SeqCst);
 
+        // INT0 edges are detected with the I/O clock, which power-down stops;
+        // only a LOW level wakes from it, so switch for the sleep
+        if sleep_mode == SleepMode::PowerDown {
+            attach_interrupt(ExternalInterrupt::Int0, InterruptMode::Low, button_handler);
+        }
+
         // Enter sleep mode
         Sleep::sleep_mode(sleep_mode);
 
         // *** CPU SLEEPS HERE ***
         // Execution continues after wake-up interrupt
 
+        // Back to edges, as a LOW level keeps firing while the button is held
+        if sleep_mode == SleepMode::PowerDown {
+            attach_interrupt(ExternalInterrupt::Int0, InterruptMode::Falling, button_handler);
+        }
+
         // Check if we woke from button press
         if BUTTON_PRESSED.load(Ordering::SeqCst) {
             serial.println("");
@@ -0,0 +1,100 @@
+//! Timed sleep test - power-down for a fixed time using the watchdog
+//!
+//! This example sleeps in power-down mode for 5 seconds at a time:
+//! 1. The watchdog wakes the CPU in steps of up to 8 seconds
+//! 2. `millis()` is advanced by the time slept, since Timer0 stops
+//! 3. A button on D2 ends the sleep early
+//!
+//! The LED blinks to show the system is awake, then it sleeps. Compare the
+//! reported sleep time with `millis()` to see the compensation.
+//!
+//! Hardware:
+//! - Arduino Uno with LED on D13
+//! - Optional button connected to D2 (with pull-up resistor)
+//!
+//! Serial Monitor: 9600 baud
+
+#![no_std]
+#![no_main]
+#![feature(asm_experimental_arch)]
+#![feature(abi_avr_interrupt)]
+
+use arduino_uno::{Peripherals, Serial, Sleep, Duration, millis, attach_interrupt, detach_interrupt, ExternalInterrupt, InterruptMode};
+use panic_halt as _;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+
+    // Configure D13 (LED) as output
+    let mut led = peripherals.pins.d13.into_output();
+
+    // Initialize serial at 9600 baud
+    let mut serial = Serial::new(9600);
+
+    serial.println("");
+    serial.println("==================");
+    serial.println("Timed Sleep Test");
+    serial.println("==================");
+    serial.println("");
+
+    fn button_handler() {}
+
+    loop {
+        // Blink LED to show we're awake
+        for _ in 0..3 {
+            led.set_high();
+            delay_ms(200);
+            led.set_low();
+            delay_ms(200);
+        }
+
+        serial.println("Entering POWER_DOWN for 5 seconds...");
+        serial.flush();
+
+        // Only a LOW level on INT0 wakes from power-down; it keeps firing
+        // while the button is held, so it is only attached for the sleep
+        attach_interrupt(ExternalInterrupt::Int0, InterruptMode::Low, button_handler);
+        let before = millis();
+        let slept = Sleep::power_down_for(Duration::secs(5));
+        detach_interrupt(ExternalInterrupt::Int0);
+
+        serial.write_str("Slept ");
+        print_number(&mut serial, slept.to_millis() as u32);
+        serial.write_str(" ms, millis() advanced ");
+        print_number(&mut serial, millis().wrapping_sub(before));
+        serial.println(" ms");
+        serial.println("");
+    }
+}
+
+// Helper function to delay in milliseconds
+fn delay_ms(ms: u32) {
+    let start = millis();
+    while millis().wrapping_sub(start) < ms {
+        unsafe { core::arch::asm!("nop"); }
+    }
+}
+
+// Helper function to print numbers
+fn print_number(serial: &mut arduino_uno::Serial, mut n: u32) {
+    if n == 0 {
+        serial.write_str("0");
+        return;
+    }
+
+    let mut buf = [0u8; 10];
+    let mut i = 0;
+
+    while n > 0 {
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        i += 1;
+    }
+
+    // Print in reverse order
+    while i > 0 {
+        i -= 1;
+        serial.write_byte(buf[i]);
+    }
+}
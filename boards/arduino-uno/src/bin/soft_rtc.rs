@@ -0,0 +1,50 @@
+//! Software RTC example
+//!
+//! Keeps the date and time without an RTC chip using `SoftRtc` in millis
+//! mode, starting from the build time. `print_time` is generic over `Rtc`,
+//! so a DS1307 or DS3231 can be swapped in without other changes.
+//!
+//! Hardware setup:
+//! - Arduino Uno only
+//! - Open serial monitor at 9600 baud
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Rtc, SoftRtc, SoftRtcSource, Delay, build_time};
+use panic_halt as _;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+
+    serial.println("Software RTC Test");
+    serial.println("-----------------");
+
+    let mut rtc = SoftRtc::new(SoftRtcSource::Millis);
+    let _ = rtc.begin();
+
+    // Correct for a resonator measured to run 50 ppm slow
+    rtc.set_trim_ppm(50);
+
+    if rtc.is_running() == Ok(false) {
+        serial.println("Setting the clock to the build time");
+        let _ = rtc.adjust(&build_time());
+    }
+
+    loop {
+        print_time(&mut serial, &mut rtc);
+        delay.delay_ms(1000);
+    }
+}
+
+fn print_time<R: Rtc>(serial: &mut Serial, rtc: &mut R) {
+    match rtc.now() {
+        Ok(now) => {
+            let _ = ufmt::uwriteln!(serial, "{}Z", now);
+        }
+        Err(_) => serial.println("Error reading RTC"),
+    }
+}
@@ -0,0 +1,62 @@
+//! Stepper motor example
+//!
+//! Moves a stepper on an A4988/DRV8825 driver back and forth with
+//! acceleration, then spins a 28BYJ-48 on a ULN2003 board at constant speed
+//! and brings it to a smooth stop. Both run in the background while the
+//! main loop reports positions.
+//!
+//! Hardware setup:
+//! - Driver STEP -> D3, DIR -> D4, ENABLE -> D8 (MS pins for microstepping)
+//! - ULN2003 IN1-IN4 -> D9, D10, D11, D12
+//! - Motor supply on the driver boards, common GND with the Uno
+//! - Open serial monitor at 9600 baud
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Delay, Stepper, StepSequence, Winding};
+use panic_halt as _;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let _peripherals = Peripherals::take().unwrap();
+    let mut serial = Serial::new(9600);
+    let mut delay = Delay::new();
+
+    serial.println("Stepper Demo");
+    serial.println("------------");
+
+    let mut axis = Stepper::step_dir(3, 4).with_enable_pin(8);
+    axis.set_max_speed(1600);
+    axis.set_acceleration(3200);
+
+    // 28BYJ-48: 4096 half steps per output shaft turn
+    let mut dial = Stepper::four_wire([9, 10, 11, 12], Winding::Unipolar, StepSequence::HalfStep);
+    dial.set_max_speed(800);
+    dial.set_acceleration(400);
+    dial.run_speed(600).unwrap();
+
+    let mut target = 3200;
+    let mut seconds = 0u8;
+    loop {
+        if !axis.is_running() {
+            axis.move_to(target).unwrap();
+            target = -target;
+        }
+
+        seconds += 1;
+        if seconds == 10 {
+            serial.println("Stopping the dial");
+            dial.stop();
+        }
+
+        let _ = ufmt::uwriteln!(
+            serial,
+            "axis {} ({} steps/s), dial {}\r",
+            axis.position(),
+            axis.speed(),
+            dial.position()
+        );
+        delay.delay_ms(1000);
+    }
+}
@@ -0,0 +1,109 @@
+//! Watchdog crash breadcrumb example
+//!
+//! This example shows how to find out why and where the system reset:
+//! 1. Decoding the cause of the last reset and any crash breadcrumb
+//! 2. Enabling the watchdog in interrupt-then-reset mode with a 2-second timeout
+//! 3. Marking the running task with `Watchdog::set_task`
+//! 4. Hanging on purpose after 10 seconds
+//!
+//! The first timeout records a breadcrumb with the task ID, PC and uptime,
+//! the second one resets the system about 4 seconds after the hang. The
+//! breadcrumb is printed after the reset, then the cycle repeats.
+//!
+//! Hardware: Arduino Uno with built-in LED on D13
+//! Serial Monitor: 9600 baud
+
+#![no_std]
+#![no_main]
+
+use arduino_uno::{Peripherals, Serial, Watchdog, WatchdogTimeout, millis};
+use panic_halt as _;
+
+// Task IDs recorded in the breadcrumb
+const TASK_BLINK: u8 = 1;
+const TASK_IDLE: u8 = 2;
+const TASK_STUCK: u8 = 3;
+
+// Uptime after which the main loop hangs
+const HANG_AFTER_MS: u32 = 10_000;
+
+#[no_mangle]
+pub extern "C" fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+
+    // Configure D13 (LED) as output
+    let mut led = peripherals.pins.d13.into_output();
+
+    // Initialize serial at 9600 baud
+    let mut serial = Serial::new(9600);
+
+    serial.println("");
+    serial.println("==========================");
+    serial.println("Watchdog Breadcrumb Test");
+    serial.println("==========================");
+    serial.println("");
+
+    // Decode the cause of the last reset
+    let cause = Watchdog::reset_cause();
+    serial.write_str("Reset flags (MCUSR): 0x");
+    serial.println_uint(cause.flags() as u32, 16);
+    if cause.power_on() {
+        serial.println("  power-on");
+    }
+    if cause.external() {
+        serial.println("  external (RESET pin)");
+    }
+    if cause.brown_out() {
+        serial.println("  brown-out");
+    }
+    if cause.watchdog() {
+        serial.println("*** WATCHDOG RESET DETECTED ***");
+    }
+    if let Some(crumb) = cause.breadcrumb() {
+        serial.println("Crash breadcrumb:");
+        serial.write_str("  task: ");
+        serial.println_uint(crumb.task_id as u32, 10);
+        serial.write_str("  pc: 0x");
+        serial.println_uint(crumb.pc as u32, 16);
+        serial.write_str("  millis: ");
+        serial.println_uint(crumb.millis, 10);
+    }
+    serial.println("");
+
+    // The first timeout records a breadcrumb, the second one resets the system
+    serial.println("Enabling watchdog in interrupt-then-reset mode (2 s)...");
+    Watchdog::enable_interrupt_and_reset(WatchdogTimeout::S2);
+    serial.println("Hanging in task 3 after 10 seconds");
+    serial.println("");
+
+    let mut last_blink = 0u32;
+    let mut blink_state = false;
+
+    loop {
+        Watchdog::set_task(TASK_BLINK);
+        let current_millis = millis();
+
+        // Blink LED every 500ms
+        if current_millis.wrapping_sub(last_blink) >= 500 {
+            last_blink = current_millis;
+            blink_state = !blink_state;
+
+            if blink_state {
+                led.set_high();
+            } else {
+                led.set_low();
+            }
+        }
+
+        if current_millis >= HANG_AFTER_MS {
+            serial.println("Hanging now...");
+            Watchdog::set_task(TASK_STUCK);
+            loop {
+                core::hint::spin_loop();
+            }
+        }
+
+        Watchdog::set_task(TASK_IDLE);
+        Watchdog::reset();
+    }
+}
@@ -0,0 +1,152 @@
+//! System clock configuration
+//!
+//! Every module that derives timing from the CPU clock (millis/micros, Serial
+//! baud rates, I2C bit rate, tone, servo, delays) reads it from here instead of
+//! hardcoding 16 MHz.
+//!
+//! The oscillator frequency is fixed at build time:
+//! - Default: 16 MHz (Arduino Uno crystal)
+//! - `clock-8mhz` feature: 8 MHz (3.3V boards, internal RC oscillator)
+//!
+//! The effective CPU frequency can additionally be divided at runtime through
+//! the CLKPR system clock prescaler (see `set_clock_prescaler()`).
+
+use core::ptr::{read_volatile, write_volatile};
+
+// Clock Prescale Register
+const CLKPR: *mut u8 = 0x61 as *mut u8;
+
+// CLKPR bits
+const CLKPCE: u8 = 7;  // Clock Prescaler Change Enable
+const CLKPS_MASK: u8 = 0x0F;  // CLKPS3:0
+
+// Status register (for saving/restoring the global interrupt flag)
+const SREG: *mut u8 = 0x5F as *mut u8;
+
+/// Oscillator frequency in Hz, selected at build time
+#[cfg(feature = "clock-8mhz")]
+pub const F_CPU: u32 = 8_000_000;
+
+/// Oscillator frequency in Hz, selected at build time
+#[cfg(not(feature = "clock-8mhz"))]
+pub const F_CPU: u32 = 16_000_000;
+
+/// Oscillator frequency in whole MHz
+const F_CPU_MHZ: u32 = F_CPU / 1_000_000;
+
+/// System clock prescaler (CLKPR division factor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ClockPrescaler {
+    /// No division (full oscillator speed)
+    Div1 = 0,
+    /// Divide by 2
+    Div2 = 1,
+    /// Divide by 4
+    Div4 = 2,
+    /// Divide by 8 (CKDIV8 fuse default)
+    Div8 = 3,
+    /// Divide by 16
+    Div16 = 4,
+    /// Divide by 32
+    Div32 = 5,
+    /// Divide by 64
+    Div64 = 6,
+    /// Divide by 128
+    Div128 = 7,
+    /// Divide by 256
+    Div256 = 8,
+}
+
+impl ClockPrescaler {
+    /// Get the division factor (1-256)
+    pub const fn divisor(&self) -> u16 {
+        1 << (*self as u8)
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        match bits & CLKPS_MASK {
+            0 => ClockPrescaler::Div1,
+            1 => ClockPrescaler::Div2,
+            2 => ClockPrescaler::Div4,
+            3 => ClockPrescaler::Div8,
+            4 => ClockPrescaler::Div16,
+            5 => ClockPrescaler::Div32,
+            6 => ClockPrescaler::Div64,
+            7 => ClockPrescaler::Div128,
+            // 9-15 are reserved, treat as the largest valid setting
+            _ => ClockPrescaler::Div256,
+        }
+    }
+}
+
+/// Get the currently active system clock prescaler
+///
+/// This reads CLKPR directly, so it also reflects the CKDIV8 fuse setting at boot.
+pub fn clock_prescaler() -> ClockPrescaler {
+    unsafe { ClockPrescaler::from_bits(read_volatile(CLKPR)) }
+}
+
+/// Get the current CPU frequency in Hz (oscillator / prescaler)
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::cpu_frequency;
+///
+/// let hz = cpu_frequency();  // 16_000_000 on a stock Uno
+/// ```
+#[inline]
+pub fn cpu_frequency() -> u32 {
+    F_CPU >> (clock_prescaler() as u8)
+}
+
+/// Convert microseconds to CPU cycles at the current clock
+///
+/// Uses only a multiply and a shift so it is cheap enough for ISRs.
+#[inline]
+pub fn micros_to_cycles(us: u32) -> u32 {
+    us.wrapping_mul(F_CPU_MHZ) >> (clock_prescaler() as u8)
+}
+
+/// Change the system clock prescaler at runtime
+///
+/// Dividing the clock reduces power consumption. After the change, the
+/// millis()/micros() timebase, the hardware Serial baud rate and the I2C bit
+/// rate are recalculated so they stay correct at the new frequency.
+///
+/// Call `Serial::flush()` first if a transmission may still be in progress,
+/// otherwise the byte being shifted out will be corrupted.
+///
+/// Tones, servos and SoftwareSerial compute their timing when they are started,
+/// so restart them after changing the prescaler.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{set_clock_prescaler, ClockPrescaler};
+///
+/// // Run at 4 MHz while idling
+/// set_clock_prescaler(ClockPrescaler::Div4);
+/// ```
+pub fn set_clock_prescaler(prescaler: ClockPrescaler) {
+    unsafe {
+        let sreg = read_volatile(SREG);
+        core::arch::asm!("cli", options(nomem, nostack));
+
+        // Timed sequence: CLKPS must be written within 4 cycles of setting CLKPCE,
+        // so both stores are done in assembly to prevent reordering
+        core::arch::asm!(
+            "sts 0x61, {enable}",
+            "sts 0x61, {bits}",
+            enable = in(reg) 1u8 << CLKPCE,
+            bits = in(reg) prescaler as u8,
+            options(nostack)
+        );
+
+        crate::time::recalibrate();
+        crate::serial::recalibrate();
+        crate::i2c::recalibrate();
+        crate::servo::recalibrate();
+
+        write_volatile(SREG, sreg);
+    }
+}
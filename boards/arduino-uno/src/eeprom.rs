@@ -7,11 +7,26 @@
 //! - EEPROM writes are slow (approximately 3.4ms per byte)
 //! - Avoid frequent writes to the same address to prevent wear
 //! - Read operations are fast and do not cause wear
-//! - Global interrupts are temporarily disabled during write operations
+//! - Global interrupts are briefly disabled while a byte write is started
+//!
+//! `write_block_async`/`update_block_async` stage up to `ASYNC_BUFFER_SIZE`
+//! bytes and return immediately; the EE_READY interrupt then programs one
+//! byte at a time in the background. Synchronous reads and writes first wait
+//! for staged writes to finish, so they always see consistent data.
+//!
+//! Plain-old-data values can be stored with `Eeprom::get`/`Eeprom::put`, or
+//! through an `EepromCell`, which adds a header that detects stale layouts.
+//!
+//! `Eeprom` implements `ossidata_core::storage::Storage`, so it can back a
+//! wear-leveled `KvStore` for settings and counters that change often.
 //!
 //! Based on information from arduino/ArduinoCore-avr via deepwiki.
 
+use core::marker::PhantomData;
+use core::mem::{size_of, MaybeUninit};
 use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::{IrqCell, IrqFlag};
+use ossidata_core::storage::{crc16, OutOfRange, Storage};
 
 // EEPROM registers (ATmega328P)
 const EECR: *mut u8 = 0x3F as *mut u8;   // EEPROM Control Register
@@ -23,10 +38,170 @@ const EEARH: *mut u8 = 0x42 as *mut u8;  // EEPROM Address Register High
 const EERE: u8 = 0;   // EEPROM Read Enable
 const EEPE: u8 = 1;   // EEPROM Program/Write Enable
 const EEMPE: u8 = 2;  // EEPROM Master Program/Write Enable
+const EERIE: u8 = 3;  // EEPROM Ready Interrupt Enable
 
 /// EEPROM size in bytes for ATmega328P
 pub const EEPROM_SIZE: u16 = 1024;
 
+/// Number of bytes that can be staged for background writing
+pub const ASYNC_BUFFER_SIZE: usize = 64;
+
+// Maximum number of separate address ranges staged at once
+const ASYNC_SEGMENTS: usize = 4;
+
+/// A contiguous range of staged bytes
+#[derive(Clone, Copy)]
+struct Segment {
+    address: u16,
+    remaining: u8,
+    update: bool,
+}
+
+/// Bytes waiting to be programmed by the EE_READY interrupt
+struct AsyncWrites {
+    data: [u8; ASYNC_BUFFER_SIZE],
+    data_head: u8,
+    data_len: u8,
+    segments: [Segment; ASYNC_SEGMENTS],
+    segment_head: u8,
+    segment_count: u8,
+}
+
+impl AsyncWrites {
+    const fn new() -> Self {
+        Self {
+            data: [0; ASYNC_BUFFER_SIZE],
+            data_head: 0,
+            data_len: 0,
+            segments: [Segment { address: 0, remaining: 0, update: false }; ASYNC_SEGMENTS],
+            segment_head: 0,
+            segment_count: 0,
+        }
+    }
+
+    fn push(&mut self, address: u16, data: &[u8], update: bool) -> bool {
+        if data.len() > ASYNC_BUFFER_SIZE - self.data_len as usize {
+            return false;
+        }
+
+        // Extend the last range if this one continues it
+        let last = (self.segment_head as usize + self.segment_count as usize + ASYNC_SEGMENTS - 1) % ASYNC_SEGMENTS;
+        let segment = &mut self.segments[last];
+        if self.segment_count > 0
+            && segment.update == update
+            && segment.address + segment.remaining as u16 == address
+        {
+            segment.remaining += data.len() as u8;
+        } else if (self.segment_count as usize) < ASYNC_SEGMENTS {
+            let next = (last + 1) % ASYNC_SEGMENTS;
+            self.segments[next] = Segment { address, remaining: data.len() as u8, update };
+            self.segment_count += 1;
+        } else {
+            return false;
+        }
+
+        for &byte in data {
+            let tail = (self.data_head as usize + self.data_len as usize) % ASYNC_BUFFER_SIZE;
+            self.data[tail] = byte;
+            self.data_len += 1;
+        }
+        true
+    }
+
+    /// Take the next byte to program as (address, value, update)
+    fn pop(&mut self) -> Option<(u16, u8, bool)> {
+        if self.data_len == 0 {
+            return None;
+        }
+
+        let value = self.data[self.data_head as usize];
+        self.data_head = ((self.data_head as usize + 1) % ASYNC_BUFFER_SIZE) as u8;
+        self.data_len -= 1;
+
+        let segment = &mut self.segments[self.segment_head as usize];
+        let next = (segment.address, value, segment.update);
+        segment.address += 1;
+        segment.remaining -= 1;
+        if segment.remaining == 0 {
+            self.segment_head = ((self.segment_head as usize + 1) % ASYNC_SEGMENTS) as u8;
+            self.segment_count -= 1;
+        }
+
+        Some(next)
+    }
+}
+
+static ASYNC_WRITES: IrqCell<AsyncWrites> = IrqCell::new(AsyncWrites::new());
+
+// Set by the ISR when the last staged byte has been programmed
+static ASYNC_COMPLETE: IrqFlag = IrqFlag::new();
+
+fn write_in_progress() -> bool {
+    unsafe { (read_volatile(EECR) & (1 << EEPE)) != 0 }
+}
+
+/// Read a byte without waiting (EEPE must be clear)
+fn read_byte_raw(address: u16) -> u8 {
+    unsafe {
+        // Set up address register (16-bit address)
+        write_volatile(EEARH, (address >> 8) as u8);
+        write_volatile(EEARL, address as u8);
+
+        // Start EEPROM read by setting EERE bit
+        write_volatile(EECR, read_volatile(EECR) | (1 << EERE));
+
+        // Return data from Data Register
+        read_volatile(EEDR)
+    }
+}
+
+/// Start programming a byte (EEPE must be clear, interrupts must be disabled)
+fn start_write_raw(address: u16, value: u8) {
+    unsafe {
+        // Set up address and data registers
+        write_volatile(EEARH, (address >> 8) as u8);
+        write_volatile(EEARL, address as u8);
+        write_volatile(EEDR, value);
+
+        // Write logical one to EEMPE (Master Program Enable)
+        write_volatile(EECR, read_volatile(EECR) | (1 << EEMPE));
+
+        // Start EEPROM write by setting EEPE (within 4 clock cycles after EEMPE)
+        write_volatile(EECR, read_volatile(EECR) | (1 << EEPE));
+    }
+}
+
+/// Program the next staged byte that needs it
+///
+/// Shared by the EE_READY ISR and `wait_ready()`. Returns true while staged
+/// bytes remain.
+fn service_async_writes() -> bool {
+    ASYNC_WRITES.lock(|writes| {
+        // The other caller may have just started a byte
+        if write_in_progress() {
+            return writes.data_len > 0;
+        }
+
+        while let Some((address, value, update)) = writes.pop() {
+            if update && read_byte_raw(address) == value {
+                continue;
+            }
+            start_write_raw(address, value);
+            return true;
+        }
+
+        // Nothing left: stop the ready interrupt from firing continuously
+        unsafe {
+            let eecr = read_volatile(EECR);
+            if eecr & (1 << EERIE) != 0 {
+                write_volatile(EECR, eecr & !(1 << EERIE));
+                ASYNC_COMPLETE.set();
+            }
+        }
+        false
+    })
+}
+
 /// EEPROM interface
 pub struct Eeprom;
 
@@ -56,20 +231,10 @@ impl Eeprom {
             return None;
         }
 
-        unsafe {
-            // Wait for completion of previous write (EEPE bit must be 0)
-            while (read_volatile(EECR) & (1 << EEPE)) != 0 {}
-
-            // Set up address register (16-bit address)
-            write_volatile(EEARH, (address >> 8) as u8);
-            write_volatile(EEARL, address as u8);
-
-            // Start EEPROM read by setting EERE bit
-            write_volatile(EECR, read_volatile(EECR) | (1 << EERE));
+        // Wait for completion of previous and staged writes
+        self.wait_ready();
 
-            // Return data from Data Register
-            Some(read_volatile(EEDR))
-        }
+        Some(critical_section::with(|_| read_byte_raw(address)))
     }
 
     /// Write a single byte to EEPROM
@@ -83,8 +248,9 @@ impl Eeprom {
     ///
     /// # Important
     /// - This operation takes approximately 3.4ms
-    /// - Interrupts are temporarily disabled during the write
+    /// - Interrupts are temporarily disabled while the write is started
     /// - Avoid excessive writes to prevent EEPROM wear
+    /// - Waits for staged background writes to finish first
     ///
     /// # Example
     /// ```no_run
@@ -96,27 +262,11 @@ impl Eeprom {
             return false;
         }
 
-        unsafe {
-            // Wait for completion of previous write (EEPE bit must be 0)
-            while (read_volatile(EECR) & (1 << EEPE)) != 0 {}
-
-            // Set up address and data registers
-            write_volatile(EEARH, (address >> 8) as u8);
-            write_volatile(EEARL, address as u8);
-            write_volatile(EEDR, value);
-
-            // Disable interrupts
-            core::arch::asm!("cli");
+        // Wait for completion of previous and staged writes
+        self.wait_ready();
 
-            // Write logical one to EEMPE (Master Program Enable)
-            write_volatile(EECR, read_volatile(EECR) | (1 << EEMPE));
-
-            // Start EEPROM write by setting EEPE (within 4 clock cycles after EEMPE)
-            write_volatile(EECR, read_volatile(EECR) | (1 << EEPE));
-
-            // Re-enable interrupts
-            core::arch::asm!("sei");
-        }
+        // The EEMPE/EEPE sequence is timed, so no interrupt may run in between
+        critical_section::with(|_| start_write_raw(address, value));
 
         true
     }
@@ -231,6 +381,55 @@ impl Eeprom {
         count
     }
 
+    /// Read a value of type `T` starting at `address`
+    ///
+    /// Equivalent to Arduino's `EEPROM.get()`.
+    ///
+    /// # Returns
+    /// The value, or None if it would extend past the end of EEPROM
+    ///
+    /// # Example
+    /// ```no_run
+    /// let eeprom = Eeprom::new();
+    /// let threshold: u16 = eeprom.get(10).unwrap_or(512);
+    /// ```
+    pub fn get<T: EepromData>(&self, address: u16) -> Option<T> {
+        if address as usize + size_of::<T>() > EEPROM_SIZE as usize {
+            return None;
+        }
+
+        let mut value = MaybeUninit::<T>::uninit();
+        // T has no padding and every bit pattern is valid (EepromData contract)
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, size_of::<T>())
+        };
+        self.read_block(address, bytes);
+
+        Some(unsafe { value.assume_init() })
+    }
+
+    /// Write a value of type `T` starting at `address`
+    ///
+    /// Equivalent to Arduino's `EEPROM.put()`. Only bytes that differ are
+    /// written, so storing an unchanged value causes no wear.
+    ///
+    /// # Returns
+    /// true if successful, false if the value would extend past the end of EEPROM
+    ///
+    /// # Example
+    /// ```no_run
+    /// let eeprom = Eeprom::new();
+    /// eeprom.put(10, &600u16);
+    /// ```
+    pub fn put<T: EepromData>(&self, address: u16, value: &T) -> bool {
+        if address as usize + size_of::<T>() > EEPROM_SIZE as usize {
+            return false;
+        }
+
+        self.update_block(address, as_bytes(value));
+        true
+    }
+
     /// Clear all EEPROM memory (set to 0xFF)
     ///
     /// # Warning
@@ -242,20 +441,101 @@ impl Eeprom {
         }
     }
 
+    /// Stage a block of bytes to be written in the background
+    ///
+    /// Returns immediately; the EE_READY interrupt programs one byte roughly
+    /// every 3.4ms. Interrupts must be enabled for the writes to progress (or
+    /// call `wait_ready()` to finish them synchronously).
+    ///
+    /// # Arguments
+    /// * `address` - Starting EEPROM address
+    /// * `data` - Data to write (copied into the staging buffer)
+    ///
+    /// # Returns
+    /// true if staged, false if the range is out of bounds or the staging
+    /// buffer lacks room (at most `ASYNC_BUFFER_SIZE` bytes in up to four
+    /// separate ranges can be pending)
+    ///
+    /// # Example
+    /// ```no_run
+    /// let eeprom = Eeprom::new();
+    /// eeprom.write_block_async(0, b"log entry");
+    ///
+    /// // Keep servicing Serial etc. while the bytes are written
+    /// while !eeprom.is_ready() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn write_block_async(&self, address: u16, data: &[u8]) -> bool {
+        self.stage(address, data, false)
+    }
+
+    /// Like `write_block_async`, but bytes that already hold the right value
+    /// are skipped instead of rewritten
+    ///
+    /// The comparison happens when each byte's turn comes, so it reflects any
+    /// earlier staged writes.
+    pub fn update_block_async(&self, address: u16, data: &[u8]) -> bool {
+        self.stage(address, data, true)
+    }
+
+    fn stage(&self, address: u16, data: &[u8], update: bool) -> bool {
+        if address as usize + data.len() > EEPROM_SIZE as usize {
+            return false;
+        }
+        if data.is_empty() {
+            return true;
+        }
+
+        ASYNC_WRITES.lock(|writes| {
+            if !writes.push(address, data, update) {
+                return false;
+            }
+
+            ASYNC_COMPLETE.clear();
+
+            // The ready interrupt fires as soon as EEPE is clear and picks up
+            // the first byte
+            unsafe {
+                write_volatile(EECR, read_volatile(EECR) | (1 << EERIE));
+            }
+            true
+        })
+    }
+
+    /// Number of staged bytes not yet written
+    pub fn pending_writes(&self) -> usize {
+        ASYNC_WRITES.lock(|writes| writes.data_len as usize)
+    }
+
+    /// Check whether a batch of background writes has finished
+    ///
+    /// Returns true once after the last staged byte has been programmed, then
+    /// false until another batch completes.
+    pub fn take_write_complete(&self) -> bool {
+        ASYNC_COMPLETE.take()
+    }
+
     /// Check if EEPROM is ready for read/write operations
     ///
     /// # Returns
-    /// true if ready, false if a write operation is in progress
+    /// true if ready, false if a write operation is in progress or staged
     pub fn is_ready(&self) -> bool {
-        unsafe { (read_volatile(EECR) & (1 << EEPE)) == 0 }
+        !write_in_progress() && self.pending_writes() == 0
     }
 
     /// Wait for EEPROM to be ready
     ///
-    /// Blocks until any pending write operation completes
+    /// Blocks until any pending write operation completes, including staged
+    /// background writes. These are programmed directly if interrupts are
+    /// disabled, so this never deadlocks.
     pub fn wait_ready(&self) {
-        unsafe {
-            while (read_volatile(EECR) & (1 << EEPE)) != 0 {}
+        loop {
+            while write_in_progress() {}
+
+            if !service_async_writes() && !write_in_progress() {
+                break;
+            }
         }
     }
 
@@ -265,8 +545,253 @@ impl Eeprom {
     }
 }
 
+/// Types that can be stored in EEPROM byte-for-byte
+///
+/// Implemented for the integer and floating point types and arrays of them.
+/// Implement it for your own `#[repr(C)]` structs made of such fields to use
+/// them with `Eeprom::get`/`put` and `EepromCell`.
+///
+/// # Safety
+/// The type must have no padding bytes, and every bit pattern must be a valid
+/// value (so no `bool`, `char`, enums or references).
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::EepromData;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Calibration {
+///     offset: i16,
+///     gain: u16,
+/// }
+///
+/// unsafe impl EepromData for Calibration {}
+/// ```
+pub unsafe trait EepromData: Copy {}
+
+macro_rules! impl_eeprom_data {
+    ($($ty:ty),*) => {$(
+        unsafe impl EepromData for $ty {}
+    )*};
+}
+
+impl_eeprom_data!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+unsafe impl<T: EepromData, const N: usize> EepromData for [T; N] {}
+
+/// View a value as its raw bytes
+fn as_bytes<T: EepromData>(value: &T) -> &[u8] {
+    // EepromData types have no padding, so every byte is initialized
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+// EepromCell header: magic, version, crc16 (LE) over the size and data
+const CELL_MAGIC: u8 = 0xE5;
+const CELL_HEADER_LEN: u16 = 4;
+
+/// A typed value stored at a fixed EEPROM address
+///
+/// The value is preceded by a 4-byte header holding a magic byte, a layout
+/// version and a CRC over the size and data. `load()` only returns a value if
+/// all of them match, so the following are all detected:
+/// - Blank EEPROM
+/// - A value written by firmware with a different version or struct size
+/// - A write torn by a power loss
+///
+/// Bump the version whenever the meaning of the stored fields changes.
+///
+/// Cells declared with `eeprom_cells!` are checked at compile time to fit in
+/// EEPROM and not overlap each other.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{eeprom_cells, EepromCell};
+///
+/// eeprom_cells! {
+///     static BOOT_COUNT: EepromCell<u32, 0> = version 1;
+///     static THRESHOLDS: EepromCell<[u16; 4], 8> = version 2;
+/// }
+///
+/// let boots = BOOT_COUNT.load().unwrap_or(0);
+/// BOOT_COUNT.store(&(boots + 1));
+/// ```
+pub struct EepromCell<T: EepromData, const ADDR: u16> {
+    version: u8,
+    _value: PhantomData<T>,
+}
+
+impl<T: EepromData, const ADDR: u16> EepromCell<T, ADDR> {
+    /// First EEPROM address used by the cell (the header)
+    pub const START: u16 = ADDR;
+
+    /// One past the last EEPROM address used by the cell
+    pub const END: u16 = ADDR + CELL_HEADER_LEN + size_of::<T>() as u16;
+
+    const IN_BOUNDS: () = assert!(
+        ADDR as usize + CELL_HEADER_LEN as usize + size_of::<T>() <= EEPROM_SIZE as usize,
+        "EepromCell does not fit in EEPROM"
+    );
+
+    /// Create a cell for the given layout version
+    pub const fn new(version: u8) -> Self {
+        let () = Self::IN_BOUNDS;
+        Self { version, _value: PhantomData }
+    }
+
+    /// Layout version of this cell
+    pub const fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Read the value, or None if the header is missing, stale or corrupt
+    pub fn load(&self) -> Option<T> {
+        let eeprom = Eeprom::new();
+        let mut header = [0u8; CELL_HEADER_LEN as usize];
+        eeprom.read_block(ADDR, &mut header);
+
+        if header[0] != CELL_MAGIC || header[1] != self.version {
+            return None;
+        }
+
+        let value: T = eeprom.get(ADDR + CELL_HEADER_LEN)?;
+        if u16::from_le_bytes([header[2], header[3]]) != Self::checksum(&value) {
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Read the value, falling back to `default` if it is not valid
+    pub fn load_or(&self, default: T) -> T {
+        self.load().unwrap_or(default)
+    }
+
+    /// Write the value and its header
+    ///
+    /// Unchanged bytes are skipped, so storing the same value again causes no wear.
+    pub fn store(&self, value: &T) {
+        let eeprom = Eeprom::new();
+        let crc = Self::checksum(value).to_le_bytes();
+
+        // Data first, header last: a torn write fails the CRC check
+        eeprom.put(ADDR + CELL_HEADER_LEN, value);
+        eeprom.update_block(ADDR + 2, &crc);
+        eeprom.update(ADDR + 1, self.version);
+        eeprom.update(ADDR, CELL_MAGIC);
+    }
+
+    /// Invalidate the stored value
+    pub fn clear(&self) {
+        Eeprom::new().update(ADDR, 0xFF);
+    }
+
+    fn checksum(value: &T) -> u16 {
+        crc16(crc16(0xFFFF, &(size_of::<T>() as u16).to_le_bytes()), as_bytes(value))
+    }
+}
+
+/// Compile-time check that EEPROM regions don't overlap
+///
+/// Each region is `(start, end)` with `end` exclusive. Used by `eeprom_cells!`;
+/// call it directly to also cover regions such as a `KvStore`:
+///
+/// ```no_run
+/// use arduino_uno::{assert_eeprom_layout, EepromCell};
+///
+/// const _: () = assert_eeprom_layout(&[
+///     (EepromCell::<u32, 0>::START, EepromCell::<u32, 0>::END),
+///     (512, 1024),  // KvStore region
+/// ]);
+/// ```
+pub const fn assert_eeprom_layout(regions: &[(u16, u16)]) {
+    let mut i = 0;
+    while i < regions.len() {
+        assert!(regions[i].1 <= EEPROM_SIZE, "EEPROM region extends past the end of EEPROM");
+
+        let mut j = i + 1;
+        while j < regions.len() {
+            let (a, b) = (regions[i], regions[j]);
+            assert!(a.1 <= b.0 || b.1 <= a.0, "EEPROM regions overlap");
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Declare `EepromCell` statics and check at compile time that they don't overlap
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{eeprom_cells, EepromCell};
+///
+/// eeprom_cells! {
+///     static CALIBRATION: EepromCell<[i16; 3], 0> = version 1;
+///     pub static SETTINGS: EepromCell<u32, 16> = version 4;
+/// }
+/// ```
+#[macro_export]
+macro_rules! eeprom_cells {
+    ($($(#[$meta:meta])* $vis:vis static $name:ident: EepromCell<$ty:ty, $addr:tt> = version $version:expr;)*) => {
+        $(
+            $(#[$meta])*
+            $vis static $name: $crate::EepromCell<$ty, { $addr }> = $crate::EepromCell::new($version);
+        )*
+
+        const _: () = $crate::assert_eeprom_layout(&[
+            $((
+                $crate::EepromCell::<$ty, { $addr }>::START,
+                $crate::EepromCell::<$ty, { $addr }>::END,
+            )),*
+        ]);
+    };
+}
+
+/// Storage backend for `KvStore`
+///
+/// Writes use `update_block`, so unchanged bytes cause no wear.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{Eeprom, KvStore};
+///
+/// // Keep the upper half of the EEPROM for the key-value store
+/// let mut settings = KvStore::mount(Eeprom::new(), 512, 512).unwrap();
+/// settings.set(0, &1200u16).unwrap();
+/// ```
+impl Storage for Eeprom {
+    type Error = OutOfRange;
+
+    fn capacity(&self) -> u16 {
+        EEPROM_SIZE
+    }
+
+    fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), OutOfRange> {
+        if self.read_block(address, buffer) == buffer.len() {
+            Ok(())
+        } else {
+            Err(OutOfRange)
+        }
+    }
+
+    fn write(&mut self, address: u16, data: &[u8]) -> Result<(), OutOfRange> {
+        if self.update_block(address, data) == data.len() {
+            Ok(())
+        } else {
+            Err(OutOfRange)
+        }
+    }
+}
+
 impl Default for Eeprom {
     fn default() -> Self {
         Self::new()
     }
 }
+
+/// EEPROM ready interrupt: programs the next staged byte
+#[link_section = ".text"]
+#[export_name = "__vector_22"]
+pub unsafe extern "avr-interrupt" fn __vector_22() {
+    crate::sleep::record_wake(crate::WakeSource::EepromReady);
+    service_async_writes();
+}
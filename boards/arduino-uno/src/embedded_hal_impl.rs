@@ -4,7 +4,11 @@
 //! Arduino Uno hardware, enabling compatibility with the embedded Rust ecosystem.
 
 use embedded_hal::digital;
+use embedded_hal::i2c;
+use embedded_hal::pwm;
+use crate::i2c::{I2c, I2cError};
 use crate::pin::{Pin, mode};
+use crate::pwm::Pwm;
 
 // Digital OutputPin trait implementation
 impl<const N: u8> digital::OutputPin for Pin<N, mode::Output> {
@@ -69,6 +73,28 @@ impl<const N: u8> digital::ErrorType for Pin<N, mode::PullUp> {
     type Error = core::convert::Infallible;
 }
 
+// PWM SetDutyCycle trait implementation for the six hardware PWM pins
+macro_rules! impl_set_duty_cycle {
+    ($($n:literal),*) => {$(
+        impl pwm::ErrorType for Pin<$n, Pwm> {
+            type Error = core::convert::Infallible;
+        }
+
+        impl pwm::SetDutyCycle for Pin<$n, Pwm> {
+            fn max_duty_cycle(&self) -> u16 {
+                255
+            }
+
+            fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+                Pin::<$n, Pwm>::set_duty(self, duty.min(255) as u8);
+                Ok(())
+            }
+        }
+    )*};
+}
+
+impl_set_duty_cycle!(3, 5, 6, 9, 10, 11);
+
 // Delay trait implementations
 use embedded_hal::delay::DelayNs;
 
@@ -100,6 +126,31 @@ impl DelayNs for crate::Delay {
     }
 }
 
+// I2C master implementation
+impl i2c::Error for I2cError {
+    fn kind(&self) -> i2c::ErrorKind {
+        match self {
+            I2cError::Nack => i2c::ErrorKind::NoAcknowledge(i2c::NoAcknowledgeSource::Unknown),
+            I2cError::BusError => i2c::ErrorKind::Bus,
+            I2cError::Timeout | I2cError::Busy => i2c::ErrorKind::Other,
+        }
+    }
+}
+
+impl i2c::ErrorType for I2c {
+    type Error = I2cError;
+}
+
+impl i2c::I2c for I2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        I2c::transaction(self, address, operations)
+    }
+}
+
 // NOTE: Serial traits were removed from embedded-hal 1.0
 // The serial module was part of embedded-hal 0.2.x but removed in 1.0
 // Our Serial type provides Arduino-compatible API directly without embedded-hal traits
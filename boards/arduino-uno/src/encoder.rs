@@ -0,0 +1,165 @@
+//! Interrupt-driven quadrature encoder reader
+//!
+//! [`Encoder`] counts every edge of both encoder channels (4x decoding) in the
+//! background. Pins D2 and D3 use the external interrupts INT0 and INT1; any
+//! other pin uses its pin change interrupt, so an encoder can go on any two
+//! pins. Up to 4 encoders can run at once.
+//!
+//! Every edge costs an interrupt, so a fast motor encoder can take a
+//! noticeable share of the CPU. Pin change interrupts that share a bank with
+//! other handlers are slower than INT0/INT1. Edges that come too fast to be
+//! told apart are counted in [`Encoder::errors`].
+
+use ossidata_core::irq::IrqCell;
+use ossidata_core::quadrature::QuadratureDecoder;
+use crate::constants::INPUT_PULLUP;
+use crate::gpio::pin_mode;
+use crate::interrupt::{attach_interrupt_with, detach_interrupt, ExternalInterrupt, InterruptMode};
+use crate::pcint::{pcint_attach_with, pcint_detach};
+use crate::ports::fast_digital_read;
+
+/// Maximum number of encoders
+const MAX_ENCODERS: usize = 4;
+
+#[derive(Clone, Copy)]
+struct Channel {
+    pin_a: u8,
+    pin_b: u8,
+    decoder: QuadratureDecoder,
+    /// Position at the last `take_delta()`
+    last_position: i32,
+}
+
+static CHANNELS: IrqCell<[Option<Channel>; MAX_ENCODERS]> = IrqCell::new([None; MAX_ENCODERS]);
+
+/// Interrupt handler contexts, one per channel slot
+static SLOTS: [usize; MAX_ENCODERS] = [0, 1, 2, 3];
+
+/// Decode an edge on either pin of an encoder
+fn on_edge(index: &usize) {
+    CHANNELS.lock(|channels| {
+        if let Some(channel) = &mut channels[*index] {
+            let a = fast_digital_read(channel.pin_a);
+            let b = fast_digital_read(channel.pin_b);
+            channel.decoder.update(a, b);
+        }
+    });
+}
+
+/// External interrupt on a pin, if it has one
+fn external_interrupt(pin: u8) -> Option<ExternalInterrupt> {
+    match pin {
+        2 => Some(ExternalInterrupt::Int0),
+        3 => Some(ExternalInterrupt::Int1),
+        _ => None,
+    }
+}
+
+/// Quadrature encoder on two pins
+///
+/// The position counts up when channel A leads channel B. Swap the pins to
+/// count the other way.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::Encoder;
+///
+/// // Motor encoder with A on D2 and B on D3
+/// let mut encoder = Encoder::new(2, 3);
+///
+/// let position = encoder.position();
+/// // Counts since the previous call, e.g. for speed control every 10 ms
+/// let counts = encoder.take_delta();
+/// ```
+pub struct Encoder {
+    index: usize,
+}
+
+impl Encoder {
+    /// Start decoding an encoder with channel A on `pin_a` and B on `pin_b`
+    ///
+    /// Both pins are configured as inputs with pull-ups, which open-collector
+    /// encoders need. The position starts at 0.
+    ///
+    /// # Panics
+    /// Panics if 4 encoders already exist.
+    pub fn new(pin_a: u8, pin_b: u8) -> Self {
+        pin_mode(pin_a, INPUT_PULLUP);
+        pin_mode(pin_b, INPUT_PULLUP);
+
+        let channel = Channel {
+            pin_a,
+            pin_b,
+            decoder: QuadratureDecoder::new(fast_digital_read(pin_a), fast_digital_read(pin_b)),
+            last_position: 0,
+        };
+
+        let index = CHANNELS.lock(|channels| {
+            let index = channels
+                .iter()
+                .position(Option::is_none)
+                .expect("Maximum number of encoders exceeded");
+            channels[index] = Some(channel);
+            index
+        });
+
+        for pin in [pin_a, pin_b] {
+            match external_interrupt(pin) {
+                Some(interrupt) => attach_interrupt_with(interrupt, InterruptMode::Change, &SLOTS[index], on_edge),
+                None => pcint_attach_with(pin, InterruptMode::Change, &SLOTS[index], on_edge),
+            }
+        }
+
+        Self { index }
+    }
+
+    fn with_channel<R>(&self, f: impl FnOnce(&mut Channel) -> R) -> R {
+        CHANNELS.lock(|channels| f(channels[self.index].as_mut().unwrap()))
+    }
+
+    /// Current position in counts (4 per encoder line)
+    pub fn position(&self) -> i32 {
+        self.with_channel(|channel| channel.decoder.position())
+    }
+
+    /// Overwrite the current position, e.g. after homing
+    pub fn set_position(&mut self, position: i32) {
+        self.with_channel(|channel| {
+            channel.decoder.set_position(position);
+            channel.last_position = position;
+        });
+    }
+
+    /// Counts since the previous call (or since creation)
+    ///
+    /// Calling this at a fixed interval gives the speed in counts per interval.
+    pub fn take_delta(&mut self) -> i32 {
+        self.with_channel(|channel| {
+            let position = channel.decoder.position();
+            let delta = position.wrapping_sub(channel.last_position);
+            channel.last_position = position;
+            delta
+        })
+    }
+
+    /// Number of missed edges so far
+    ///
+    /// Non-zero means the encoder turned faster than the interrupts could
+    /// follow, so the position is no longer exact.
+    pub fn errors(&self) -> u16 {
+        self.with_channel(|channel| channel.decoder.errors())
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        let (pin_a, pin_b) = self.with_channel(|channel| (channel.pin_a, channel.pin_b));
+        for pin in [pin_a, pin_b] {
+            match external_interrupt(pin) {
+                Some(interrupt) => detach_interrupt(interrupt),
+                None => pcint_detach(pin),
+            }
+        }
+        CHANNELS.lock(|channels| channels[self.index] = None);
+    }
+}
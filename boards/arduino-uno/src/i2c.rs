@@ -7,6 +7,10 @@
 //! This implementation provides blocking master mode I2C communication.
 
 use core::ptr::{read_volatile, write_volatile};
+use core::cell::Cell;
+use critical_section::Mutex;
+use embedded_hal::i2c::{ErrorKind, Operation};
+use crate::power::{self, PowerPeripheral};
 
 // TWI registers
 const TWBR: *mut u8 = 0xB8 as *mut u8;   // TWI Bit Rate Register
@@ -36,6 +40,9 @@ const TW_MR_DATA_NACK: u8 = 0x58;    // Data received, NACK returned
 
 const TW_STATUS_MASK: u8 = 0xF8;
 
+// SCL frequency of the active bus (0 = not initialized), reapplied on clock changes
+static TWI_FREQUENCY: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
 // Read/Write bits
 const TW_WRITE: u8 = 0;
 const TW_READ: u8 = 1;
@@ -49,6 +56,45 @@ pub enum I2cError {
     Timeout,
     /// Bus error or arbitration lost
     BusError,
+    /// Bus claimed by another driver (see `SharedI2c`)
+    Busy,
+}
+
+impl I2cError {
+    /// Map an error from any embedded-hal I2C bus onto the crate's I2C error
+    pub(crate) fn from_bus<E: embedded_hal::i2c::Error>(error: E) -> Self {
+        match error.kind() {
+            ErrorKind::NoAcknowledge(_) => I2cError::Nack,
+            ErrorKind::Other => I2cError::Timeout,
+            _ => I2cError::BusError,
+        }
+    }
+}
+
+/// Program TWBR for an SCL frequency at the current CPU clock
+fn apply_frequency(freq_hz: u32) {
+    if freq_hz == 0 {
+        return;
+    }
+
+    // SCL = CPU_CLK / (16 + 2 * TWBR * prescaler)
+    // We use prescaler = 1, so:
+    // TWBR = ((CPU_CLK / SCL) - 16) / 2
+    // Clamped so that slow clocks give the fastest achievable SCL
+    let twbr_val = ((crate::clock::cpu_frequency() / freq_hz).saturating_sub(16) / 2).min(255);
+
+    unsafe {
+        write_volatile(TWBR, twbr_val as u8);
+
+        // Set prescaler to 1 (TWPS bits = 0)
+        write_volatile(TWSR, 0);
+    }
+}
+
+/// Reapply the active SCL frequency after a CPU clock change
+pub(crate) fn recalibrate() {
+    let freq_hz = critical_section::with(|cs| TWI_FREQUENCY.borrow(cs).get());
+    apply_frequency(freq_hz);
 }
 
 /// I2C master controller
@@ -60,6 +106,7 @@ impl I2c {
     /// Initialize I2C with 100kHz clock (standard mode)
     ///
     /// For 16MHz CPU: TWBR = ((16000000/100000) - 16) / 2 = 72
+    /// For 8MHz CPU: TWBR = ((8000000/100000) - 16) / 2 = 32
     pub fn new() -> Self {
         Self::with_frequency(100_000)
     }
@@ -70,17 +117,14 @@ impl I2c {
     /// - 100 kHz (standard mode)
     /// - 400 kHz (fast mode)
     pub fn with_frequency(freq_hz: u32) -> Self {
-        unsafe {
-            // Calculate TWBR value for desired frequency
-            // SCL = CPU_CLK / (16 + 2 * TWBR * prescaler)
-            // We use prescaler = 1, so:
-            // TWBR = ((CPU_CLK / SCL) - 16) / 2
-            let twbr_val = ((16_000_000 / freq_hz) - 16) / 2;
-            write_volatile(TWBR, twbr_val as u8);
+        critical_section::with(|cs| {
+            TWI_FREQUENCY.borrow(cs).set(freq_hz);
+        });
 
-            // Set prescaler to 1 (TWPS bits = 0)
-            write_volatile(TWSR, 0);
+        power::acquire(PowerPeripheral::Twi);
+        apply_frequency(freq_hz);
 
+        unsafe {
             // Enable TWI
             write_volatile(TWCR, 1 << TWEN);
         }
@@ -262,6 +306,52 @@ impl I2c {
         self.read(address, buffer)
     }
 
+    /// Run a sequence of reads and writes as one bus transaction
+    ///
+    /// Adjacent operations of the same kind are merged; a repeated START
+    /// separates reads from writes and a STOP ends the transaction, also on
+    /// error. This backs the `embedded_hal::i2c::I2c` implementation.
+    pub(crate) fn transaction(&self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), I2cError> {
+        let result = self.run_operations(address, operations);
+        self.stop();
+        result
+    }
+
+    fn run_operations(&self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), I2cError> {
+        let mut previous_read = None;
+        for i in 0..operations.len() {
+            let is_read = matches!(operations[i], Operation::Read(_));
+            let next_is_read = operations.get(i + 1).map(|op| matches!(op, Operation::Read(_)));
+
+            if previous_read != Some(is_read) {
+                self.start()?;
+                if is_read {
+                    self.write_byte((address << 1) | TW_READ, TW_MR_SLA_ACK)?;
+                } else {
+                    self.write_byte((address << 1) | TW_WRITE, TW_MT_SLA_ACK)?;
+                }
+            }
+
+            match &mut operations[i] {
+                Operation::Write(bytes) => {
+                    for &byte in bytes.iter() {
+                        self.write_byte(byte, TW_MT_DATA_ACK)?;
+                    }
+                }
+                Operation::Read(buffer) => {
+                    // NACK the last byte before a write, repeated START or STOP
+                    let last = buffer.len().wrapping_sub(1);
+                    let ends_read = next_is_read != Some(true);
+                    for (j, byte) in buffer.iter_mut().enumerate() {
+                        *byte = self.read_byte(!(ends_read && j == last))?;
+                    }
+                }
+            }
+            previous_read = Some(is_read);
+        }
+        Ok(())
+    }
+
     /// Scan the I2C bus for devices
     ///
     /// Returns a list of found device addresses (0-127)
@@ -280,4 +370,17 @@ impl I2c {
 
         found
     }
+
+    /// Disable the TWI peripheral
+    ///
+    /// The TWI is powered down once no other `I2c` instance is alive.
+    pub fn end(self) {}
+}
+
+impl Drop for I2c {
+    fn drop(&mut self) {
+        power::release(PowerPeripheral::Twi, || unsafe {
+            write_volatile(TWCR, 0);
+        });
+    }
 }
@@ -9,9 +9,14 @@
 //! - Any change (CHANGE)
 //! - Falling edge (FALLING)
 //! - Rising edge (RISING)
+//!
+//! Handlers can be plain functions (`attach_interrupt`) or closures that carry
+//! their own state. Closures attached inside `interrupt_scope()` may borrow local
+//! variables and are detached automatically, so no `static mut` is needed.
 
 use core::ptr::{read_volatile, write_volatile};
 use core::cell::Cell;
+use core::marker::PhantomData;
 use critical_section::Mutex;
 
 // External Interrupt Control Register A
@@ -60,8 +65,65 @@ pub enum ExternalInterrupt {
 /// Type for interrupt handler functions
 type InterruptHandler = fn();
 
+/// Closure handler callable from an ISR (see `interrupt_scope()`)
+///
+/// `Send` keeps closures from capturing state that the main code could touch
+/// without a critical section (e.g. `&Cell<T>`).
+pub type InterruptClosure<'a> = dyn FnMut() + Send + 'a;
+
+/// Handler stored for an interrupt source (shared with the PCINT module)
+#[derive(Clone, Copy)]
+pub(crate) enum Handler {
+    /// Plain function
+    Fn(InterruptHandler),
+    /// Function with a `'static` context reference: the trampoline for the
+    /// context type, the function and the context
+    Context(unsafe fn(*const (), *const ()), *const (), *const ()),
+    /// Closure attached through an `InterruptScope`
+    Closure(*mut InterruptClosure<'static>),
+}
+
+// The pointers are only dereferenced from the ISR, which cannot nest with
+// itself, and they stay valid until the handler is detached.
+unsafe impl Send for Handler {}
+
+impl Handler {
+    /// Store a function together with its context
+    pub(crate) fn context<T: Sync>(context: &'static T, handler: fn(&T)) -> Self {
+        Handler::Context(call_context::<T>, handler as *const (), context as *const T as *const ())
+    }
+
+    /// Erase the lifetime of a closure so it can be stored in a static
+    ///
+    /// # Safety
+    /// The handler must be detached before `'a` ends.
+    pub(crate) unsafe fn closure<'a>(handler: &'a mut InterruptClosure<'a>) -> Self {
+        let ptr: *mut InterruptClosure<'a> = handler;
+        Handler::Closure(core::mem::transmute::<*mut InterruptClosure<'a>, *mut InterruptClosure<'static>>(ptr))
+    }
+
+    /// Invoke the handler
+    pub(crate) fn call(self) {
+        match self {
+            Handler::Fn(handler) => handler(),
+            Handler::Context(trampoline, handler, context) => unsafe { trampoline(handler, context) },
+            Handler::Closure(handler) => unsafe { (*handler)() },
+        }
+    }
+}
+
 /// Storage for interrupt handlers
-static INTERRUPT_HANDLERS: Mutex<Cell<[Option<InterruptHandler>; 2]>> =
+/// Call a context handler stored by `Handler::context` with its typed context
+///
+/// # Safety
+/// `handler` and `context` must come from `Handler::context::<T>`.
+unsafe fn call_context<T>(handler: *const (), context: *const ()) {
+    // Turn the pointers back into the types they were erased from
+    let handler = core::mem::transmute::<*const (), fn(&T)>(handler);
+    handler(&*(context as *const T));
+}
+
+static INTERRUPT_HANDLERS: Mutex<Cell<[Option<Handler>; 2]>> =
     Mutex::new(Cell::new([None, None]));
 
 /// Attach an interrupt handler
@@ -91,6 +153,44 @@ pub fn attach_interrupt(
     mode: InterruptMode,
     handler: InterruptHandler,
 ) {
+    attach_handler(interrupt, mode, Handler::Fn(handler));
+}
+
+/// Attach a handler that receives a reference to its context
+///
+/// Lets a handler work on shared state passed in by reference instead of
+/// reaching for `static mut` globals. The context must be `Sync` because it is
+/// also accessible from the main code, e.g. a `critical_section::Mutex` or an
+/// atomic. To borrow local variables instead, use `interrupt_scope()`.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{attach_interrupt_with, ExternalInterrupt, InterruptMode};
+/// use core::cell::Cell;
+/// use critical_section::Mutex;
+///
+/// static PRESSES: Mutex<Cell<u16>> = Mutex::new(Cell::new(0));
+///
+/// fn count(presses: &Mutex<Cell<u16>>) {
+///     critical_section::with(|cs| {
+///         let count = presses.borrow(cs);
+///         count.set(count.get() + 1);
+///     });
+/// }
+///
+/// attach_interrupt_with(ExternalInterrupt::Int0, InterruptMode::Falling, &PRESSES, count);
+/// ```
+pub fn attach_interrupt_with<T: Sync>(
+    interrupt: ExternalInterrupt,
+    mode: InterruptMode,
+    context: &'static T,
+    handler: fn(&T),
+) {
+    attach_handler(interrupt, mode, Handler::context(context, handler));
+}
+
+/// Store a handler and enable the interrupt
+fn attach_handler(interrupt: ExternalInterrupt, mode: InterruptMode, handler: Handler) {
     critical_section::with(|cs| {
         // Store the handler
         let mut handlers = INTERRUPT_HANDLERS.borrow(cs).get();
@@ -171,14 +271,173 @@ pub fn restore_interrupts(state: u8) {
     }
 }
 
+/// Run `f` with a scope in which closures borrowing local state can be attached
+///
+/// Every handler attached through the scope is detached before this function
+/// returns, so the closures may borrow anything that outlives the call (declare
+/// them before calling `interrupt_scope()`). Handlers
+/// can also be detached early by dropping the returned guard.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{interrupt_scope, ExternalInterrupt, InterruptMode};
+/// use core::cell::Cell;
+/// use critical_section::Mutex;
+///
+/// let presses = Mutex::new(Cell::new(0u16));
+/// let mut on_press = || critical_section::with(|cs| {
+///     let count = presses.borrow(cs);
+///     count.set(count.get() + 1);
+/// });
+///
+/// interrupt_scope(|scope| {
+///     let _button = scope.attach(ExternalInterrupt::Int0, InterruptMode::Falling, &mut on_press);
+///
+///     loop {
+///         let count = critical_section::with(|cs| presses.borrow(cs).get());
+///         // ...
+///     }
+/// });
+/// ```
+pub fn interrupt_scope<'env, R>(f: impl FnOnce(&InterruptScope<'env>) -> R) -> R {
+    let scope = InterruptScope {
+        attached: Cell::new(0),
+        _env: PhantomData,
+    };
+    f(&scope)
+    // `scope` is dropped here, detaching everything it attached
+}
+
+/// Source of an interrupt attached through an `InterruptScope`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    External(ExternalInterrupt),
+    PinChange(u8),
+}
+
+impl Source {
+    /// Bit in the scope's attached mask (bits 0-1 external, 2-21 pins 0-19)
+    fn mask(self) -> u32 {
+        match self {
+            Source::External(interrupt) => 1 << (interrupt as u8),
+            Source::PinChange(pin) => 1 << (pin + 2),
+        }
+    }
+
+    fn detach(self) {
+        match self {
+            Source::External(interrupt) => detach_interrupt(interrupt),
+            Source::PinChange(pin) => crate::pcint::pcint_detach(pin),
+        }
+    }
+}
+
+/// Scope for attaching closures that borrow local state
+///
+/// Created by `interrupt_scope()`.
+pub struct InterruptScope<'env> {
+    attached: Cell<u32>,
+    // Invariant over 'env, like std::thread::Scope
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'env> InterruptScope<'env> {
+    /// Attach a closure to an external interrupt (INT0/INT1)
+    ///
+    /// Replaces any handler already attached to the interrupt.
+    pub fn attach(
+        &self,
+        interrupt: ExternalInterrupt,
+        mode: InterruptMode,
+        handler: &'env mut InterruptClosure<'env>,
+    ) -> ScopedInterrupt<'_, 'env> {
+        // The scope detaches the handler before 'env ends
+        attach_handler(interrupt, mode, unsafe { Handler::closure(handler) });
+        self.track(Source::External(interrupt))
+    }
+
+    /// Attach a closure to a pin change interrupt (pins 0-19)
+    ///
+    /// See `pcint_attach_mode()` for how `mode` is applied.
+    pub fn attach_pcint(
+        &self,
+        pin: u8,
+        mode: InterruptMode,
+        handler: &'env mut InterruptClosure<'env>,
+    ) -> ScopedInterrupt<'_, 'env> {
+        // The scope detaches the handler before 'env ends
+        crate::pcint::attach_handler(pin, mode, unsafe { Handler::closure(handler) });
+        self.track(Source::PinChange(pin))
+    }
+
+    fn track(&self, source: Source) -> ScopedInterrupt<'_, 'env> {
+        self.attached.set(self.attached.get() | source.mask());
+        ScopedInterrupt { scope: self, source }
+    }
+}
+
+impl Drop for InterruptScope<'_> {
+    fn drop(&mut self) {
+        let attached = self.attached.get();
+
+        for interrupt in [ExternalInterrupt::Int0, ExternalInterrupt::Int1] {
+            if attached & Source::External(interrupt).mask() != 0 {
+                detach_interrupt(interrupt);
+            }
+        }
+        for pin in 0..20 {
+            if attached & Source::PinChange(pin).mask() != 0 {
+                crate::pcint::pcint_detach(pin);
+            }
+        }
+    }
+}
+
+/// Guard for a handler attached through an `InterruptScope`
+///
+/// Dropping the guard detaches the interrupt source it was created for. Forgetting it is harmless: the
+/// handler is then detached when the scope ends.
+pub struct ScopedInterrupt<'scope, 'env> {
+    scope: &'scope InterruptScope<'env>,
+    source: Source,
+}
+
+impl Drop for ScopedInterrupt<'_, '_> {
+    fn drop(&mut self) {
+        let mask = self.source.mask();
+        let attached = self.scope.attached.get();
+
+        // Skip if another guard for the same source already detached it
+        if attached & mask != 0 {
+            self.source.detach();
+            self.scope.attached.set(attached & !mask);
+        }
+    }
+}
+
+/// Trigger mode of an external interrupt, if it is enabled in EIMSK
+pub(crate) fn enabled_mode(interrupt: ExternalInterrupt) -> Option<InterruptMode> {
+    let shift = interrupt as u8 * (ISC10 - ISC00);
+    unsafe {
+        if read_volatile(EIMSK) & (1 << interrupt as u8) == 0 {
+            return None;
+        }
+        Some(match (read_volatile(EICRA) >> shift) & 0b11 {
+            0b00 => InterruptMode::Low,
+            0b01 => InterruptMode::Change,
+            0b10 => InterruptMode::Falling,
+            _ => InterruptMode::Rising,
+        })
+    }
+}
+
 /// Internal function called by ISR
 fn handle_interrupt(interrupt: ExternalInterrupt) {
-    critical_section::with(|cs| {
-        let handlers = INTERRUPT_HANDLERS.borrow(cs).get();
-        if let Some(handler) = handlers[interrupt as usize] {
-            handler();
-        }
-    });
+    crate::sleep::record_wake(crate::WakeSource::External(interrupt));
+    let handler = critical_section::with(|cs| INTERRUPT_HANDLERS.borrow(cs).get()[interrupt as usize]);
+    if let Some(handler) = handler {
+        handler.call();
+    }
 }
 
 // Interrupt Service Routines
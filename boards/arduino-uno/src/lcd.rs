@@ -1,9 +1,12 @@
-//! HD44780 LCD driver for 16x2/20x4 displays with PCF8574 I2C backpack
+//! HD44780 character LCD driver (16x1 up to 40x2 and 20x4)
 //!
-//! This module provides a driver for HD44780-compatible LCD displays
-//! with PCF8574 I2C I/O expander backpack.
+//! The command layer in [`Lcd`] is shared by two transports:
 //!
-//! Hardware connections:
+//! - [`LcdI2c`]: PCF8574 I2C backpack (4-bit mode)
+//! - [`LcdParallel`]: LCD wired directly to GPIO pins, either 6 pins (RS, EN,
+//!   D4-D7, 4-bit mode) or 10 pins (RS, EN, D0-D7, 8-bit mode)
+//!
+//! PCF8574 backpack connections:
 //! - PCF8574 P0 -> LCD RS (Register Select)
 //! - PCF8574 P1 -> LCD RW (Read/Write)
 //! - PCF8574 P2 -> LCD EN (Enable)
@@ -12,8 +15,16 @@
 //!
 //! Common I2C addresses: 0x27 or 0x3F
 //! Use the i2c_scanner example to find your device's address.
+//!
+//! For the parallel transport, tie the LCD's RW pin to GND.
 
+use core::convert::Infallible;
+use ufmt::uWrite;
+use crate::constants::OUTPUT;
+use crate::gpio::pin_mode;
+use embedded_hal::i2c::I2c as I2cBus;
 use crate::i2c::{I2c, I2cError};
+use crate::ports::fast_digital_write;
 use crate::Delay;
 
 // PCF8574 pin mapping
@@ -27,11 +38,14 @@ const LCD_CLEARDISPLAY: u8 = 0x01;
 const LCD_RETURNHOME: u8 = 0x02;
 const LCD_ENTRYMODESET: u8 = 0x04;
 const LCD_DISPLAYCONTROL: u8 = 0x08;
+const LCD_CURSORSHIFT: u8 = 0x10;
 const LCD_FUNCTIONSET: u8 = 0x20;
+const LCD_SETCGRAMADDR: u8 = 0x40;
 const LCD_SETDDRAMADDR: u8 = 0x80;
 
 // Entry mode flags
 const LCD_ENTRYLEFT: u8 = 0x02;
+const LCD_ENTRYSHIFTINCREMENT: u8 = 0x01;
 const LCD_ENTRYSHIFTDECREMENT: u8 = 0x00;
 
 // Display control flags
@@ -41,27 +55,268 @@ const LCD_CURSOROFF: u8 = 0x00;
 const LCD_BLINKON: u8 = 0x01;
 const LCD_BLINKOFF: u8 = 0x00;
 
+// Cursor/display shift flags
+const LCD_DISPLAYMOVE: u8 = 0x08;
+const LCD_CURSORMOVE: u8 = 0x00;
+const LCD_MOVERIGHT: u8 = 0x04;
+const LCD_MOVELEFT: u8 = 0x00;
+
 // Function set flags
+const LCD_8BITMODE: u8 = 0x10;
 const LCD_4BITMODE: u8 = 0x00;
 const LCD_2LINE: u8 = 0x08;
+const LCD_1LINE: u8 = 0x00;
 const LCD_5X8_DOTS: u8 = 0x00;
 
-// Row offsets for different display sizes
-const ROW_OFFSETS: [u8; 4] = [0x00, 0x40, 0x14, 0x54];
+/// Number of CGRAM slots for custom characters
+pub const LCD_CUSTOM_CHARS: u8 = 8;
+
+/// Display size
+///
+/// Selects the number of lines in the function set and the DDRAM address of
+/// each row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LcdGeometry {
+    /// 16 columns, 1 row (1-line mode, DDRAM 0x00-0x0F)
+    Lcd16x1,
+    /// 16 columns, 2 rows
+    Lcd16x2,
+    /// 16 columns, 4 rows
+    Lcd16x4,
+    /// 20 columns, 2 rows
+    Lcd20x2,
+    /// 20 columns, 4 rows
+    Lcd20x4,
+    /// 40 columns, 2 rows
+    Lcd40x2,
+}
 
-/// LCD display controller
-pub struct Lcd {
-    i2c: I2c,
+impl LcdGeometry {
+    /// Number of columns
+    pub const fn columns(self) -> u8 {
+        match self {
+            LcdGeometry::Lcd16x1 | LcdGeometry::Lcd16x2 | LcdGeometry::Lcd16x4 => 16,
+            LcdGeometry::Lcd20x2 | LcdGeometry::Lcd20x4 => 20,
+            LcdGeometry::Lcd40x2 => 40,
+        }
+    }
+
+    /// Number of rows
+    pub const fn rows(self) -> u8 {
+        match self {
+            LcdGeometry::Lcd16x1 => 1,
+            LcdGeometry::Lcd16x4 | LcdGeometry::Lcd20x4 => 4,
+            _ => 2,
+        }
+    }
+
+    /// DDRAM address of the first column of each row
+    ///
+    /// 4-row displays continue rows 0 and 1 into rows 2 and 3, so their
+    /// offsets depend on the column count.
+    pub const fn row_offsets(self) -> [u8; 4] {
+        match self {
+            LcdGeometry::Lcd16x4 => [0x00, 0x40, 0x10, 0x50],
+            _ => [0x00, 0x40, 0x14, 0x54],
+        }
+    }
+}
+
+/// Bus connecting the command layer to the LCD
+///
+/// A transport only latches values into the controller; command sequencing
+/// and timing of slow commands are handled by [`Lcd`].
+pub trait LcdTransport {
+    /// Error returned by bus operations
+    type Error;
+
+    /// Whether all 8 data lines are connected
+    fn eight_bit(&self) -> bool;
+
+    /// Prepare the bus before the initialization sequence
+    fn begin(&mut self) -> Result<(), Self::Error>;
+
+    /// Put `value` on the data lines and pulse EN
+    ///
+    /// 4-bit transports send the upper nibble of `value`. `data` selects the
+    /// data register (RS high) instead of the instruction register. Must wait
+    /// long enough for a regular command to complete (37 us).
+    fn latch(&mut self, value: u8, data: bool) -> Result<(), Self::Error>;
+
+    /// Switch the backlight, if the transport controls it
+    fn set_backlight(&mut self, on: bool) -> Result<(), Self::Error>;
+}
+
+/// PCF8574 I2C backpack transport
+///
+/// Works on the on-chip [`I2c`] or on a proxy from [`SharedI2c`](crate::SharedI2c).
+pub struct LcdI2c<I2C = I2c> {
+    i2c: I2C,
     address: u8,
     backlight_state: u8,
+}
+
+impl<I2C: I2cBus> LcdI2c<I2C> {
+    /// Create a transport for the backpack at `address`
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        LcdI2c {
+            i2c,
+            address,
+            backlight_state: BACKLIGHT,
+        }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Write a single byte to the I2C expander
+    fn i2c_write(&mut self, data: u8) -> Result<(), I2cError> {
+        self.i2c.write(self.address, &[data]).map_err(I2cError::from_bus)
+    }
+}
+
+impl<I2C: I2cBus> LcdTransport for LcdI2c<I2C> {
+    type Error = I2cError;
+
+    fn eight_bit(&self) -> bool {
+        false
+    }
+
+    fn begin(&mut self) -> Result<(), I2cError> {
+        // Reset I2C expander
+        self.i2c_write(self.backlight_state)?;
+        Delay::new().delay_ms(1000);
+        Ok(())
+    }
+
+    fn latch(&mut self, value: u8, data: bool) -> Result<(), I2cError> {
+        let mode = if data { RS } else { 0 };
+        let data = (value & 0xF0) | mode | self.backlight_state;
+        self.i2c_write(data)?;
+
+        // Pulse the enable pin to latch data
+        self.i2c_write(data | EN)?;
+        crate::delay_micros(1);
+        self.i2c_write(data & !EN)?;
+        crate::delay_micros(50);
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, on: bool) -> Result<(), I2cError> {
+        self.backlight_state = if on { BACKLIGHT } else { 0 };
+        self.i2c_write(self.backlight_state)
+    }
+}
+
+/// Direct GPIO transport (6-pin 4-bit or 10-pin 8-bit)
+///
+/// Pins are Arduino pin numbers (0-19, with A0-A5 as 14-19).
+pub struct LcdParallel {
+    rs: u8,
+    enable: u8,
+    data: [u8; 8],
+    eight_bit: bool,
+    backlight: Option<u8>,
+}
+
+impl LcdParallel {
+    /// 4-bit bus on RS, EN and D4-D7
+    pub fn four_bit(rs: u8, enable: u8, data: [u8; 4]) -> Self {
+        LcdParallel {
+            rs,
+            enable,
+            data: [data[0], data[1], data[2], data[3], 0, 0, 0, 0],
+            eight_bit: false,
+            backlight: None,
+        }
+    }
+
+    /// 8-bit bus on RS, EN and D0-D7
+    pub fn eight_bit(rs: u8, enable: u8, data: [u8; 8]) -> Self {
+        LcdParallel {
+            rs,
+            enable,
+            data,
+            eight_bit: true,
+            backlight: None,
+        }
+    }
+
+    /// Drive the backlight (e.g. through a transistor) from a pin
+    pub fn with_backlight(mut self, pin: u8) -> Self {
+        self.backlight = Some(pin);
+        self
+    }
+
+    fn data_pins(&self) -> &[u8] {
+        if self.eight_bit { &self.data } else { &self.data[..4] }
+    }
+}
+
+impl LcdTransport for LcdParallel {
+    type Error = Infallible;
+
+    fn eight_bit(&self) -> bool {
+        self.eight_bit
+    }
+
+    fn begin(&mut self) -> Result<(), Infallible> {
+        for &pin in [self.rs, self.enable].iter().chain(self.data_pins()) {
+            pin_mode(pin, OUTPUT);
+            fast_digital_write(pin, false);
+        }
+        if let Some(pin) = self.backlight {
+            pin_mode(pin, OUTPUT);
+            fast_digital_write(pin, true);
+        }
+        Ok(())
+    }
+
+    fn latch(&mut self, value: u8, data: bool) -> Result<(), Infallible> {
+        fast_digital_write(self.rs, data);
+
+        // D4-D7 carry the upper nibble in 4-bit mode
+        let bits = if self.eight_bit { value } else { value >> 4 };
+        for (i, &pin) in self.data_pins().iter().enumerate() {
+            fast_digital_write(pin, bits & (1 << i) != 0);
+        }
+
+        // Enable pulse must be >450 ns; commands need >37 us to settle
+        fast_digital_write(self.enable, true);
+        crate::delay_micros(1);
+        fast_digital_write(self.enable, false);
+        crate::delay_micros(50);
+
+        Ok(())
+    }
+
+    fn set_backlight(&mut self, on: bool) -> Result<(), Infallible> {
+        if let Some(pin) = self.backlight {
+            fast_digital_write(pin, on);
+        }
+        Ok(())
+    }
+}
+
+/// LCD display controller
+///
+/// Defaults to a 16x2 display; see [`Lcd::with_geometry`].
+pub struct Lcd<T: LcdTransport = LcdI2c> {
+    transport: T,
+    geometry: LcdGeometry,
+    display_control: u8,
+    entry_mode: u8,
     delay: Delay,
 }
 
-impl Lcd {
-    /// Create a new LCD instance
+impl<I2C: I2cBus> Lcd<LcdI2c<I2C>> {
+    /// Create a new LCD instance on a PCF8574 I2C backpack
     ///
     /// # Arguments
-    /// * `i2c` - I2C peripheral instance
+    /// * `i2c` - I2C peripheral instance or a `SharedI2c` proxy
     /// * `address` - I2C address of the LCD (use i2c_scanner to find)
     ///
     /// # Example
@@ -70,52 +325,98 @@ impl Lcd {
     /// let mut lcd = Lcd::new(i2c, 0x3F);  // Use address from i2c_scanner
     /// lcd.init().unwrap();
     /// ```
-    pub fn new(i2c: I2c, address: u8) -> Self {
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Lcd::with_transport(LcdI2c::new(i2c, address))
+    }
+}
+
+impl<T: LcdTransport> Lcd<T> {
+    /// Create a new LCD instance on any transport
+    ///
+    /// # Example
+    /// ```no_run
+    /// use arduino_uno::{Lcd, LcdGeometry, LcdParallel};
+    ///
+    /// // RS=D12, EN=D11, D4-D7 = D5, D4, D3, D2 (Arduino LiquidCrystal wiring)
+    /// let bus = LcdParallel::four_bit(12, 11, [5, 4, 3, 2]);
+    /// let mut lcd = Lcd::with_transport(bus).with_geometry(LcdGeometry::Lcd20x4);
+    /// lcd.init().unwrap();
+    /// ```
+    pub fn with_transport(transport: T) -> Self {
         Lcd {
-            i2c,
-            address,
-            backlight_state: BACKLIGHT,
+            transport,
+            geometry: LcdGeometry::Lcd16x2,
+            display_control: LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF,
+            entry_mode: LCD_ENTRYLEFT | LCD_ENTRYSHIFTDECREMENT,
             delay: Delay::new(),
         }
     }
 
+    /// Set the display size (call before `init()`)
+    pub fn with_geometry(mut self, geometry: LcdGeometry) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    /// Display size
+    pub fn geometry(&self) -> LcdGeometry {
+        self.geometry
+    }
+
+    /// Release the transport
+    pub fn release(self) -> T {
+        self.transport
+    }
+
     /// Initialize the LCD display
     ///
     /// This performs the full initialization sequence as per HD44780 datasheet.
     /// Must be called before using any other LCD functions.
-    pub fn init(&mut self) -> Result<(), I2cError> {
+    pub fn init(&mut self) -> Result<(), T::Error> {
         // Wait for LCD power-up
         self.delay.delay_ms(50);
 
-        // Reset I2C expander
-        self.i2c_write(self.backlight_state)?;
-        self.delay.delay_ms(1000);
+        self.transport.begin()?;
 
-        // Start initialization sequence: transition to 4-bit mode
-        // Send 0x03 three times with specific delays
-        self.write_nibble(0x03 << 4, 0)?;
-        self.delay_us(4500);
+        let lines = if self.geometry.rows() > 1 { LCD_2LINE } else { LCD_1LINE };
 
-        self.write_nibble(0x03 << 4, 0)?;
-        self.delay_us(4500);
+        if self.transport.eight_bit() {
+            // Function set 8-bit three times with specific delays
+            self.transport.latch(LCD_FUNCTIONSET | LCD_8BITMODE, false)?;
+            self.delay_us(4500);
+            self.transport.latch(LCD_FUNCTIONSET | LCD_8BITMODE, false)?;
+            self.delay_us(150);
+            self.transport.latch(LCD_FUNCTIONSET | LCD_8BITMODE, false)?;
 
-        self.write_nibble(0x03 << 4, 0)?;
-        self.delay_us(150);
+            self.send_command(LCD_FUNCTIONSET | LCD_8BITMODE | lines | LCD_5X8_DOTS)?;
+        } else {
+            // Start initialization sequence: transition to 4-bit mode
+            // Send 0x03 three times with specific delays
+            self.transport.latch(0x03 << 4, false)?;
+            self.delay_us(4500);
 
-        // Finally, set to 4-bit interface
-        self.write_nibble(0x02 << 4, 0)?;
+            self.transport.latch(0x03 << 4, false)?;
+            self.delay_us(4500);
 
-        // Function set: 4-bit mode, 2 lines, 5x8 dots
-        self.send_command(LCD_FUNCTIONSET | LCD_4BITMODE | LCD_2LINE | LCD_5X8_DOTS)?;
+            self.transport.latch(0x03 << 4, false)?;
+            self.delay_us(150);
+
+            // Finally, set to 4-bit interface
+            self.transport.latch(0x02 << 4, false)?;
+
+            self.send_command(LCD_FUNCTIONSET | LCD_4BITMODE | lines | LCD_5X8_DOTS)?;
+        }
 
         // Display control: display on, cursor off, blink off
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF)?;
+        self.display_control = LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF;
+        self.update_display_control()?;
 
         // Clear display
         self.clear()?;
 
         // Entry mode: left to right, no shift
-        self.send_command(LCD_ENTRYMODESET | LCD_ENTRYLEFT | LCD_ENTRYSHIFTDECREMENT)?;
+        self.entry_mode = LCD_ENTRYLEFT | LCD_ENTRYSHIFTDECREMENT;
+        self.update_entry_mode()?;
 
         // Home
         self.home()?;
@@ -123,56 +424,20 @@ impl Lcd {
         Ok(())
     }
 
-    /// Write a single byte to the I2C expander
-    fn i2c_write(&self, data: u8) -> Result<(), I2cError> {
-        self.i2c.write(self.address, &[data])
-    }
-
-    /// Pulse the enable pin to latch data
-    fn pulse_enable(&mut self, data: u8) -> Result<(), I2cError> {
-        // Set EN high
-        self.i2c_write(data | EN)?;
-        self.delay_us(1);
-
-        // Set EN low
-        self.i2c_write(data & !EN)?;
-        self.delay_us(50);
-
-        Ok(())
-    }
-
-    /// Write a 4-bit nibble to the LCD
-    ///
-    /// # Arguments
-    /// * `nibble` - 4-bit value in upper nibble (bits 4-7)
-    /// * `mode` - RS bit (0 for command, RS for data)
-    fn write_nibble(&mut self, nibble: u8, mode: u8) -> Result<(), I2cError> {
-        let data = nibble | mode | self.backlight_state;
-        self.i2c_write(data)?;
-        self.pulse_enable(data)?;
-        Ok(())
-    }
-
-    /// Write a full byte (8 bits) as two nibbles
-    ///
-    /// # Arguments
-    /// * `value` - 8-bit value to write
-    /// * `mode` - RS bit (0 for command, RS for data)
-    fn write_byte(&mut self, value: u8, mode: u8) -> Result<(), I2cError> {
-        // Send high nibble first
-        let high_nibble = value & 0xF0;
-        self.write_nibble(high_nibble, mode)?;
-
-        // Send low nibble
-        let low_nibble = (value << 4) & 0xF0;
-        self.write_nibble(low_nibble, mode)?;
-
-        Ok(())
+    /// Write a full byte, as two nibbles on a 4-bit bus
+    fn write_byte(&mut self, value: u8, data: bool) -> Result<(), T::Error> {
+        if self.transport.eight_bit() {
+            self.transport.latch(value, data)
+        } else {
+            // Send high nibble first
+            self.transport.latch(value & 0xF0, data)?;
+            self.transport.latch(value << 4, data)
+        }
     }
 
     /// Send a command to the LCD
-    fn send_command(&mut self, cmd: u8) -> Result<(), I2cError> {
-        self.write_byte(cmd, 0)?;
+    fn send_command(&mut self, cmd: u8) -> Result<(), T::Error> {
+        self.write_byte(cmd, false)?;
 
         // Some commands need extra time
         if cmd == LCD_CLEARDISPLAY || cmd == LCD_RETURNHOME {
@@ -183,38 +448,48 @@ impl Lcd {
     }
 
     /// Send data (character) to the LCD
-    fn send_data(&mut self, data: u8) -> Result<(), I2cError> {
-        self.write_byte(data, RS)
+    fn send_data(&mut self, data: u8) -> Result<(), T::Error> {
+        self.write_byte(data, true)
+    }
+
+    fn update_display_control(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_DISPLAYCONTROL | self.display_control)
+    }
+
+    fn update_entry_mode(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_ENTRYMODESET | self.entry_mode)
     }
 
     /// Clear the display
-    pub fn clear(&mut self) -> Result<(), I2cError> {
+    pub fn clear(&mut self) -> Result<(), T::Error> {
         self.send_command(LCD_CLEARDISPLAY)
     }
 
-    /// Return cursor to home position (0, 0)
-    pub fn home(&mut self) -> Result<(), I2cError> {
+    /// Return cursor to home position (0, 0) and undo display shifts
+    pub fn home(&mut self) -> Result<(), T::Error> {
         self.send_command(LCD_RETURNHOME)
     }
 
     /// Set cursor position
     ///
     /// # Arguments
-    /// * `row` - Row number (0-3, depending on display size)
-    /// * `col` - Column number (0-15 for 16x2, 0-19 for 20x4)
-    pub fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), I2cError> {
-        let row = row.min(3);
-        let offset = ROW_OFFSETS[row as usize];
-        self.send_command(LCD_SETDDRAMADDR | (col + offset))
+    /// * `row` - Row number (clamped to the last row of the geometry)
+    /// * `col` - Column number (0-15 for 16x2, 0-19 for 20x4, 0-39 for 40x2)
+    pub fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), T::Error> {
+        let row = row.min(self.geometry.rows() - 1);
+        let offset = self.geometry.row_offsets()[row as usize];
+        self.send_command(LCD_SETDDRAMADDR | (col.wrapping_add(offset) & 0x7F))
     }
 
     /// Write a single character at the current cursor position
-    pub fn write_char(&mut self, ch: char) -> Result<(), I2cError> {
+    ///
+    /// Characters `'\u{0}'` to `'\u{7}'` show the custom characters.
+    pub fn write_char(&mut self, ch: char) -> Result<(), T::Error> {
         self.send_data(ch as u8)
     }
 
     /// Write a string at the current cursor position
-    pub fn write_str(&mut self, s: &str) -> Result<(), I2cError> {
+    pub fn write_str(&mut self, s: &str) -> Result<(), T::Error> {
         for ch in s.chars() {
             self.write_char(ch)?;
         }
@@ -227,51 +502,135 @@ impl Lcd {
     /// * `row` - Row number (0-3)
     /// * `col` - Column number
     /// * `s` - String to write
-    pub fn print_at(&mut self, row: u8, col: u8, s: &str) -> Result<(), I2cError> {
+    pub fn print_at(&mut self, row: u8, col: u8, s: &str) -> Result<(), T::Error> {
         self.set_cursor(row, col)?;
         self.write_str(s)
     }
 
+    /// Define a custom 5x8 character
+    ///
+    /// Each byte is one pixel row, top to bottom, using the lower 5 bits.
+    /// Show it with `write_custom(slot)` or `write_char('\u{slot}')`.
+    ///
+    /// This moves the controller's address into CGRAM, so call
+    /// `set_cursor()`, `home()` or `clear()` before writing text again.
+    ///
+    /// # Arguments
+    /// * `slot` - Character code 0-7 (higher values wrap)
+    /// * `pattern` - Pixel rows
+    ///
+    /// # Example
+    /// ```no_run
+    /// const HEART: [u8; 8] = [0x00, 0x0A, 0x1F, 0x1F, 0x0E, 0x04, 0x00, 0x00];
+    /// lcd.create_char(0, HEART).unwrap();
+    /// lcd.set_cursor(0, 0).unwrap();
+    /// lcd.write_custom(0).unwrap();
+    /// ```
+    pub fn create_char(&mut self, slot: u8, pattern: [u8; 8]) -> Result<(), T::Error> {
+        let slot = slot % LCD_CUSTOM_CHARS;
+        self.send_command(LCD_SETCGRAMADDR | (slot << 3))?;
+        for row in pattern {
+            self.send_data(row & 0x1F)?;
+        }
+        Ok(())
+    }
+
+    /// Write a custom character defined with `create_char()`
+    pub fn write_custom(&mut self, slot: u8) -> Result<(), T::Error> {
+        self.send_data(slot % LCD_CUSTOM_CHARS)
+    }
+
     /// Turn backlight on
-    pub fn backlight_on(&mut self) -> Result<(), I2cError> {
-        self.backlight_state = BACKLIGHT;
-        self.i2c_write(self.backlight_state)
+    pub fn backlight_on(&mut self) -> Result<(), T::Error> {
+        self.transport.set_backlight(true)
     }
 
     /// Turn backlight off
-    pub fn backlight_off(&mut self) -> Result<(), I2cError> {
-        self.backlight_state = 0;
-        self.i2c_write(self.backlight_state)
+    pub fn backlight_off(&mut self) -> Result<(), T::Error> {
+        self.transport.set_backlight(false)
     }
 
-    /// Turn display on (with cursor off and blink off)
-    pub fn display_on(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF)
+    /// Turn display on (cursor and blink settings are kept)
+    pub fn display_on(&mut self) -> Result<(), T::Error> {
+        self.display_control |= LCD_DISPLAYON;
+        self.update_display_control()
     }
 
-    /// Turn display off
-    pub fn display_off(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL)
+    /// Turn display off (display RAM is kept)
+    pub fn display_off(&mut self) -> Result<(), T::Error> {
+        self.display_control &= !LCD_DISPLAYON;
+        self.update_display_control()
     }
 
     /// Show cursor
-    pub fn cursor_on(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSORON | LCD_BLINKOFF)
+    pub fn cursor_on(&mut self) -> Result<(), T::Error> {
+        self.display_control |= LCD_CURSORON;
+        self.update_display_control()
     }
 
     /// Hide cursor
-    pub fn cursor_off(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF)
+    pub fn cursor_off(&mut self) -> Result<(), T::Error> {
+        self.display_control &= !LCD_CURSORON;
+        self.update_display_control()
     }
 
     /// Enable cursor blink
-    pub fn blink_on(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKON)
+    pub fn blink_on(&mut self) -> Result<(), T::Error> {
+        self.display_control |= LCD_BLINKON;
+        self.update_display_control()
     }
 
     /// Disable cursor blink
-    pub fn blink_off(&mut self) -> Result<(), I2cError> {
-        self.send_command(LCD_DISPLAYCONTROL | LCD_DISPLAYON | LCD_CURSOROFF | LCD_BLINKOFF)
+    pub fn blink_off(&mut self) -> Result<(), T::Error> {
+        self.display_control &= !LCD_BLINKON;
+        self.update_display_control()
+    }
+
+    /// Shift the whole display one position to the left
+    ///
+    /// All rows shift together; DDRAM contents and the cursor address are
+    /// unchanged. `home()` undoes the shift.
+    pub fn scroll_display_left(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_CURSORSHIFT | LCD_DISPLAYMOVE | LCD_MOVELEFT)
+    }
+
+    /// Shift the whole display one position to the right
+    pub fn scroll_display_right(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_CURSORSHIFT | LCD_DISPLAYMOVE | LCD_MOVERIGHT)
+    }
+
+    /// Move the cursor one position to the left without writing
+    pub fn move_cursor_left(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_CURSORSHIFT | LCD_CURSORMOVE | LCD_MOVELEFT)
+    }
+
+    /// Move the cursor one position to the right without writing
+    pub fn move_cursor_right(&mut self) -> Result<(), T::Error> {
+        self.send_command(LCD_CURSORSHIFT | LCD_CURSORMOVE | LCD_MOVERIGHT)
+    }
+
+    /// Shift the display on every character written, keeping the cursor in place
+    pub fn autoscroll_on(&mut self) -> Result<(), T::Error> {
+        self.entry_mode |= LCD_ENTRYSHIFTINCREMENT;
+        self.update_entry_mode()
+    }
+
+    /// Stop shifting the display on writes
+    pub fn autoscroll_off(&mut self) -> Result<(), T::Error> {
+        self.entry_mode &= !LCD_ENTRYSHIFTINCREMENT;
+        self.update_entry_mode()
+    }
+
+    /// Write text left to right (default)
+    pub fn left_to_right(&mut self) -> Result<(), T::Error> {
+        self.entry_mode |= LCD_ENTRYLEFT;
+        self.update_entry_mode()
+    }
+
+    /// Write text right to left (the cursor moves left after each character)
+    pub fn right_to_left(&mut self) -> Result<(), T::Error> {
+        self.entry_mode &= !LCD_ENTRYLEFT;
+        self.update_entry_mode()
     }
 
     /// Microsecond delay helper
@@ -280,11 +639,16 @@ impl Lcd {
         if ms > 0 {
             self.delay.delay_ms(ms);
         }
-        // For sub-millisecond delays, use a busy loop
-        // At 16MHz: 1us ~= 16 cycles, we use 4 nops per iteration
+        // For sub-millisecond delays, use the clock-calibrated busy loop
         let remaining_us = us % 1000;
-        for _ in 0..(remaining_us * 4) {
-            unsafe { core::arch::asm!("nop"); }
-        }
+        crate::delay_micros(remaining_us as u16);
+    }
+}
+
+impl<T: LcdTransport> uWrite for Lcd<T> {
+    type Error = T::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        Lcd::write_str(self, s)
     }
 }
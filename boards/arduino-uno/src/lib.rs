@@ -10,6 +10,7 @@ pub use ossidata_core::prelude::*;
 use core::mem::MaybeUninit;
 
 // Hardware-specific implementations
+mod clock;
 mod gpio_impl;
 mod gpio;
 mod pin;
@@ -20,15 +21,20 @@ mod adc;
 mod time;
 mod i2c;
 mod lcd;
+mod oled;
 mod spi;
 mod rtc;
+mod soft_rtc;
 mod interrupt;
 mod eeprom;
 mod tone;
+mod melody;
+mod pcm;
 mod pulse;
 mod shift;
 mod watchdog;
 mod sleep;
+mod power;
 mod embedded_hal_impl;
 mod utils;
 mod constants;
@@ -39,6 +45,10 @@ mod memory;
 mod software_serial;
 mod string;
 mod servo;
+mod shared_bus;
+mod stepper;
+mod motor;
+mod encoder;
 
 // Re-export our hardware types
 pub use pin::{Pin, PinState, digital_read, digital_write};
@@ -52,20 +62,31 @@ pub use ports::{
 pub use serial::Serial;
 pub use pwm::{Pwm, PwmFrequency};
 pub use adc::{Adc, AdcReference};
-pub use time::{millis, micros, delay_micros};
+pub use clock::{F_CPU, ClockPrescaler, cpu_frequency, clock_prescaler, set_clock_prescaler, micros_to_cycles};
+pub use time::{millis, micros, delay_micros, millis64, micros64, now, elapsed_since, SystemClock};
+pub use ossidata_core::time::{Instant, Duration};
 pub use i2c::{I2c, I2cError};
-pub use lcd::Lcd;
+pub use lcd::{Lcd, LcdGeometry, LcdI2c, LcdParallel, LcdTransport, LCD_CUSTOM_CHARS};
+pub use oled::{Oled, OledController, OledError, OledInterface, OledI2c, OledSpi, PageBuffer, OLED_I2C_ADDRESS, OLED_WIDTH, OLED_HEIGHT};
 pub use spi::{Spi, SpiSettings, SpiClock, SpiMode, BitOrder};
-pub use rtc::{DateTime, Rtc, RtcError, DS1307, DS3231};
-pub use interrupt::{attach_interrupt, detach_interrupt, disable_interrupts, restore_interrupts, ExternalInterrupt, InterruptMode};
-pub use eeprom::{Eeprom, EEPROM_SIZE};
-pub use tone::{tone, tone_duration, no_tone};
+pub use shared_bus::{SharedI2c, I2cDevice, SharedSpi, SpiDevice, SpiDeviceError};
+pub use rtc::{build_time, BUILD_TIMESTAMP, Alarm1Mode, Alarm2Mode, Ds1307SquareWave, Ds3231Alarm, Ds3231SquareWave, Pcf8563Clkout, Rtc, RtcError, DS1307, DS1307_NVRAM_SIZE, DS3231, MCP7940N, PCF8563};
+pub use soft_rtc::{SoftRtc, SoftRtcSource};
+pub use interrupt::{attach_interrupt, attach_interrupt_with, detach_interrupt, interrupt_scope, InterruptScope, ScopedInterrupt, InterruptClosure, disable_interrupts, restore_interrupts, ExternalInterrupt, InterruptMode};
+pub use eeprom::{Eeprom, EepromData, EepromCell, assert_eeprom_layout, EEPROM_SIZE, ASYNC_BUFFER_SIZE};
+pub use ossidata_core::storage::{KvStore, KvError, KvValue, Storage};
+pub use ossidata_core::datetime::{DateTime, DateTimeParseError, DstRule, TimeZone, ZonedDateTime};
+pub use tone::{tone, tone_with_volume, tone_duration, no_tone, set_tone_volume, MAX_TONES, MAX_VOLUME};
+pub use melody::{Melody, play_melody, loop_melody, stop_melody, is_melody_playing, set_melody_tempo, DEFAULT_MELODY_TEMPO};
+pub use ossidata_core::melody::{Note, RtttlError, note_frequency};
+pub use pcm::{PcmPlayer, PcmClip, PcmError, PCM_SILENCE, MIN_SAMPLE_RATE, MAX_SAMPLE_RATE};
 pub use pulse::{pulse_in, pulse_in_long, PulseState};
 pub use shift::{shift_out, shift_in};
-pub use watchdog::{Watchdog, WatchdogTimeout};
-pub use sleep::{Sleep, SleepMode};
+pub use watchdog::{Breadcrumb, ResetCause, Watchdog, WatchdogTimeout};
+pub use sleep::{PowerDownOptions, Sleep, SleepMode, WakeConfig, WakeError, WakeSource};
+pub use power::{PowerManager, PowerPeripheral};
 pub use progmem::{FlashString, pgm_read_byte, pgm_read_word, pgm_read_dword, pgm_read_float, pgm_read_ptr};
-pub use pcint::{PcintBank, pcint_attach, pcint_detach, pcint_enable_bank, pcint_disable_bank};
+pub use pcint::{PcintBank, pcint_attach, pcint_attach_mode, pcint_attach_with, pcint_detach, pcint_enable_bank, pcint_disable_bank, pcint_changed_pins};
 pub use timer::{
     Timer, Prescaler, TimerMode,
     timer_read, timer_write, timer_set_prescaler,
@@ -86,6 +107,10 @@ pub use memory::{
 pub use software_serial::SoftwareSerial;
 pub use string::{ArduinoString, String, DEFAULT_STRING_CAPACITY};
 pub use servo::Servo;
+pub use stepper::{Stepper, StepperError, StepSequence, Winding};
+pub use motor::{Motor, HBridge, PwmDir, DualPwm, DecayMode, MOTOR_MAX_SPEED};
+pub use encoder::Encoder;
+pub use ossidata_core::pid::{Pid, PidGains, GAIN_ONE};
 
 // Utility functions
 pub use utils::{
@@ -205,6 +230,7 @@ impl Peripherals {
 
 /// Delay implementation using busy-wait loops
 ///
+/// Calibrated from the current CPU clock (see `cpu_frequency()`).
 /// For ATmega328P at 16MHz:
 /// - 1 clock cycle = 62.5ns
 /// - This implementation provides approximate millisecond delays
@@ -219,7 +245,7 @@ impl Delay {
     /// Delay for the specified number of milliseconds
     ///
     /// At 16MHz, we need approximately 16,000 cycles per millisecond.
-    /// This implementation uses a calibrated busy-wait loop.
+    /// This implementation uses a busy-wait loop calibrated to the CPU clock.
     pub fn delay_ms(&mut self, ms: u32) {
         // Each iteration of the inner loop takes approximately 4 cycles:
         // - 1 cycle for the loop counter decrement
@@ -227,11 +253,11 @@ impl Delay {
         // - 1 cycle for the NOP
         // - 1 cycle overhead
         //
-        // So we need about 4000 iterations per millisecond
-        const CYCLES_PER_MS: u32 = 4000;
+        // So we need about 4000 iterations per millisecond at 16MHz
+        let iterations_per_ms = clock::cpu_frequency() / 4000;
 
         for _ in 0..ms {
-            for _ in 0..CYCLES_PER_MS {
+            for _ in 0..iterations_per_ms {
                 unsafe {
                     core::arch::asm!("nop");
                 }
@@ -0,0 +1,350 @@
+//! Non-blocking melody playback
+//!
+//! [`play_melody`] plays a [`Melody`] on any pin in the background: the timer
+//! interrupt that generates `tone()` also loads each next note when the
+//! current one ends, so the main program keeps running. Melodies are either
+//! note arrays or RTTTL (Nokia ringtone) text, kept in flash with the
+//! [`melody!`](crate::melody!) and [`rtttl!`](crate::rtttl!) macros or in
+//! RAM.
+//!
+//! Each note sounds for 7/8 of its length followed by a short silence, so
+//! repeated notes are heard separately. A melody uses the tone timer of its
+//! pin and can play while `tone()` sounds on other pins; one melody plays at
+//! a time.
+
+use ossidata_core::irq::IrqCell;
+use ossidata_core::melody::{Note, RtttlError, RtttlParser};
+use crate::progmem::pgm_read_byte;
+use crate::tone;
+
+/// Tempo used when neither the melody nor the caller sets one
+pub const DEFAULT_MELODY_TEMPO: u16 = 120;
+
+/// Timer rate used to time rests and gaps without sound
+const SILENT_FREQUENCY: u16 = 1000;
+
+#[derive(Clone, Copy)]
+enum Source {
+    Notes { notes: *const Note, len: usize, index: usize },
+    Rtttl { text: *const u8, parser: RtttlParser },
+}
+
+/// A melody to play with [`play_melody`]
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{melody, play_melody, rtttl, Note};
+///
+/// // Notes in flash, at 100 quarter notes per minute
+/// let fanfare = melody![
+///     Note::new(523, Note::EIGHTH),
+///     Note::new(659, Note::EIGHTH),
+///     Note::new(784, Note::QUARTER),
+/// ]
+/// .with_tempo(100);
+/// play_melody(11, fanfare);
+///
+/// // RTTTL in flash, tempo from the b= setting
+/// let ringtone = rtttl!("Beep:d=8,o=5,b=160:c,e,g,4c6").unwrap();
+/// play_melody(11, ringtone);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Melody {
+    source: Source,
+    flash: bool,
+    tempo: u16,
+}
+
+// The pointers refer to `'static` data that is never written
+unsafe impl Send for Melody {}
+
+impl Melody {
+    /// Create a melody from notes in RAM
+    pub const fn new(notes: &'static [Note]) -> Self {
+        Self {
+            source: Source::Notes { notes: notes.as_ptr(), len: notes.len(), index: 0 },
+            flash: false,
+            tempo: DEFAULT_MELODY_TEMPO,
+        }
+    }
+
+    /// Create a melody from notes in flash
+    ///
+    /// Prefer the [`melody!`](crate::melody!) macro, which places the notes
+    /// in flash for you.
+    ///
+    /// # Safety
+    /// `notes` must be placed in `.progmem.data`.
+    pub const unsafe fn from_progmem(notes: &'static [Note]) -> Self {
+        Self {
+            source: Source::Notes { notes: notes.as_ptr(), len: notes.len(), index: 0 },
+            flash: true,
+            tempo: DEFAULT_MELODY_TEMPO,
+        }
+    }
+
+    /// Parse an RTTTL melody in RAM
+    pub fn rtttl(text: &'static str) -> Result<Self, RtttlError> {
+        Self::from_rtttl(text.as_ptr(), text.len(), false)
+    }
+
+    /// Parse an RTTTL melody in flash
+    ///
+    /// Prefer the [`rtttl!`](crate::rtttl!) macro, which places the text in
+    /// flash for you.
+    ///
+    /// # Safety
+    /// `text` must be placed in `.progmem.data`.
+    pub unsafe fn from_progmem_rtttl(text: &'static [u8]) -> Result<Self, RtttlError> {
+        Self::from_rtttl(text.as_ptr(), text.len(), true)
+    }
+
+    fn from_rtttl(text: *const u8, len: usize, flash: bool) -> Result<Self, RtttlError> {
+        let parser = RtttlParser::new(len, |i| read_byte(text, i, flash))?;
+        Ok(Self { source: Source::Rtttl { text, parser }, flash, tempo: parser.tempo() })
+    }
+
+    /// Play at a tempo in quarter notes per minute
+    pub const fn with_tempo(mut self, bpm: u16) -> Self {
+        self.tempo = bpm;
+        self
+    }
+
+    /// Tempo in quarter notes per minute
+    pub const fn tempo(&self) -> u16 {
+        self.tempo
+    }
+
+    /// Next note, `None` at the end or after a malformed RTTTL note
+    fn next_note(&mut self) -> Option<Note> {
+        let flash = self.flash;
+        match &mut self.source {
+            Source::Notes { notes, len, index } => {
+                if *index >= *len {
+                    return None;
+                }
+                let note = read_note(unsafe { notes.add(*index) }, flash);
+                *index += 1;
+                Some(note)
+            }
+            Source::Rtttl { text, parser } => {
+                let text = *text;
+                parser.next_note(|i| read_byte(text, i, flash))?.ok()
+            }
+        }
+    }
+
+    /// Go back to the first note
+    fn rewind(&mut self) {
+        match &mut self.source {
+            Source::Notes { index, .. } => *index = 0,
+            Source::Rtttl { parser, .. } => parser.rewind(),
+        }
+    }
+}
+
+fn read_byte(text: *const u8, index: usize, flash: bool) -> u8 {
+    unsafe {
+        if flash {
+            pgm_read_byte(text.add(index))
+        } else {
+            *text.add(index)
+        }
+    }
+}
+
+fn read_note(note: *const Note, flash: bool) -> Note {
+    if flash {
+        // `Note` is two little-endian u16 fields
+        let bytes = note as *const u8;
+        let word = |offset| u16::from_le_bytes([read_byte(bytes, offset, true), read_byte(bytes, offset + 1, true)]);
+        Note::new(word(0), word(2))
+    } else {
+        unsafe { *note }
+    }
+}
+
+/// Place notes in flash and create a [`Melody`] from them
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{melody, Note};
+///
+/// let scale = melody![
+///     Note::new(262, Note::QUARTER),
+///     Note::new(294, Note::QUARTER),
+///     Note::rest(Note::QUARTER),
+/// ];
+/// ```
+#[macro_export]
+macro_rules! melody {
+    ($($note:expr),* $(,)?) => {{
+        #[link_section = ".progmem.data"]
+        static NOTES: [$crate::Note; [$(stringify!($note)),*].len()] = [$($note),*];
+        unsafe { $crate::Melody::from_progmem(&NOTES) }
+    }};
+}
+
+/// Place RTTTL text in flash and parse it into a [`Melody`]
+///
+/// Returns `Result<Melody, RtttlError>`.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::rtttl;
+///
+/// let tune = rtttl!("Beep:d=8,o=5,b=160:c,e,g,4c6").unwrap();
+/// ```
+#[macro_export]
+macro_rules! rtttl {
+    ($text:literal) => {{
+        const LEN: usize = $text.len();
+        #[link_section = ".progmem.data"]
+        static TEXT: [u8; LEN] = {
+            let bytes = $text.as_bytes();
+            let mut text = [0u8; LEN];
+            let mut i = 0;
+            while i < LEN {
+                text[i] = bytes[i];
+                i += 1;
+            }
+            text
+        };
+        unsafe { $crate::Melody::from_progmem_rtttl(&TEXT) }
+    }};
+}
+
+#[derive(Clone, Copy)]
+struct Sequencer {
+    pin: u8,
+    melody: Melody,
+    looping: bool,
+    /// Silence still to play after the current note
+    gap_ms: u32,
+}
+
+static SEQUENCER: IrqCell<Option<Sequencer>> = IrqCell::new(None);
+
+/// Play a melody once on a pin in the background
+///
+/// Replaces the melody that is playing and any tone on the pin. Use `is_melody_playing()` to
+/// check when it has finished.
+///
+/// Returns false if nothing is playing: the pin is invalid, the melody is
+/// empty or no tone timer is free (servos, PCM playback, a stepper, a crystal
+/// `SoftRtc` or PWM can hold all three).
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{is_melody_playing, play_melody, rtttl};
+///
+/// play_melody(11, rtttl!("Beep:d=8,o=5,b=160:c,e,g,4c6").unwrap());
+/// while is_melody_playing() {
+///     // Free to do other work
+/// }
+/// ```
+pub fn play_melody(pin: u8, melody: Melody) -> bool {
+    start(pin, melody, false)
+}
+
+/// Play a melody on a pin in the background, repeating until stopped
+///
+/// Returns false if nothing is playing, like [`play_melody`].
+pub fn loop_melody(pin: u8, melody: Melody) -> bool {
+    start(pin, melody, true)
+}
+
+fn start(pin: u8, melody: Melody, looping: bool) -> bool {
+    if pin > 13 {
+        return false;
+    }
+
+    stop_melody();
+    tone::stop(pin);
+    SEQUENCER.set(Some(Sequencer { pin, melody, looping, gap_ms: 0 }));
+    let started = advance(pin);
+    if !started {
+        tone::stop(pin);
+    }
+
+    unsafe {
+        core::arch::asm!("sei");
+    }
+    started
+}
+
+/// Stop the melody that is playing, if any
+pub fn stop_melody() {
+    if let Some(sequencer) = SEQUENCER.replace(None) {
+        tone::stop(sequencer.pin);
+    }
+}
+
+/// Check if a melody is playing
+pub fn is_melody_playing() -> bool {
+    SEQUENCER.lock(|sequencer| sequencer.is_some())
+}
+
+/// Change the tempo of the playing melody, taking effect from the next note
+pub fn set_melody_tempo(bpm: u16) {
+    SEQUENCER.lock(|sequencer| {
+        if let Some(sequencer) = sequencer {
+            sequencer.melody.tempo = bpm.max(1);
+        }
+    });
+}
+
+/// Forget the melody playing on `pin` without touching the tone output
+pub(crate) fn cancel(pin: u8) {
+    SEQUENCER.lock(|sequencer| {
+        if sequencer.is_some_and(|sequencer| sequencer.pin == pin) {
+            *sequencer = None;
+        }
+    });
+}
+
+/// Start the next note or gap of the melody
+///
+/// Called from the tone ISR when the previous one ends on `pin`. Returns
+/// false when there is nothing left to play there, or no timer is free to
+/// play it; the melody is then over.
+pub(crate) fn advance(pin: u8) -> bool {
+    SEQUENCER.lock(|slot| {
+        let Some(sequencer) = slot.as_mut().filter(|sequencer| sequencer.pin == pin) else {
+            return false;
+        };
+
+        if sequencer.gap_ms > 0 {
+            let gap_ms = core::mem::take(&mut sequencer.gap_ms);
+            let started = tone::start(pin, SILENT_FREQUENCY, tone::toggles_for(SILENT_FREQUENCY, gap_ms), true, None);
+            if !started {
+                *slot = None;
+            }
+            return started;
+        }
+
+        let mut note = sequencer.melody.next_note();
+        if note.is_none() && sequencer.looping {
+            sequencer.melody.rewind();
+            note = sequencer.melody.next_note();
+        }
+        let Some(note) = note else {
+            *slot = None;
+            return false;
+        };
+
+        let duration_ms = note.duration_ms(sequencer.melody.tempo).max(1);
+        let gap_ms = if note.is_rest() { 0 } else { duration_ms / 8 };
+        let sound_ms = duration_ms - gap_ms;
+        sequencer.gap_ms = gap_ms;
+
+        // Out of range frequencies play as rests
+        let started = tone::start(pin, note.frequency, tone::toggles_for(note.frequency, sound_ms), false, None)
+            || tone::start(pin, SILENT_FREQUENCY, tone::toggles_for(SILENT_FREQUENCY, sound_ms), true, None);
+        if !started {
+            // No timer is free: end the melody instead of leaving it playing forever
+            *slot = None;
+        }
+        started
+    })
+}
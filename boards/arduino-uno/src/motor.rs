@@ -0,0 +1,214 @@
+//! DC motor control through an H-bridge
+//!
+//! [`Motor`] drives a brushed DC motor with a signed speed from -255 (full
+//! reverse) to 255 (full forward), and can let it coast or brake it. Two
+//! wiring styles cover the common driver boards:
+//!
+//! - [`Motor::pwm_dir`]: one PWM pin for speed plus two direction pins, as
+//!   on the L298N (ENA, IN1, IN2) and TB6612FNG (PWMA, AIN1, AIN2)
+//! - [`Motor::dual_pwm`]: two PWM pins, one per bridge input, as on the
+//!   DRV8833 (AIN1, AIN2)
+//!
+//! Speed pins come from `into_pwm()`, so they must be one of the hardware
+//! PWM pins D3, D5, D6, D9, D10 or D11. `PwmFrequency::Freq31kHz` keeps the
+//! PWM above the audible range, which stops the motor from whining. D5 and D6
+//! share Timer0 with `millis()`, so only use them with `PwmFrequency::Freq980Hz`.
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+/// Highest speed magnitude
+pub const MOTOR_MAX_SPEED: i16 = 255;
+
+/// How the current decays during the off part of a PWM period
+///
+/// Only the DRV8833 style wiring can choose; the L298N always decays fast and
+/// the TB6612FNG always decays slowly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecayMode {
+    /// Let the motor coast between pulses, speed drops off at low duty cycles
+    #[default]
+    Fast,
+    /// Brake the motor between pulses, speed is more linear in the duty cycle
+    Slow,
+}
+
+/// Bridge wiring with one PWM pin and two direction pins (L298N, TB6612FNG)
+pub struct PwmDir<P, A, B> {
+    pwm: P,
+    in1: A,
+    in2: B,
+}
+
+/// Bridge wiring with a PWM pin on each input (DRV8833)
+pub struct DualPwm<A, B> {
+    in1: A,
+    in2: B,
+    decay: DecayMode,
+}
+
+/// The low-level operations of an H-bridge wiring
+pub trait HBridge {
+    /// Drive with a duty cycle of `duty`/255 in the given direction
+    fn drive(&mut self, forward: bool, duty: u8);
+
+    /// Disconnect the motor so it spins freely
+    fn coast(&mut self);
+
+    /// Short the motor terminals so it stops quickly
+    fn brake(&mut self);
+}
+
+/// Set a duty cycle out of 255, ignoring errors from infallible pins
+fn set_duty<P: SetDutyCycle>(pin: &mut P, duty: u8) {
+    let _ = pin.set_duty_cycle_fraction(duty as u16, 255);
+}
+
+/// Set a direction pin, ignoring errors from infallible pins
+fn set_level<P: OutputPin>(pin: &mut P, high: bool) {
+    let _ = if high { pin.set_high() } else { pin.set_low() };
+}
+
+impl<P: SetDutyCycle, A: OutputPin, B: OutputPin> HBridge for PwmDir<P, A, B> {
+    fn drive(&mut self, forward: bool, duty: u8) {
+        set_level(&mut self.in1, forward);
+        set_level(&mut self.in2, !forward);
+        set_duty(&mut self.pwm, duty);
+    }
+
+    fn coast(&mut self) {
+        // Both drivers disconnect the motor with both inputs low
+        set_duty(&mut self.pwm, 0);
+        set_level(&mut self.in1, false);
+        set_level(&mut self.in2, false);
+    }
+
+    fn brake(&mut self) {
+        set_level(&mut self.in1, true);
+        set_level(&mut self.in2, true);
+        set_duty(&mut self.pwm, 255);
+    }
+}
+
+impl<A: SetDutyCycle, B: SetDutyCycle> HBridge for DualPwm<A, B> {
+    fn drive(&mut self, forward: bool, duty: u8) {
+        let (driven, other) = match self.decay {
+            // Driven input pulses, other input low: coast between pulses
+            DecayMode::Fast => (duty, 0),
+            // Driven input high, other input pulses high: brake between pulses
+            DecayMode::Slow => (255, 255 - duty),
+        };
+        let (in1, in2) = if forward { (driven, other) } else { (other, driven) };
+        set_duty(&mut self.in1, in1);
+        set_duty(&mut self.in2, in2);
+    }
+
+    fn coast(&mut self) {
+        set_duty(&mut self.in1, 0);
+        set_duty(&mut self.in2, 0);
+    }
+
+    fn brake(&mut self) {
+        set_duty(&mut self.in1, 255);
+        set_duty(&mut self.in2, 255);
+    }
+}
+
+/// Brushed DC motor on an H-bridge
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{Motor, Peripherals, PwmFrequency};
+///
+/// let peripherals = Peripherals::take().unwrap();
+/// let pins = peripherals.pins;
+///
+/// // TB6612FNG: PWMA on D9, AIN1 on D7, AIN2 on D8
+/// let mut motor = Motor::pwm_dir(
+///     pins.d9.into_output().into_pwm(PwmFrequency::Freq31kHz),
+///     pins.d7.into_output(),
+///     pins.d8.into_output(),
+/// );
+///
+/// motor.set_speed(200);   // Forward at ~80%
+/// motor.set_speed(-100);  // Reverse at ~40%
+/// motor.brake();
+/// ```
+pub struct Motor<H: HBridge> {
+    bridge: H,
+    speed: i16,
+    inverted: bool,
+}
+
+impl<P: SetDutyCycle, A: OutputPin, B: OutputPin> Motor<PwmDir<P, A, B>> {
+    /// Create a motor on a bridge with a PWM enable pin and two direction pins
+    ///
+    /// The motor starts coasting.
+    pub fn pwm_dir(pwm: P, in1: A, in2: B) -> Self {
+        Self::new(PwmDir { pwm, in1, in2 })
+    }
+}
+
+impl<A: SetDutyCycle, B: SetDutyCycle> Motor<DualPwm<A, B>> {
+    /// Create a motor on a bridge driven by two PWM inputs
+    ///
+    /// The motor starts coasting, using fast decay.
+    pub fn dual_pwm(in1: A, in2: B) -> Self {
+        Self::new(DualPwm { in1, in2, decay: DecayMode::Fast })
+    }
+
+    /// Choose how the current decays between PWM pulses
+    pub fn set_decay_mode(&mut self, decay: DecayMode) {
+        self.bridge.decay = decay;
+        self.set_speed(self.speed);
+    }
+}
+
+impl<H: HBridge> Motor<H> {
+    /// Create a motor on any H-bridge wiring
+    ///
+    /// The motor starts coasting.
+    pub fn new(mut bridge: H) -> Self {
+        bridge.coast();
+        Self { bridge, speed: 0, inverted: false }
+    }
+
+    /// Swap forward and reverse, e.g. for the motor on the other side of a robot
+    pub fn with_inverted_direction(mut self) -> Self {
+        self.inverted = true;
+        self.set_speed(self.speed);
+        self
+    }
+
+    /// Drive at a signed speed from -255 to 255
+    ///
+    /// Positive speeds turn forward, negative speeds in reverse. Out of range
+    /// values are clamped. A speed of 0 lets the motor coast.
+    pub fn set_speed(&mut self, speed: i16) {
+        let speed = speed.clamp(-MOTOR_MAX_SPEED, MOTOR_MAX_SPEED);
+        self.speed = speed;
+        if speed == 0 {
+            self.bridge.coast();
+        } else {
+            let forward = (speed > 0) != self.inverted;
+            self.bridge.drive(forward, speed.unsigned_abs() as u8);
+        }
+    }
+
+    /// The last speed set, 0 after `coast()` or `brake()`
+    pub fn speed(&self) -> i16 {
+        self.speed
+    }
+
+    /// Cut the power and let the motor spin down freely
+    pub fn coast(&mut self) {
+        self.speed = 0;
+        self.bridge.coast();
+    }
+
+    /// Short the motor terminals to stop it quickly and hold it
+    pub fn brake(&mut self) {
+        self.speed = 0;
+        self.bridge.brake();
+    }
+}
@@ -0,0 +1,612 @@
+//! SSD1306/SH1106 128x64 monochrome OLED driver over I2C or SPI
+//!
+//! A full frame buffer would take half of the Uno's 2 KB of RAM, so this driver
+//! offers two buffer-free ways to draw:
+//!
+//! - **Page-buffered graphics**: [`Oled::draw`] renders a scene once per
+//!   8-pixel-high page into a 128-byte [`PageBuffer`], which implements
+//!   `embedded_graphics_core::draw_target::DrawTarget`.
+//! - **Text terminal**: [`Oled::write_str`] writes characters from a built-in
+//!   5x7 font stored in flash straight to the display (21 columns x 8 rows).
+//!
+//! Both controllers are driven in page addressing mode. The SH1106 has 132
+//! columns of RAM with the visible 128 starting at column 2.
+//!
+//! Hardware connections (I2C): SDA -> A4, SCL -> A5, address 0x3C or 0x3D.
+//! Hardware connections (SPI): MOSI -> D11, SCK -> D13, plus any two pins for
+//! DC and CS; CS belongs to the SPI device, e.g. from
+//! [`SharedSpi::device`](crate::SharedSpi::device). Drive the RESET pin (if
+//! present) low briefly before `init()`.
+
+use core::convert::Infallible;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::Pixel;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::I2c as I2cBus;
+use embedded_hal::spi::{Error as _, ErrorKind as SpiErrorKind, SpiDevice};
+use ufmt::uWrite;
+use crate::i2c::{I2c, I2cError};
+use crate::progmem::pgm_read_byte;
+use crate::FlashString;
+
+/// Display width in pixels
+pub const OLED_WIDTH: usize = 128;
+/// Display height in pixels
+pub const OLED_HEIGHT: usize = 64;
+const PAGES: u8 = (OLED_HEIGHT / 8) as u8;
+
+// Text terminal geometry (5x7 glyph plus one column of spacing)
+const GLYPH_WIDTH: usize = 5;
+const CELL_WIDTH: u8 = GLYPH_WIDTH as u8 + 1;
+const TEXT_COLUMNS: u8 = OLED_WIDTH as u8 / CELL_WIDTH;
+
+// Fundamental commands
+const SET_CONTRAST: u8 = 0x81;
+const DISPLAY_RESUME: u8 = 0xA4;
+const NORMAL_DISPLAY: u8 = 0xA6;
+const INVERT_DISPLAY: u8 = 0xA7;
+const DISPLAY_OFF: u8 = 0xAE;
+const DISPLAY_ON: u8 = 0xAF;
+
+// Addressing commands
+const MEMORY_MODE: u8 = 0x20;   // SSD1306 only
+const PAGE_ADDRESSING: u8 = 0x02;
+const SET_PAGE: u8 = 0xB0;
+const SET_LOW_COLUMN: u8 = 0x00;
+const SET_HIGH_COLUMN: u8 = 0x10;
+
+// Hardware configuration commands
+const SET_START_LINE: u8 = 0x40;
+const SEG_REMAP: u8 = 0xA1;     // Column 127 mapped to SEG0
+const SET_MULTIPLEX: u8 = 0xA8;
+const COM_SCAN_DEC: u8 = 0xC8;
+const SET_DISPLAY_OFFSET: u8 = 0xD3;
+const SET_COM_PINS: u8 = 0xDA;
+
+// Timing and driving commands
+const SET_CLOCK_DIV: u8 = 0xD5;
+const SET_PRECHARGE: u8 = 0xD9;
+const SET_VCOM_DETECT: u8 = 0xDB;
+const SSD1306_CHARGE_PUMP: u8 = 0x8D;
+const SH1106_DC_DC: u8 = 0xAD;
+
+// I2C control bytes (Co = 0, D/C# selects command or data)
+const I2C_COMMAND: u8 = 0x00;
+const I2C_DATA: u8 = 0x40;
+// Payload bytes per I2C transfer
+const I2C_CHUNK: usize = 16;
+
+/// Default I2C address of most 128x64 modules (0x3D if the address jumper is set)
+pub const OLED_I2C_ADDRESS: u8 = 0x3C;
+
+/// OLED controller type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OledController {
+    /// SSD1306 (128 columns, internal charge pump)
+    Ssd1306,
+    /// SH1106 (132 columns, visible area starts at column 2)
+    Sh1106,
+}
+
+impl OledController {
+    /// RAM column of the first visible pixel
+    const fn column_offset(self) -> u8 {
+        match self {
+            OledController::Ssd1306 => 0,
+            OledController::Sh1106 => 2,
+        }
+    }
+}
+
+/// OLED error types
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OledError {
+    /// I2C transfer failed
+    I2c(I2cError),
+    /// SPI transfer failed, including its chip select
+    Spi(SpiErrorKind),
+    /// Setting the DC pin failed
+    Pin,
+}
+
+impl From<I2cError> for OledError {
+    fn from(error: I2cError) -> Self {
+        OledError::I2c(error)
+    }
+}
+
+/// Bus used to send commands and display data to the controller
+pub trait OledInterface {
+    /// Send a sequence of command bytes
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError>;
+
+    /// Send display RAM data at the current position
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError>;
+}
+
+/// I2C bus interface
+///
+/// Works on the on-chip [`I2c`] or on a proxy from [`SharedI2c`](crate::SharedI2c).
+pub struct OledI2c<I2C = I2c> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C: I2cBus> OledI2c<I2C> {
+    /// Create an I2C interface for the module at `address` (usually [`OLED_I2C_ADDRESS`])
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Send bytes in chunks, each prefixed with the control byte
+    fn send(&mut self, control: u8, bytes: &[u8]) -> Result<(), OledError> {
+        let mut buffer = [0u8; I2C_CHUNK + 1];
+        buffer[0] = control;
+        for chunk in bytes.chunks(I2C_CHUNK) {
+            buffer[1..=chunk.len()].copy_from_slice(chunk);
+            self.i2c.write(self.address, &buffer[..=chunk.len()]).map_err(I2cError::from_bus)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C: I2cBus> OledInterface for OledI2c<I2C> {
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        self.send(I2C_COMMAND, commands)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.send(I2C_DATA, data)
+    }
+}
+
+/// 4-wire SPI bus interface (SPI device with chip select, plus a data/command pin)
+///
+/// Works on any `embedded_hal::spi::SpiDevice`, such as a proxy from
+/// [`SharedSpi`](crate::SharedSpi). The device selects the display for each
+/// transfer and deselects it again on every path, errors included.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{Oled, OledController, OledSpi, SharedSpi, Spi, SpiSettings};
+///
+/// let peripherals = arduino_uno::Peripherals::take().unwrap();
+/// let dc = peripherals.pins.d9.into_output();
+/// let cs = peripherals.pins.d10.into_output();
+///
+/// let bus = SharedSpi::new(Spi::new());
+/// let interface = OledSpi::new(bus.device(cs, SpiSettings::default()), dc);
+/// let mut oled = Oled::new(interface, OledController::Ssd1306);
+/// ```
+pub struct OledSpi<SPI, DC> {
+    spi: SPI,
+    dc: DC,
+}
+
+impl<SPI: SpiDevice, DC: OutputPin> OledSpi<SPI, DC> {
+    /// Create an SPI interface using `dc` for data/command
+    pub fn new(spi: SPI, dc: DC) -> Self {
+        Self { spi, dc }
+    }
+
+    /// Release the SPI device and DC pin
+    pub fn release(self) -> (SPI, DC) {
+        (self.spi, self.dc)
+    }
+
+    fn send(&mut self, data_mode: bool, bytes: &[u8]) -> Result<(), OledError> {
+        if data_mode {
+            self.dc.set_high().map_err(|_| OledError::Pin)?;
+        } else {
+            self.dc.set_low().map_err(|_| OledError::Pin)?;
+        }
+
+        self.spi.write(bytes).map_err(|error| OledError::Spi(error.kind()))
+    }
+}
+
+impl<SPI: SpiDevice, DC: OutputPin> OledInterface for OledSpi<SPI, DC> {
+    fn send_commands(&mut self, commands: &[u8]) -> Result<(), OledError> {
+        self.send(false, commands)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), OledError> {
+        self.send(true, data)
+    }
+}
+
+/// One 128x8 pixel page of the display, used as an `embedded-graphics` target
+///
+/// The draw target covers the whole 128x64 display; pixels outside the page
+/// being rendered are discarded. See [`Oled::draw`].
+pub struct PageBuffer {
+    data: [u8; OLED_WIDTH],
+    page: u8,
+}
+
+impl PageBuffer {
+    const fn new() -> Self {
+        Self { data: [0; OLED_WIDTH], page: 0 }
+    }
+
+    /// Index of the page being rendered (0-7, top to bottom)
+    pub fn page(&self) -> u8 {
+        self.page
+    }
+
+    /// Range of display rows covered by this page
+    pub fn rows(&self) -> core::ops::Range<i32> {
+        let top = self.page as i32 * 8;
+        top..top + 8
+    }
+
+    /// Set or clear a pixel in display coordinates
+    ///
+    /// Pixels outside this page are ignored.
+    pub fn set_pixel(&mut self, x: i32, y: i32, on: bool) {
+        if !(0..OLED_WIDTH as i32).contains(&x) || !self.rows().contains(&y) {
+            return;
+        }
+        let bit = 1 << (y - self.page as i32 * 8);
+        if on {
+            self.data[x as usize] |= bit;
+        } else {
+            self.data[x as usize] &= !bit;
+        }
+    }
+}
+
+impl OriginDimensions for PageBuffer {
+    fn size(&self) -> Size {
+        Size::new(OLED_WIDTH as u32, OLED_HEIGHT as u32)
+    }
+}
+
+impl DrawTarget for PageBuffer {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.set_pixel(point.x, point.y, color.is_on());
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.data = [if color.is_on() { 0xFF } else { 0x00 }; OLED_WIDTH];
+        Ok(())
+    }
+}
+
+/// SSD1306/SH1106 OLED display
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{I2c, Oled, OledController, OledI2c, OLED_I2C_ADDRESS};
+/// use embedded_graphics_core::prelude::*;
+/// use embedded_graphics_core::pixelcolor::BinaryColor;
+/// use embedded_graphics_core::primitives::Rectangle;
+///
+/// let interface = OledI2c::new(I2c::new(), OLED_I2C_ADDRESS);
+/// let mut oled = Oled::new(interface, OledController::Ssd1306);
+/// oled.init().unwrap();
+///
+/// // Text terminal
+/// oled.write_str("Hello!\n").unwrap();
+///
+/// // Page-buffered graphics
+/// oled.draw(|page| {
+///     let frame = Rectangle::new(Point::new(0, 0), Size::new(128, 64));
+///     page.fill_solid(&frame, BinaryColor::On).ok();
+/// }).unwrap();
+/// ```
+pub struct Oled<DI> {
+    interface: DI,
+    controller: OledController,
+    column: u8,
+    row: u8,
+}
+
+impl<DI: OledInterface> Oled<DI> {
+    /// Create a new display driver
+    ///
+    /// Call `init()` before drawing.
+    pub fn new(interface: DI, controller: OledController) -> Self {
+        Self { interface, controller, column: 0, row: 0 }
+    }
+
+    /// Release the bus interface
+    pub fn release(self) -> DI {
+        self.interface
+    }
+
+    /// Initialize the controller for a 128x64 panel, clear it and turn it on
+    pub fn init(&mut self) -> Result<(), OledError> {
+        self.interface.send_commands(&[
+            DISPLAY_OFF,
+            SET_CLOCK_DIV, 0x80,
+            SET_MULTIPLEX, (OLED_HEIGHT - 1) as u8,
+            SET_DISPLAY_OFFSET, 0x00,
+            SET_START_LINE,
+            SEG_REMAP,
+            COM_SCAN_DEC,
+            SET_COM_PINS, 0x12,
+            SET_CONTRAST, 0xCF,
+            SET_PRECHARGE, 0xF1,
+            SET_VCOM_DETECT, 0x40,
+            DISPLAY_RESUME,
+            NORMAL_DISPLAY,
+        ])?;
+
+        match self.controller {
+            OledController::Ssd1306 => self.interface.send_commands(&[
+                SSD1306_CHARGE_PUMP, 0x14,
+                MEMORY_MODE, PAGE_ADDRESSING,
+            ])?,
+            OledController::Sh1106 => self.interface.send_commands(&[SH1106_DC_DC, 0x8B])?,
+        }
+
+        self.clear()?;
+        self.interface.send_commands(&[DISPLAY_ON])
+    }
+
+    /// Turn the display on or off (display RAM is kept)
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), OledError> {
+        self.interface.send_commands(&[if on { DISPLAY_ON } else { DISPLAY_OFF }])
+    }
+
+    /// Set the contrast (0-255)
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), OledError> {
+        self.interface.send_commands(&[SET_CONTRAST, contrast])
+    }
+
+    /// Invert all pixels
+    pub fn set_inverted(&mut self, inverted: bool) -> Result<(), OledError> {
+        self.interface.send_commands(&[if inverted { INVERT_DISPLAY } else { NORMAL_DISPLAY }])
+    }
+
+    /// Clear the display and move the text cursor home
+    pub fn clear(&mut self) -> Result<(), OledError> {
+        for page in 0..PAGES {
+            self.fill_page(page, 0)?;
+        }
+        self.column = 0;
+        self.row = 0;
+        Ok(())
+    }
+
+    /// Move the RAM write position to a page and visible column
+    fn set_position(&mut self, page: u8, column: u8) -> Result<(), OledError> {
+        let column = column + self.controller.column_offset();
+        self.interface.send_commands(&[
+            SET_PAGE | page,
+            SET_LOW_COLUMN | (column & 0x0F),
+            SET_HIGH_COLUMN | (column >> 4),
+        ])
+    }
+
+    fn fill_page(&mut self, page: u8, pattern: u8) -> Result<(), OledError> {
+        self.set_position(page, 0)?;
+        let chunk = [pattern; I2C_CHUNK];
+        for _ in 0..OLED_WIDTH / I2C_CHUNK {
+            self.interface.send_data(&chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Write one full page (8 pixel rows, LSB at the top)
+    pub fn write_page(&mut self, page: u8, data: &[u8; OLED_WIDTH]) -> Result<(), OledError> {
+        self.set_position(page % PAGES, 0)?;
+        self.interface.send_data(data)
+    }
+
+    /// Render a scene page by page
+    ///
+    /// `scene` is called once for each of the 8 pages with a cleared
+    /// [`PageBuffer`] and should draw the whole scene in display coordinates;
+    /// only the pixels within the current page are kept. The scene must draw
+    /// the same thing on every call.
+    pub fn draw<F: FnMut(&mut PageBuffer)>(&mut self, mut scene: F) -> Result<(), OledError> {
+        let mut buffer = PageBuffer::new();
+        for page in 0..PAGES {
+            buffer.page = page;
+            buffer.data = [0; OLED_WIDTH];
+            scene(&mut buffer);
+            self.write_page(page, &buffer.data)?;
+        }
+        Ok(())
+    }
+
+    // ===== Text terminal =====
+
+    /// Move the text cursor (21 columns x 8 rows)
+    pub fn set_cursor(&mut self, column: u8, row: u8) {
+        self.column = column.min(TEXT_COLUMNS - 1);
+        self.row = row % PAGES;
+    }
+
+    /// Current text cursor as (column, row)
+    pub fn cursor(&self) -> (u8, u8) {
+        (self.column, self.row)
+    }
+
+    /// Write a character at the cursor
+    ///
+    /// `\n` moves to the start of the next row and clears it; output wraps
+    /// from the last row back to the top. Characters outside printable ASCII
+    /// are shown as `?`.
+    pub fn write_char(&mut self, c: char) -> Result<(), OledError> {
+        match c {
+            '\n' => return self.new_line(),
+            '\r' => {
+                self.column = 0;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if self.column >= TEXT_COLUMNS {
+            self.new_line()?;
+        }
+
+        let mut cell = [0u8; CELL_WIDTH as usize];
+        cell[..GLYPH_WIDTH].copy_from_slice(&glyph(c));
+        self.set_position(self.row, self.column * CELL_WIDTH)?;
+        self.interface.send_data(&cell)?;
+        self.column += 1;
+        Ok(())
+    }
+
+    /// Write a string at the cursor
+    pub fn write_str(&mut self, s: &str) -> Result<(), OledError> {
+        for c in s.chars() {
+            self.write_char(c)?;
+        }
+        Ok(())
+    }
+
+    /// Write a string stored in flash (see `F!`)
+    pub fn write_flash_str(&mut self, flash_str: &FlashString) -> Result<(), OledError> {
+        for byte in flash_str.bytes() {
+            self.write_char(byte as char)?;
+        }
+        Ok(())
+    }
+
+    fn new_line(&mut self) -> Result<(), OledError> {
+        self.column = 0;
+        self.row = (self.row + 1) % PAGES;
+        self.fill_page(self.row, 0)
+    }
+}
+
+impl<DI: OledInterface> uWrite for Oled<DI> {
+    type Error = OledError;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        Oled::write_str(self, s)
+    }
+}
+
+/// Font columns for a character, read from flash
+fn glyph(c: char) -> [u8; GLYPH_WIDTH] {
+    let code = if (' '..='~').contains(&c) { c as usize } else { '?' as usize };
+    let offset = (code - ' ' as usize) * GLYPH_WIDTH;
+
+    let mut columns = [0u8; GLYPH_WIDTH];
+    for (i, column) in columns.iter_mut().enumerate() {
+        *column = unsafe { pgm_read_byte(FONT_5X7.as_ptr().add(offset + i)) };
+    }
+    columns
+}
+
+/// 5x7 font for printable ASCII (0x20-0x7E), one byte per column, LSB at the top
+#[link_section = ".progmem.data"]
+static FONT_5X7: [u8; 95 * GLYPH_WIDTH] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, // ' '
+    0x00, 0x00, 0x5F, 0x00, 0x00, // '!'
+    0x00, 0x07, 0x00, 0x07, 0x00, // '"'
+    0x14, 0x7F, 0x14, 0x7F, 0x14, // '#'
+    0x24, 0x2A, 0x7F, 0x2A, 0x12, // '$'
+    0x23, 0x13, 0x08, 0x64, 0x62, // '%'
+    0x36, 0x49, 0x55, 0x22, 0x50, // '&'
+    0x00, 0x05, 0x03, 0x00, 0x00, // '''
+    0x00, 0x1C, 0x22, 0x41, 0x00, // '('
+    0x00, 0x41, 0x22, 0x1C, 0x00, // ')'
+    0x08, 0x2A, 0x1C, 0x2A, 0x08, // '*'
+    0x08, 0x08, 0x3E, 0x08, 0x08, // '+'
+    0x00, 0x50, 0x30, 0x00, 0x00, // ','
+    0x08, 0x08, 0x08, 0x08, 0x08, // '-'
+    0x00, 0x60, 0x60, 0x00, 0x00, // '.'
+    0x20, 0x10, 0x08, 0x04, 0x02, // '/'
+    0x3E, 0x51, 0x49, 0x45, 0x3E, // '0'
+    0x00, 0x42, 0x7F, 0x40, 0x00, // '1'
+    0x42, 0x61, 0x51, 0x49, 0x46, // '2'
+    0x21, 0x41, 0x45, 0x4B, 0x31, // '3'
+    0x18, 0x14, 0x12, 0x7F, 0x10, // '4'
+    0x27, 0x45, 0x45, 0x45, 0x39, // '5'
+    0x3C, 0x4A, 0x49, 0x49, 0x30, // '6'
+    0x01, 0x71, 0x09, 0x05, 0x03, // '7'
+    0x36, 0x49, 0x49, 0x49, 0x36, // '8'
+    0x06, 0x49, 0x49, 0x29, 0x1E, // '9'
+    0x00, 0x36, 0x36, 0x00, 0x00, // ':'
+    0x00, 0x56, 0x36, 0x00, 0x00, // ';'
+    0x08, 0x14, 0x22, 0x41, 0x00, // '<'
+    0x14, 0x14, 0x14, 0x14, 0x14, // '='
+    0x00, 0x41, 0x22, 0x14, 0x08, // '>'
+    0x02, 0x01, 0x51, 0x09, 0x06, // '?'
+    0x32, 0x49, 0x79, 0x41, 0x3E, // '@'
+    0x7E, 0x11, 0x11, 0x11, 0x7E, // 'A'
+    0x7F, 0x49, 0x49, 0x49, 0x36, // 'B'
+    0x3E, 0x41, 0x41, 0x41, 0x22, // 'C'
+    0x7F, 0x41, 0x41, 0x22, 0x1C, // 'D'
+    0x7F, 0x49, 0x49, 0x49, 0x41, // 'E'
+    0x7F, 0x09, 0x09, 0x09, 0x01, // 'F'
+    0x3E, 0x41, 0x49, 0x49, 0x7A, // 'G'
+    0x7F, 0x08, 0x08, 0x08, 0x7F, // 'H'
+    0x00, 0x41, 0x7F, 0x41, 0x00, // 'I'
+    0x20, 0x40, 0x41, 0x3F, 0x01, // 'J'
+    0x7F, 0x08, 0x14, 0x22, 0x41, // 'K'
+    0x7F, 0x40, 0x40, 0x40, 0x40, // 'L'
+    0x7F, 0x02, 0x0C, 0x02, 0x7F, // 'M'
+    0x7F, 0x04, 0x08, 0x10, 0x7F, // 'N'
+    0x3E, 0x41, 0x41, 0x41, 0x3E, // 'O'
+    0x7F, 0x09, 0x09, 0x09, 0x06, // 'P'
+    0x3E, 0x41, 0x51, 0x21, 0x5E, // 'Q'
+    0x7F, 0x09, 0x19, 0x29, 0x46, // 'R'
+    0x46, 0x49, 0x49, 0x49, 0x31, // 'S'
+    0x01, 0x01, 0x7F, 0x01, 0x01, // 'T'
+    0x3F, 0x40, 0x40, 0x40, 0x3F, // 'U'
+    0x1F, 0x20, 0x40, 0x20, 0x1F, // 'V'
+    0x3F, 0x40, 0x38, 0x40, 0x3F, // 'W'
+    0x63, 0x14, 0x08, 0x14, 0x63, // 'X'
+    0x07, 0x08, 0x70, 0x08, 0x07, // 'Y'
+    0x61, 0x51, 0x49, 0x45, 0x43, // 'Z'
+    0x00, 0x7F, 0x41, 0x41, 0x00, // '['
+    0x02, 0x04, 0x08, 0x10, 0x20, // '\'
+    0x00, 0x41, 0x41, 0x7F, 0x00, // ']'
+    0x04, 0x02, 0x01, 0x02, 0x04, // '^'
+    0x40, 0x40, 0x40, 0x40, 0x40, // '_'
+    0x00, 0x01, 0x02, 0x04, 0x00, // '`'
+    0x20, 0x54, 0x54, 0x54, 0x78, // 'a'
+    0x7F, 0x48, 0x44, 0x44, 0x38, // 'b'
+    0x38, 0x44, 0x44, 0x44, 0x20, // 'c'
+    0x38, 0x44, 0x44, 0x48, 0x7F, // 'd'
+    0x38, 0x54, 0x54, 0x54, 0x18, // 'e'
+    0x08, 0x7E, 0x09, 0x01, 0x02, // 'f'
+    0x0C, 0x52, 0x52, 0x52, 0x3E, // 'g'
+    0x7F, 0x08, 0x04, 0x04, 0x78, // 'h'
+    0x00, 0x44, 0x7D, 0x40, 0x00, // 'i'
+    0x20, 0x40, 0x44, 0x3D, 0x00, // 'j'
+    0x7F, 0x10, 0x28, 0x44, 0x00, // 'k'
+    0x00, 0x41, 0x7F, 0x40, 0x00, // 'l'
+    0x7C, 0x04, 0x18, 0x04, 0x78, // 'm'
+    0x7C, 0x08, 0x04, 0x04, 0x78, // 'n'
+    0x38, 0x44, 0x44, 0x44, 0x38, // 'o'
+    0x7C, 0x14, 0x14, 0x14, 0x08, // 'p'
+    0x08, 0x14, 0x14, 0x18, 0x7C, // 'q'
+    0x7C, 0x08, 0x04, 0x04, 0x08, // 'r'
+    0x48, 0x54, 0x54, 0x54, 0x20, // 's'
+    0x04, 0x3F, 0x44, 0x40, 0x20, // 't'
+    0x3C, 0x40, 0x40, 0x20, 0x7C, // 'u'
+    0x1C, 0x20, 0x40, 0x20, 0x1C, // 'v'
+    0x3C, 0x40, 0x30, 0x40, 0x3C, // 'w'
+    0x44, 0x28, 0x10, 0x28, 0x44, // 'x'
+    0x0C, 0x50, 0x50, 0x50, 0x3C, // 'y'
+    0x44, 0x64, 0x54, 0x4C, 0x44, // 'z'
+    0x00, 0x08, 0x36, 0x41, 0x00, // '{'
+    0x00, 0x00, 0x7F, 0x00, 0x00, // '|'
+    0x00, 0x41, 0x36, 0x08, 0x00, // '}'
+    0x08, 0x04, 0x08, 0x10, 0x08, // '~'
+];
@@ -5,12 +5,16 @@
 //! - PCINT1 (PCINT8-14): Analog pins A0-A5 (Port C)
 //! - PCINT2 (PCINT16-23): Digital pins 0-7 (Port D)
 //!
-//! Pin Change Interrupts trigger on ANY change (rising or falling edge) on enabled pins.
-//! Unlike external interrupts (INT0/INT1), you cannot configure the trigger mode.
+//! The hardware triggers on ANY change (rising or falling edge) on enabled pins and
+//! only reports which bank fired. The ISRs keep a snapshot of each port's last state,
+//! compute which pins changed and in which direction, and dispatch per-pin handlers
+//! filtered by `InterruptMode` (Rising, Falling or Change).
 
 use core::ptr::{read_volatile, write_volatile};
 use core::cell::Cell;
 use critical_section::Mutex;
+use crate::interrupt::{Handler, InterruptMode};
+use crate::ports::{Port, port_input_register};
 
 // Pin Change Interrupt Control Register
 const PCICR: *mut u8 = 0x68 as *mut u8;
@@ -42,10 +46,30 @@ pub enum PcintBank {
 /// Type for PCINT handler functions
 type PcintHandler = fn();
 
+/// Per-pin handler with its trigger mode
+#[derive(Clone, Copy)]
+struct PinHandler {
+    mode: InterruptMode,
+    handler: Handler,
+}
+
 /// Storage for PCINT handlers (one per bank)
 static PCINT_HANDLERS: Mutex<Cell<[Option<PcintHandler>; 3]>> =
     Mutex::new(Cell::new([None, None, None]));
 
+/// Storage for per-pin handlers (8 bits per bank)
+static PIN_HANDLERS: Mutex<[Cell<Option<PinHandler>>; 24]> =
+    Mutex::new([const { Cell::new(None) }; 24]);
+
+/// Last sampled port state per bank, used to detect which pins changed
+static LAST_STATE: Mutex<[Cell<u8>; 3]> = Mutex::new([const { Cell::new(0) }; 3]);
+
+/// Pins that changed on the most recent interrupt per bank
+static LAST_CHANGED: Mutex<[Cell<u8>; 3]> = Mutex::new([const { Cell::new(0) }; 3]);
+
+/// RX pin of the listening SoftwareSerial instance, serviced before user handlers
+static SOFTWARE_SERIAL_RX: Mutex<Cell<Option<u8>>> = Mutex::new(Cell::new(None));
+
 /// Map an Arduino pin number to its PCINT bank and bit mask
 ///
 /// Returns (bank, pin_mask) where pin_mask is the bit to set in PCMSKx
@@ -67,11 +91,86 @@ fn get_pcmsk_register(bank: PcintBank) -> *mut u8 {
     }
 }
 
+/// Get the I/O port backing a given bank
+fn bank_port(bank: PcintBank) -> Port {
+    match bank {
+        PcintBank::Bank0 => Port::B,
+        PcintBank::Bank1 => Port::C,
+        PcintBank::Bank2 => Port::D,
+    }
+}
+
+/// Read the current input state of a bank's port
+fn read_bank(bank: PcintBank) -> u8 {
+    unsafe { read_volatile(port_input_register(bank_port(bank))) }
+}
+
+/// Take a fresh snapshot of a bank so the next interrupt only reports real changes
+fn snapshot_bank(cs: critical_section::CriticalSection, bank: PcintBank) {
+    LAST_STATE.borrow(cs)[bank as usize].set(read_bank(bank));
+}
+
+/// Bit of the SoftwareSerial RX pin within a bank, if it lives there
+fn software_serial_mask(cs: critical_section::CriticalSection, bank: PcintBank) -> u8 {
+    match SOFTWARE_SERIAL_RX.borrow(cs).get().and_then(pin_to_pcint) {
+        Some((rx_bank, bit)) if rx_bank == bank => 1 << bit,
+        _ => 0,
+    }
+}
+
+/// Enable the mask bit and bank for a pin without touching handlers
+pub(crate) fn enable_pin(pin: u8) {
+    if let Some((bank, bit)) = pin_to_pcint(pin) {
+        critical_section::with(|cs| {
+            snapshot_bank(cs, bank);
+
+            unsafe {
+                let pcmsk = get_pcmsk_register(bank);
+                write_volatile(pcmsk, read_volatile(pcmsk) | (1 << bit));
+
+                let current_pcicr = read_volatile(PCICR);
+                write_volatile(PCICR, current_pcicr | (1 << (bank as u8)));
+            }
+        });
+    }
+}
+
+/// Route changes on a pin to SoftwareSerial's receiver (None to release it)
+///
+/// The SoftwareSerial receiver runs before any user handler in the same bank,
+/// so user handlers on other pins can coexist with it.
+pub(crate) fn set_software_serial_rx(pin: Option<u8>) {
+    let previous = critical_section::with(|cs| SOFTWARE_SERIAL_RX.borrow(cs).replace(pin));
+
+    if let Some(old_pin) = previous {
+        if Some(old_pin) != pin && !has_pin_handler(old_pin) {
+            pcint_detach(old_pin);
+        }
+    }
+
+    if let Some(rx_pin) = pin {
+        enable_pin(rx_pin);
+    }
+}
+
+/// Check whether a user handler is attached to a pin
+fn has_pin_handler(pin: u8) -> bool {
+    match pin_to_pcint(pin) {
+        Some((bank, bit)) => critical_section::with(|cs| {
+            PIN_HANDLERS.borrow(cs)[bank as usize * 8 + bit as usize].get().is_some()
+        }),
+        None => false,
+    }
+}
+
 /// Enable Pin Change Interrupt on a specific pin
 ///
+/// The handler is called whenever this pin changes state (rising or falling edge).
+/// Use `pcint_attach_mode()` to only react to one edge.
+///
 /// # Arguments
 /// * `pin` - Arduino pin number (0-19)
-/// * `handler` - Function to call when any enabled pin in the bank changes
+/// * `handler` - Function to call when the pin changes
 ///
 /// # Safety
 /// The handler function must be interrupt-safe:
@@ -80,38 +179,78 @@ fn get_pcmsk_register(bank: PcintBank) -> *mut u8 {
 /// - Use volatile access for shared data
 /// - Avoid complex operations
 ///
-/// Note: All pins in the same bank share the same interrupt handler.
-/// The handler must check which pin(s) changed by reading the port.
-///
 /// # Example
 /// ```no_run
 /// use arduino_uno::pcint_attach;
 ///
 /// fn pin_changed() {
-///     // Handle pin change - read pins to determine which changed
+///     // Handle pin change
 /// }
 ///
 /// pcint_attach(2, pin_changed);  // Enable PCINT on pin 2
 /// ```
 pub fn pcint_attach(pin: u8, handler: PcintHandler) {
-    if let Some((bank, bit)) = pin_to_pcint(pin) {
-        critical_section::with(|cs| {
-            // Store the handler for this bank
-            let mut handlers = PCINT_HANDLERS.borrow(cs).get();
-            handlers[bank as usize] = Some(handler);
-            PCINT_HANDLERS.borrow(cs).set(handlers);
+    pcint_attach_mode(pin, InterruptMode::Change, handler);
+}
 
-            unsafe {
-                // Enable the pin in the mask register
-                let pcmsk = get_pcmsk_register(bank);
-                let current = read_volatile(pcmsk);
-                write_volatile(pcmsk, current | (1 << bit));
+/// Enable Pin Change Interrupt on a specific pin with edge filtering
+///
+/// Each pin has its own handler, even when several pins share a bank.
+///
+/// # Arguments
+/// * `pin` - Arduino pin number (0-19)
+/// * `mode` - `Rising`, `Falling` or `Change`. Pin change interrupts cannot
+///   detect levels, so `Low` behaves like `Falling`.
+/// * `handler` - Function to call when the selected edge occurs
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{pcint_attach_mode, InterruptMode};
+///
+/// fn button_released() {
+///     // Only called on LOW -> HIGH transitions of pin 8
+/// }
+///
+/// pcint_attach_mode(8, InterruptMode::Rising, button_released);
+/// ```
+pub fn pcint_attach_mode(pin: u8, mode: InterruptMode, handler: PcintHandler) {
+    attach_handler(pin, mode, Handler::Fn(handler));
+}
 
-                // Enable the PCINT bank in PCICR
-                let current_pcicr = read_volatile(PCICR);
-                write_volatile(PCICR, current_pcicr | (1 << (bank as u8)));
-            }
+/// Enable Pin Change Interrupt on a pin with a handler that receives a context
+///
+/// The pin change counterpart of `attach_interrupt_with()`.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{pcint_attach_with, InterruptMode};
+/// use core::cell::Cell;
+/// use critical_section::Mutex;
+///
+/// static PULSES: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+///
+/// fn count(pulses: &Mutex<Cell<u32>>) {
+///     critical_section::with(|cs| {
+///         let count = pulses.borrow(cs);
+///         count.set(count.get() + 1);
+///     });
+/// }
+///
+/// pcint_attach_with(14, InterruptMode::Rising, &PULSES, count);
+/// ```
+pub fn pcint_attach_with<T: Sync>(pin: u8, mode: InterruptMode, context: &'static T, handler: fn(&T)) {
+    attach_handler(pin, mode, Handler::context(context, handler));
+}
+
+/// Store a per-pin handler and enable the pin
+pub(crate) fn attach_handler(pin: u8, mode: InterruptMode, handler: Handler) {
+    if let Some((bank, bit)) = pin_to_pcint(pin) {
+        critical_section::with(|cs| {
+            PIN_HANDLERS.borrow(cs)[bank as usize * 8 + bit as usize]
+                .set(Some(PinHandler { mode, handler }));
         });
+
+        enable_pin(pin);
     }
 }
 
@@ -128,24 +267,35 @@ pub fn pcint_attach(pin: u8, handler: PcintHandler) {
 /// ```
 pub fn pcint_detach(pin: u8) {
     if let Some((bank, bit)) = pin_to_pcint(pin) {
-        unsafe {
-            // Disable the pin in the mask register
-            let pcmsk = get_pcmsk_register(bank);
-            let current = read_volatile(pcmsk);
-            write_volatile(pcmsk, current & !(1 << bit));
+        critical_section::with(|cs| {
+            PIN_HANDLERS.borrow(cs)[bank as usize * 8 + bit as usize].set(None);
 
-            // If no pins are enabled in this bank, disable the bank
-            if read_volatile(pcmsk) == 0 {
-                let current_pcicr = read_volatile(PCICR);
-                write_volatile(PCICR, current_pcicr & !(1 << (bank as u8)));
+            // Keep the pin enabled while SoftwareSerial is receiving on it
+            if software_serial_mask(cs, bank) & (1 << bit) != 0 {
+                return;
             }
-        }
+
+            unsafe {
+                // Disable the pin in the mask register
+                let pcmsk = get_pcmsk_register(bank);
+                let current = read_volatile(pcmsk);
+                write_volatile(pcmsk, current & !(1 << bit));
+
+                // If no pins are enabled in this bank, disable the bank
+                if read_volatile(pcmsk) == 0 {
+                    let current_pcicr = read_volatile(PCICR);
+                    write_volatile(PCICR, current_pcicr & !(1 << (bank as u8)));
+                }
+            }
+        });
     }
 }
 
 /// Enable all pins in a bank for Pin Change Interrupts
 ///
 /// This is useful when you want to monitor multiple pins in the same bank.
+/// The bank handler runs on every interrupt of the bank, after any per-pin
+/// handlers; `pcint_changed_pins()` tells it which pins changed.
 ///
 /// # Arguments
 /// * `bank` - Which bank to enable
@@ -170,10 +320,12 @@ pub fn pcint_enable_bank(bank: PcintBank, pin_mask: u8, handler: PcintHandler) {
         handlers[bank as usize] = Some(handler);
         PCINT_HANDLERS.borrow(cs).set(handlers);
 
+        snapshot_bank(cs, bank);
+
         unsafe {
-            // Set the mask register
+            // Set the mask register, keeping the SoftwareSerial RX pin if present
             let pcmsk = get_pcmsk_register(bank);
-            write_volatile(pcmsk, pin_mask);
+            write_volatile(pcmsk, pin_mask | software_serial_mask(cs, bank));
 
             // Enable the PCINT bank
             let current_pcicr = read_volatile(PCICR);
@@ -184,59 +336,152 @@ pub fn pcint_enable_bank(bank: PcintBank, pin_mask: u8, handler: PcintHandler) {
 
 /// Disable all pins in a bank
 ///
+/// A SoftwareSerial receiver listening on a pin of this bank keeps working.
+///
 /// # Arguments
 /// * `bank` - Which bank to disable
 pub fn pcint_disable_bank(bank: PcintBank) {
+    critical_section::with(|cs| {
+        // Remove bank and per-pin handlers
+        let mut handlers = PCINT_HANDLERS.borrow(cs).get();
+        handlers[bank as usize] = None;
+        PCINT_HANDLERS.borrow(cs).set(handlers);
+
+        for slot in &PIN_HANDLERS.borrow(cs)[bank as usize * 8..bank as usize * 8 + 8] {
+            slot.set(None);
+        }
+
+        let keep = software_serial_mask(cs, bank);
+
+        unsafe {
+            // Clear the mask register
+            let pcmsk = get_pcmsk_register(bank);
+            write_volatile(pcmsk, keep);
+
+            // Disable the PCINT bank
+            if keep == 0 {
+                let current_pcicr = read_volatile(PCICR);
+                write_volatile(PCICR, current_pcicr & !(1 << (bank as u8)));
+            }
+        }
+    });
+}
+
+/// Get the pins that changed on the most recent interrupt of a bank
+///
+/// Bit N corresponds to bit N of the bank's port (see `PcintBank`).
+/// Useful inside a bank handler registered with `pcint_enable_bank()`.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{PcintBank, pcint_changed_pins};
+///
+/// fn pins_changed() {
+///     let changed = pcint_changed_pins(PcintBank::Bank0);
+///     if changed & 0b001 != 0 {
+///         // Pin 8 changed
+///     }
+/// }
+/// ```
+pub fn pcint_changed_pins(bank: PcintBank) -> u8 {
+    critical_section::with(|cs| LAST_CHANGED.borrow(cs)[bank as usize].get())
+}
+
+/// Whether a bank can raise interrupts (enabled in PCICR with pins in PCMSK)
+pub(crate) fn bank_enabled(bank: PcintBank) -> bool {
     unsafe {
-        // Clear the mask register
-        let pcmsk = get_pcmsk_register(bank);
-        write_volatile(pcmsk, 0);
+        read_volatile(PCICR) & (1 << bank as u8) != 0
+            && read_volatile(get_pcmsk_register(bank)) != 0
+    }
+}
+
+/// Common ISR body: detect changed pins and dispatch handlers
+fn handle_bank(bank: PcintBank) {
+    crate::sleep::record_wake(crate::WakeSource::PinChange(bank));
+    let state = read_bank(bank);
+    let enabled = unsafe { read_volatile(get_pcmsk_register(bank)) };
+
+    let (mut changed, serial_mask) = critical_section::with(|cs| {
+        let last = LAST_STATE.borrow(cs)[bank as usize].replace(state);
+        ((state ^ last) & enabled, software_serial_mask(cs, bank))
+    });
+
+    // SoftwareSerial reception is timing critical, so it goes first
+    if changed & serial_mask != 0 {
+        crate::software_serial::handle_interrupt();
+
+        // Reception consumed the whole frame; resync the RX bit to its idle state
+        let now = read_bank(bank);
+        critical_section::with(|cs| {
+            let last = &LAST_STATE.borrow(cs)[bank as usize];
+            last.set((last.get() & !serial_mask) | (now & serial_mask));
+        });
+        changed &= !serial_mask;
+    }
+
+    critical_section::with(|cs| {
+        LAST_CHANGED.borrow(cs)[bank as usize].set(changed);
+    });
+
+    // Per-pin handlers
+    let mut bits = changed;
+    let mut bit = 0usize;
+    while bits != 0 {
+        if bits & 1 != 0 {
+            let entry = critical_section::with(|cs| {
+                PIN_HANDLERS.borrow(cs)[bank as usize * 8 + bit].get()
+            });
+
+            if let Some(PinHandler { mode, handler }) = entry {
+                let is_high = state & (1 << bit) != 0;
+                let fire = match mode {
+                    InterruptMode::Change => true,
+                    InterruptMode::Rising => is_high,
+                    InterruptMode::Falling | InterruptMode::Low => !is_high,
+                };
+                if fire {
+                    handler.call();
+                }
+            }
+        }
+        bits >>= 1;
+        bit += 1;
+    }
 
-        // Disable the PCINT bank
-        let current_pcicr = read_volatile(PCICR);
-        write_volatile(PCICR, current_pcicr & !(1 << (bank as u8)));
+    // Bank-wide handler
+    let bank_handler = critical_section::with(|cs| PCINT_HANDLERS.borrow(cs).get()[bank as usize]);
+    if let Some(handler) = bank_handler {
+        handler();
     }
 }
 
 // Interrupt handlers - these call the user-provided functions
 
-#[no_mangle]
 #[link_section = ".text"]
-pub unsafe extern "avr-interrupt" fn _ivr_pcint0() {
+#[export_name = "__vector_3"]
+pub unsafe extern "avr-interrupt" fn __vector_3() {
     // Port B (pins 8-13)
-    critical_section::with(|cs| {
-        if let Some(handler) = PCINT_HANDLERS.borrow(cs).get()[0] {
-            handler();
-        }
-    });
+    handle_bank(PcintBank::Bank0);
 
     // Clear the interrupt flag
     write_volatile(PCIFR, 1 << PCIE0);
 }
 
-#[no_mangle]
 #[link_section = ".text"]
-pub unsafe extern "avr-interrupt" fn _ivr_pcint1() {
+#[export_name = "__vector_4"]
+pub unsafe extern "avr-interrupt" fn __vector_4() {
     // Port C (pins A0-A5)
-    critical_section::with(|cs| {
-        if let Some(handler) = PCINT_HANDLERS.borrow(cs).get()[1] {
-            handler();
-        }
-    });
+    handle_bank(PcintBank::Bank1);
 
     // Clear the interrupt flag
     write_volatile(PCIFR, 1 << PCIE1);
 }
 
-#[no_mangle]
 #[link_section = ".text"]
-pub unsafe extern "avr-interrupt" fn _ivr_pcint2() {
+#[export_name = "__vector_5"]
+pub unsafe extern "avr-interrupt" fn __vector_5() {
     // Port D (pins 0-7)
-    critical_section::with(|cs| {
-        if let Some(handler) = PCINT_HANDLERS.borrow(cs).get()[2] {
-            handler();
-        }
-    });
+    handle_bank(PcintBank::Bank2);
 
     // Clear the interrupt flag
     write_volatile(PCIFR, 1 << PCIE2);
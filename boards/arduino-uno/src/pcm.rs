@@ -0,0 +1,434 @@
+//! 8-bit PCM audio playback without a DAC
+//!
+//! Plays 8-bit unsigned samples (128 is silence) on D11 or D3: Timer2 runs
+//! fast PWM with no prescaler, 62.5 kHz at 16 MHz and well above hearing,
+//! and each sample becomes the duty cycle of one PWM period. A Timer1
+//! interrupt at the sample rate (4-16 kHz) loads the next sample, so playback
+//! runs in the background.
+//!
+//! Samples come from a [`PcmClip`] in flash or RAM, played once or looped,
+//! or are streamed through a ring buffer that the main program keeps filled
+//! with [`PcmPlayer::fill`].
+//!
+//! A piezo buzzer can be driven from the pin directly. For a speaker, filter
+//! the PWM carrier with an RC low-pass (e.g. 1 kohm and 33 nF) and feed a
+//! small amplifier such as an LM386 or PAM8403.
+//!
+//! Timer1 is used only while a sound plays: attaching a servo stops the
+//! playback, and `tone()` and PWM on D9/D10 cannot use Timer1 meanwhile.
+//! Timer2 belongs to the player as long as it exists, like any PWM on D3/D11.
+
+use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::{ByteQueue, IrqCell};
+use crate::pin::Pin;
+use crate::power::{self, PowerPeripheral};
+use crate::progmem::pgm_read_byte;
+use crate::pwm::Pwm;
+
+// Timer2 registers (PWM output)
+const TCCR2B: *mut u8 = 0xB1 as *mut u8;  // Timer/Counter2 Control Register B
+const OCR2A: *mut u8 = 0xB3 as *mut u8;   // Output Compare Register 2 A (D11)
+const OCR2B: *mut u8 = 0xB4 as *mut u8;   // Output Compare Register 2 B (D3)
+
+// Timer1 registers (sample clock)
+const TCCR1A: *mut u8 = 0x80 as *mut u8;  // Timer/Counter1 Control Register A
+const TCCR1B: *mut u8 = 0x81 as *mut u8;  // Timer/Counter1 Control Register B
+const TCNT1L: *mut u8 = 0x84 as *mut u8;  // Timer/Counter1 Low
+const TCNT1H: *mut u8 = 0x85 as *mut u8;  // Timer/Counter1 High
+const ICR1L: *mut u8 = 0x86 as *mut u8;   // Input Capture Register 1 Low (TOP)
+const ICR1H: *mut u8 = 0x87 as *mut u8;   // Input Capture Register 1 High (TOP)
+const TIMSK1: *mut u8 = 0x6F as *mut u8;  // Timer/Counter1 Interrupt Mask Register
+const TIFR1: *mut u8 = 0x36 as *mut u8;   // Timer/Counter1 Interrupt Flag Register
+
+// Timer bits
+const CS20: u8 = 0;   // Timer2 clock without prescaler
+const CS10: u8 = 0;   // Timer1 clock without prescaler
+const WGM12: u8 = 3;  // With WGM13: CTC mode, TOP = ICR1
+const WGM13: u8 = 4;
+const ICIE1: u8 = 5;  // Input Capture Interrupt Enable (fires at TOP in CTC mode 12)
+const ICF1: u8 = 5;   // Input Capture Flag
+
+/// Lowest supported sample rate in Hz
+pub const MIN_SAMPLE_RATE: u16 = 4000;
+
+/// Highest supported sample rate in Hz
+pub const MAX_SAMPLE_RATE: u16 = 16000;
+
+/// Sample value of silence (half duty cycle)
+pub const PCM_SILENCE: u8 = 128;
+
+/// Size of the stream ring buffer (holds one sample less)
+const STREAM_BUFFER_SIZE: usize = 128;
+
+/// PCM playback error types
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PcmError {
+    /// Sample rate outside `MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE`
+    InvalidSampleRate,
+    /// Servos or PWM on D9/D10 are using Timer1
+    TimerBusy,
+}
+
+/// A sound of 8-bit unsigned samples
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::pcm_clip;
+///
+/// // One cycle of a 1 kHz square wave at 8 kHz, from flash
+/// let beep = pcm_clip!(8000, &[255, 255, 255, 255, 0, 0, 0, 0]);
+///
+/// // Raw unsigned 8-bit mono recordings work the same way, e.g. exported with
+/// // `sox hello.wav -r 8000 -c 1 -b 8 -e unsigned hello.raw`:
+/// // let hello = pcm_clip!(8000, include_bytes!("hello.raw"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct PcmClip {
+    samples: *const u8,
+    len: usize,
+    flash: bool,
+    sample_rate: u16,
+}
+
+// The pointer refers to `'static` data that is never written
+unsafe impl Send for PcmClip {}
+
+impl PcmClip {
+    /// Create a clip from samples in RAM
+    pub const fn new(samples: &'static [u8], sample_rate: u16) -> Self {
+        Self { samples: samples.as_ptr(), len: samples.len(), flash: false, sample_rate }
+    }
+
+    /// Create a clip from samples in flash
+    ///
+    /// Prefer the [`pcm_clip!`](crate::pcm_clip!) macro, which places the
+    /// samples in flash for you.
+    ///
+    /// # Safety
+    /// `samples` must be placed in `.progmem.data`.
+    pub const unsafe fn from_progmem(samples: &'static [u8], sample_rate: u16) -> Self {
+        Self { samples: samples.as_ptr(), len: samples.len(), flash: true, sample_rate }
+    }
+
+    /// Number of samples
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the clip has no samples
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sample rate in Hz
+    pub const fn sample_rate(&self) -> u16 {
+        self.sample_rate
+    }
+
+    /// Playing time in milliseconds
+    pub const fn duration_ms(&self) -> u32 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        self.len as u32 * 1000 / self.sample_rate as u32
+    }
+
+    fn sample(&self, index: usize) -> u8 {
+        unsafe {
+            if self.flash {
+                pgm_read_byte(self.samples.add(index))
+            } else {
+                *self.samples.add(index)
+            }
+        }
+    }
+}
+
+/// Place samples in flash and create a [`PcmClip`] from them
+///
+/// Takes the sample rate and a `&[u8]` constant expression, such as
+/// `include_bytes!` of a raw unsigned 8-bit mono file.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::pcm_clip;
+///
+/// let click = pcm_clip!(8000, &[128, 200, 255, 200, 128, 56, 0, 56, 128]);
+/// ```
+#[macro_export]
+macro_rules! pcm_clip {
+    ($sample_rate:expr, $samples:expr) => {{
+        const SAMPLES: &[u8] = $samples;
+        #[link_section = ".progmem.data"]
+        static DATA: [u8; SAMPLES.len()] = {
+            let mut data = [0u8; SAMPLES.len()];
+            let mut i = 0;
+            while i < SAMPLES.len() {
+                data[i] = SAMPLES[i];
+                i += 1;
+            }
+            data
+        };
+        unsafe { $crate::PcmClip::from_progmem(&DATA, $sample_rate) }
+    }};
+}
+
+#[derive(Clone, Copy)]
+enum Source {
+    Clip { clip: PcmClip, index: usize, looping: bool },
+    Stream,
+}
+
+#[derive(Clone, Copy)]
+struct Playback {
+    /// Compare register of the output pin
+    output: *mut u8,
+    source: Source,
+}
+
+// The register address is fixed hardware
+unsafe impl Send for Playback {}
+
+static PLAYBACK: IrqCell<Option<Playback>> = IrqCell::new(None);
+static STREAM: ByteQueue<STREAM_BUFFER_SIZE> = ByteQueue::new();
+
+/// PCM player on a Timer2 PWM pin
+///
+/// Takes D11 or D3 in PWM mode and switches Timer2 to its fastest PWM
+/// rate, which also applies to PWM on the other Timer2 pin. The output
+/// rests at [`PCM_SILENCE`] between sounds so starting and stopping does
+/// not click. Only one sound plays at a time, even with two players.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{pcm_clip, Peripherals, PcmPlayer, PwmFrequency};
+///
+/// let peripherals = Peripherals::take().unwrap();
+/// let pin = peripherals.pins.d11.into_output().into_pwm(PwmFrequency::Freq31kHz);
+/// let mut player = PcmPlayer::new(pin);
+///
+/// player.play(pcm_clip!(8000, &[128, 200, 255, 200, 128, 56, 0, 56, 128])).unwrap();
+/// while player.is_playing() {
+///     // Free to do other work
+/// }
+/// ```
+pub struct PcmPlayer<const N: u8> {
+    _pin: Pin<N, Pwm>,
+}
+
+impl<const N: u8> PcmPlayer<N> {
+    const VALID_PIN: () = assert!(N == 11 || N == 3, "PcmPlayer needs D11 (OC2A) or D3 (OC2B)");
+
+    /// Create a player on D11 (OC2A) or D3 (OC2B)
+    pub fn new(pin: Pin<N, Pwm>) -> Self {
+        let () = Self::VALID_PIN;
+        unsafe {
+            write_volatile(TCCR2B, 1 << CS20);
+        }
+        let player = Self { _pin: pin };
+        player.set_level(PCM_SILENCE);
+        player
+    }
+
+    fn output() -> *mut u8 {
+        if N == 11 { OCR2A } else { OCR2B }
+    }
+
+    fn set_level(&self, sample: u8) {
+        unsafe { write_volatile(Self::output(), sample) };
+    }
+
+    /// Play a clip once, replacing the sound that is playing
+    pub fn play(&mut self, clip: PcmClip) -> Result<(), PcmError> {
+        self.start(Source::Clip { clip, index: 0, looping: false }, clip.sample_rate)
+    }
+
+    /// Play a clip over and over until stopped
+    pub fn play_looping(&mut self, clip: PcmClip) -> Result<(), PcmError> {
+        self.start(Source::Clip { clip, index: 0, looping: true }, clip.sample_rate)
+    }
+
+    /// Play the samples queued with [`fill`](Self::fill) and
+    /// [`write`](Self::write) until stopped
+    ///
+    /// The buffer holds 127 samples, about 8 ms at 16 kHz, so refill it
+    /// often. When it runs empty the output holds the last sample.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use arduino_uno::{Peripherals, PcmPlayer, PwmFrequency};
+    /// # let peripherals = Peripherals::take().unwrap();
+    /// # let mut player = PcmPlayer::new(peripherals.pins.d11.into_output().into_pwm(PwmFrequency::Freq31kHz));
+    /// // 250 Hz sawtooth at 8 kHz
+    /// let mut phase: u8 = 0;
+    /// player.stream(8000).unwrap();
+    /// loop {
+    ///     player.fill(|| {
+    ///         phase = phase.wrapping_add(8);
+    ///         Some(phase)
+    ///     });
+    ///     // Other work, shorter than the buffered time
+    /// }
+    /// ```
+    pub fn stream(&mut self, sample_rate: u16) -> Result<(), PcmError> {
+        self.start(Source::Stream, sample_rate)
+    }
+
+    /// Queue samples from `next` until the buffer is full or it returns `None`
+    ///
+    /// Returns the number of samples queued.
+    pub fn fill(&mut self, mut next: impl FnMut() -> Option<u8>) -> usize {
+        let mut count = 0;
+        while !STREAM.is_full() {
+            let Some(sample) = next() else {
+                break;
+            };
+            // Cannot fail: only this producer adds to the buffer
+            let _ = STREAM.push(sample);
+            count += 1;
+        }
+        count
+    }
+
+    /// Queue as many of `samples` as fit, returning how many were queued
+    pub fn write(&mut self, samples: &[u8]) -> usize {
+        let mut samples = samples.iter().copied();
+        self.fill(|| samples.next())
+    }
+
+    /// Stop playing and return the output to silence
+    pub fn stop(&mut self) {
+        critical_section::with(|_| {
+            if self.is_playing() {
+                stop_playback();
+            }
+        });
+    }
+
+    /// Check if this player is playing a clip or stream
+    pub fn is_playing(&self) -> bool {
+        PLAYBACK.lock(|playback| playback.is_some_and(|playback| core::ptr::eq(playback.output, Self::output())))
+    }
+
+    /// Stop playing and give the PWM pin back
+    pub fn into_pin(mut self) -> Pin<N, Pwm> {
+        self.stop();
+        unsafe { Pin::new() }
+    }
+
+    fn start(&mut self, source: Source, sample_rate: u16) -> Result<(), PcmError> {
+        if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&sample_rate) {
+            return Err(PcmError::InvalidSampleRate);
+        }
+
+        critical_section::with(|_| {
+            stop_playback();
+            if crate::servo::timer1_in_use() {
+                return Err(PcmError::TimerBusy);
+            }
+            // A tone has to give way like it does for servos
+            crate::tone::release_timer1();
+            if unsafe { read_volatile(TCCR1A) } & 0xF0 != 0 {
+                return Err(PcmError::TimerBusy);
+            }
+
+            STREAM.clear();
+            PLAYBACK.set(Some(Playback { output: Self::output(), source }));
+            start_sample_clock(sample_rate);
+            Ok(())
+        })?;
+
+        unsafe {
+            core::arch::asm!("sei");
+        }
+        Ok(())
+    }
+}
+
+impl<const N: u8> Drop for PcmPlayer<N> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Check if PCM playback is using Timer1
+pub(crate) fn timer1_in_use() -> bool {
+    PLAYBACK.lock(|playback| playback.is_some())
+}
+
+/// Stop the playback before a servo takes Timer1 over
+pub(crate) fn release_timer1() {
+    critical_section::with(|_| stop_playback());
+}
+
+/// Run Timer1 in CTC mode 12 with an interrupt at every sample
+///
+/// Must be called inside a critical section.
+fn start_sample_clock(sample_rate: u16) {
+    let rate = sample_rate as u32;
+    let top = (crate::clock::cpu_frequency() + rate / 2) / rate - 1;
+
+    power::acquire(PowerPeripheral::Timer1);
+    unsafe {
+        write_volatile(TCCR1B, 0);
+        write_volatile(TCCR1A, 0);
+        write_volatile(ICR1H, (top >> 8) as u8);
+        write_volatile(ICR1L, top as u8);
+        write_volatile(TCNT1H, 0);
+        write_volatile(TCNT1L, 0);
+        write_volatile(TIFR1, 1 << ICF1);
+        write_volatile(TIMSK1, read_volatile(TIMSK1) | (1 << ICIE1));
+        write_volatile(TCCR1B, (1 << WGM13) | (1 << WGM12) | (1 << CS10));
+    }
+}
+
+/// Stop the sample clock and silence the output, if playing
+///
+/// Must be called inside a critical section.
+fn stop_playback() {
+    let Some(playback) = PLAYBACK.replace(None) else {
+        return;
+    };
+
+    unsafe {
+        write_volatile(TIMSK1, read_volatile(TIMSK1) & !(1 << ICIE1));
+        write_volatile(TCCR1B, 0);
+        write_volatile(playback.output, PCM_SILENCE);
+    }
+    power::release(PowerPeripheral::Timer1, || {});
+}
+
+/// Timer1 Input Capture interrupt handler (TOP in CTC mode): output the next sample
+#[link_section = ".text"]
+#[export_name = "__vector_10"]
+pub unsafe extern "avr-interrupt" fn __vector_10() {
+    let finished = PLAYBACK.lock(|playback| {
+        let Some(playback) = playback else {
+            return false;
+        };
+
+        let sample = match &mut playback.source {
+            Source::Clip { clip, index, looping } => {
+                if *index >= clip.len {
+                    if !*looping || clip.is_empty() {
+                        return true;
+                    }
+                    *index = 0;
+                }
+                let sample = clip.sample(*index);
+                *index += 1;
+                Some(sample)
+            }
+            Source::Stream => STREAM.pop(),
+        };
+
+        if let Some(sample) = sample {
+            write_volatile(playback.output, sample);
+        }
+        false
+    });
+
+    if finished {
+        stop_playback();
+    }
+}
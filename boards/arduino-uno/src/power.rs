@@ -0,0 +1,244 @@
+//! Power reduction management for ATmega328P
+//!
+//! The Power Reduction Register (PRR) stops the clock of individual on-chip
+//! peripherals. Drivers power their peripheral up when constructed and
+//! release it when dropped (or on `end()`); a peripheral is powered down once
+//! its last user is gone:
+//!
+//! - `I2c` → TWI
+//! - `Spi` → SPI
+//! - `Serial` → USART0
+//! - `Adc` → ADC
+//! - PWM pins, `tone()` and `Servo` → Timer0/1/2
+//!
+//! Timer0 drives `millis()` and is never released once the clock is started.
+//! The low-level `timer_*` functions do not manage power; use
+//! [`PowerManager::power_up`] if a timer may have been powered down.
+//!
+//! [`PowerManager`] queries and overrides these states, and disables the
+//! digital input buffers (DIDR0/DIDR1) of pins used only for analog input.
+
+use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::IrqCell;
+
+// Power Reduction Register
+const PRR: *mut u8 = 0x64 as *mut u8;
+
+// Digital Input Disable Registers
+const DIDR0: *mut u8 = 0x7E as *mut u8;  // ADC0D..ADC5D (A0-A5)
+const DIDR1: *mut u8 = 0x7F as *mut u8;  // AIN0D (D6), AIN1D (D7)
+
+// ADC Control and Status Register A
+const ADCSRA: *mut u8 = 0x7A as *mut u8;
+const ADEN: u8 = 7;  // ADC Enable
+
+/// Peripherals that can be powered down through PRR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PowerPeripheral {
+    /// Analog-to-digital converter (PRADC)
+    Adc = 0,
+    /// USART0, used by `Serial` (PRUSART0)
+    Usart0 = 1,
+    /// SPI (PRSPI)
+    Spi = 2,
+    /// Timer/Counter1 (PRTIM1)
+    Timer1 = 3,
+    /// Timer/Counter0, used by `millis()` (PRTIM0)
+    Timer0 = 5,
+    /// Timer/Counter2 (PRTIM2)
+    Timer2 = 6,
+    /// Two-wire interface, used by `I2c` (PRTWI)
+    Twi = 7,
+}
+
+impl PowerPeripheral {
+    /// All PRR-controlled peripherals
+    pub const ALL: [PowerPeripheral; 7] = [
+        PowerPeripheral::Adc,
+        PowerPeripheral::Usart0,
+        PowerPeripheral::Spi,
+        PowerPeripheral::Timer1,
+        PowerPeripheral::Timer0,
+        PowerPeripheral::Timer2,
+        PowerPeripheral::Twi,
+    ];
+
+    /// PRR bit mask for this peripheral
+    const fn mask(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Index into the user count table (PRR bit 4 is reserved)
+    const fn index(self) -> usize {
+        match self {
+            PowerPeripheral::Timer0 => 4,
+            PowerPeripheral::Timer2 => 5,
+            PowerPeripheral::Twi => 6,
+            other => other as usize,
+        }
+    }
+}
+
+// Number of live driver users per peripheral
+static USERS: IrqCell<[u8; 7]> = IrqCell::new([0; 7]);
+
+/// Register a user of a peripheral and make sure it is powered
+pub(crate) fn acquire(peripheral: PowerPeripheral) {
+    critical_section::with(|_| {
+        USERS.lock(|users| {
+            let count = &mut users[peripheral.index()];
+            *count = count.saturating_add(1);
+        });
+        set_powered(peripheral, true);
+    });
+}
+
+/// Drop a user of a peripheral
+///
+/// When this was the last user, `shutdown` runs while the peripheral is still
+/// clocked, then the peripheral is powered down.
+pub(crate) fn release(peripheral: PowerPeripheral, shutdown: impl FnOnce()) {
+    let last = USERS.lock(|users| {
+        let count = &mut users[peripheral.index()];
+        *count = count.saturating_sub(1);
+        *count == 0
+    });
+
+    if last {
+        shutdown();
+        critical_section::with(|_| {
+            // A new user may have appeared while shutting down
+            if PowerManager::users(peripheral) == 0 {
+                set_powered(peripheral, false);
+            }
+        });
+    }
+}
+
+fn set_powered(peripheral: PowerPeripheral, powered: bool) {
+    critical_section::with(|_| unsafe {
+        let prr = read_volatile(PRR);
+        if powered {
+            write_volatile(PRR, prr & !peripheral.mask());
+        } else {
+            if peripheral == PowerPeripheral::Adc {
+                // The ADC must be disabled before it is shut down
+                write_volatile(ADCSRA, read_volatile(ADCSRA) & !(1 << ADEN));
+            }
+            write_volatile(PRR, prr | peripheral.mask());
+        }
+    });
+}
+
+/// DIDR register and bit for a pin with a digital input buffer switch
+fn didr_bit(pin: u8) -> Option<(*mut u8, u8)> {
+    match pin {
+        6 => Some((DIDR1, 0)),               // AIN0
+        7 => Some((DIDR1, 1)),               // AIN1
+        14..=19 => Some((DIDR0, pin - 14)),  // A0-A5
+        _ => None,
+    }
+}
+
+/// Peripheral power state control
+///
+/// # Examples
+/// ```no_run
+/// use arduino_uno::{PowerManager, PowerPeripheral};
+///
+/// // Switch off everything no driver is using
+/// PowerManager::power_down_unused();
+///
+/// // A0 is only used with the ADC
+/// PowerManager::disable_digital_input(14);
+///
+/// if !PowerManager::is_powered(PowerPeripheral::Twi) {
+///     // I2C is off
+/// }
+/// ```
+pub struct PowerManager;
+
+impl PowerManager {
+    /// Check whether a peripheral is currently powered (its PRR bit is clear)
+    pub fn is_powered(peripheral: PowerPeripheral) -> bool {
+        unsafe { read_volatile(PRR) & peripheral.mask() == 0 }
+    }
+
+    /// Number of live drivers using a peripheral
+    pub fn users(peripheral: PowerPeripheral) -> u8 {
+        USERS.lock(|users| users[peripheral.index()])
+    }
+
+    /// Raw PRR value (a set bit means powered down)
+    pub fn state() -> u8 {
+        unsafe { read_volatile(PRR) }
+    }
+
+    /// Power a peripheral up regardless of its users
+    ///
+    /// Needed before using the low-level timer functions on a timer that may
+    /// have been powered down.
+    pub fn power_up(peripheral: PowerPeripheral) {
+        set_powered(peripheral, true);
+    }
+
+    /// Power a peripheral down regardless of its users
+    ///
+    /// Drivers still using the peripheral stop working until it is powered up
+    /// again; a powered-down peripheral ignores register writes.
+    pub fn power_down(peripheral: PowerPeripheral) {
+        set_powered(peripheral, false);
+    }
+
+    /// Power down every peripheral without a live driver
+    pub fn power_down_unused() {
+        critical_section::with(|_| {
+            for peripheral in PowerPeripheral::ALL {
+                if Self::users(peripheral) == 0 {
+                    set_powered(peripheral, false);
+                }
+            }
+        });
+    }
+
+    /// Disable the digital input buffer of an analog pin
+    ///
+    /// Saves power on pins that only carry analog signals (A0-A5 as 14-19,
+    /// and the comparator inputs D6/D7). Digital reads of the pin return 0
+    /// afterwards. Returns `false` for pins without a buffer switch.
+    pub fn disable_digital_input(pin: u8) -> bool {
+        match didr_bit(pin) {
+            Some((didr, bit)) => {
+                critical_section::with(|_| unsafe {
+                    write_volatile(didr, read_volatile(didr) | (1 << bit));
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-enable the digital input buffer of an analog pin
+    ///
+    /// Returns `false` for pins without a buffer switch.
+    pub fn enable_digital_input(pin: u8) -> bool {
+        match didr_bit(pin) {
+            Some((didr, bit)) => {
+                critical_section::with(|_| unsafe {
+                    write_volatile(didr, read_volatile(didr) & !(1 << bit));
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether a pin's digital input buffer is enabled
+    pub fn digital_input_enabled(pin: u8) -> bool {
+        match didr_bit(pin) {
+            Some((didr, bit)) => unsafe { read_volatile(didr) & (1 << bit) == 0 },
+            None => true,
+        }
+    }
+}
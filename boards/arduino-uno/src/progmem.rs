@@ -3,8 +3,10 @@
 //! This module provides Arduino-compatible PROGMEM macros for storing
 //! constant data in flash memory instead of RAM, which is critical on
 //! AVR microcontrollers with limited SRAM.
-
-use core::ptr::read_volatile;
+//!
+//! Flash is a separate address space on AVR: normal loads read RAM at the
+//! same address, so flash data must be read with the `pgm_read_*` functions,
+//! which use the `lpm` instruction.
 
 /// A string stored in program memory (flash)
 ///
@@ -46,7 +48,7 @@ impl FlashString {
             None
         } else {
             unsafe {
-                Some(read_volatile(self.ptr.add(index)))
+                Some(pgm_read_byte(self.ptr.add(index)))
             }
         }
     }
@@ -65,7 +67,7 @@ impl FlashString {
     pub fn copy_to_slice(&self, buffer: &mut [u8]) -> usize {
         let copy_len = core::cmp::min(self.len, buffer.len());
         for i in 0..copy_len {
-            buffer[i] = unsafe { read_volatile(self.ptr.add(i)) };
+            buffer[i] = unsafe { pgm_read_byte(self.ptr.add(i)) };
         }
         copy_len
     }
@@ -106,8 +108,18 @@ impl<'a> ExactSizeIterator for FlashStringIter<'a> {}
 #[macro_export]
 macro_rules! F {
     ($s:expr) => {{
+        const TEXT: &[u8] = $s.as_bytes();
+        // The bytes themselves go to flash, not just a reference to them
         #[link_section = ".progmem.data"]
-        static DATA: &[u8] = $s.as_bytes();
+        static DATA: [u8; TEXT.len()] = {
+            let mut data = [0u8; TEXT.len()];
+            let mut i = 0;
+            while i < TEXT.len() {
+                data[i] = TEXT[i];
+                i += 1;
+            }
+            data
+        };
         unsafe {
             $crate::FlashString::new(
                 DATA.as_ptr(),
@@ -122,10 +134,18 @@ macro_rules! F {
 /// This is equivalent to Arduino's `pgm_read_byte()` macro.
 ///
 /// # Safety
-/// The pointer must point to valid flash memory
+/// The pointer must be a flash address within the first 64 KB, such as a
+/// static placed in `.progmem.data`
 #[inline]
 pub unsafe fn pgm_read_byte(ptr: *const u8) -> u8 {
-    read_volatile(ptr)
+    let byte: u8;
+    core::arch::asm!(
+        "lpm {byte}, Z",
+        byte = out(reg) byte,
+        in("Z") ptr,
+        options(pure, readonly, nostack, preserves_flags)
+    );
+    byte
 }
 
 /// Read a word (u16) from program memory
@@ -133,10 +153,11 @@ pub unsafe fn pgm_read_byte(ptr: *const u8) -> u8 {
 /// This is equivalent to Arduino's `pgm_read_word()` macro.
 ///
 /// # Safety
-/// The pointer must point to valid flash memory and be properly aligned
+/// The pointer must be a flash address within the first 64 KB
 #[inline]
 pub unsafe fn pgm_read_word(ptr: *const u16) -> u16 {
-    read_volatile(ptr)
+    let bytes = ptr as *const u8;
+    u16::from_le_bytes([pgm_read_byte(bytes), pgm_read_byte(bytes.add(1))])
 }
 
 /// Read a dword (u32) from program memory
@@ -144,10 +165,16 @@ pub unsafe fn pgm_read_word(ptr: *const u16) -> u16 {
 /// This is equivalent to Arduino's `pgm_read_dword()` macro.
 ///
 /// # Safety
-/// The pointer must point to valid flash memory and be properly aligned
+/// The pointer must be a flash address within the first 64 KB
 #[inline]
 pub unsafe fn pgm_read_dword(ptr: *const u32) -> u32 {
-    read_volatile(ptr)
+    let bytes = ptr as *const u8;
+    u32::from_le_bytes([
+        pgm_read_byte(bytes),
+        pgm_read_byte(bytes.add(1)),
+        pgm_read_byte(bytes.add(2)),
+        pgm_read_byte(bytes.add(3)),
+    ])
 }
 
 /// Read a float from program memory
@@ -155,10 +182,10 @@ pub unsafe fn pgm_read_dword(ptr: *const u32) -> u32 {
 /// This is equivalent to Arduino's `pgm_read_float()` macro.
 ///
 /// # Safety
-/// The pointer must point to valid flash memory and be properly aligned
+/// The pointer must be a flash address within the first 64 KB
 #[inline]
 pub unsafe fn pgm_read_float(ptr: *const f32) -> f32 {
-    read_volatile(ptr)
+    f32::from_bits(pgm_read_dword(ptr as *const u32))
 }
 
 /// Read a pointer from program memory
@@ -166,8 +193,9 @@ pub unsafe fn pgm_read_float(ptr: *const f32) -> f32 {
 /// This is equivalent to Arduino's `pgm_read_ptr()` macro.
 ///
 /// # Safety
-/// The pointer must point to valid flash memory and be properly aligned
+/// The pointer must be a flash address within the first 64 KB
 #[inline]
 pub unsafe fn pgm_read_ptr<T>(ptr: *const *const T) -> *const T {
-    read_volatile(ptr)
+    // Pointers are 16 bits on AVR
+    pgm_read_word(ptr as *const u16) as usize as *const T
 }
@@ -10,6 +10,7 @@
 use core::ptr::{read_volatile, write_volatile};
 use crate::pin::{Pin, mode};
 use crate::gpio_impl;
+use crate::power::{self, PowerPeripheral};
 
 /// PWM pin mode marker
 pub struct Pwm;
@@ -100,6 +101,7 @@ impl Pin<3, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(3);
+            power::acquire(PowerPeripheral::Timer2);
             init_timer2(freq);
             // Set COM2B1 to enable PWM on OC2B
             let tccr2a = read_volatile(TCCR2A);
@@ -123,6 +125,7 @@ impl Pin<3, Pwm> {
             // Disable PWM by clearing COM2B bits
             let tccr2a = read_volatile(TCCR2A);
             write_volatile(TCCR2A, tccr2a & !(0b11 << 4));
+            power::release(PowerPeripheral::Timer2, || {});
             Pin::new()
         }
     }
@@ -135,6 +138,7 @@ impl Pin<5, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(5);
+            power::acquire(PowerPeripheral::Timer0);
             init_timer0(freq);
             // Set COM0B1 to enable PWM on OC0B
             let tccr0a = read_volatile(TCCR0A);
@@ -158,6 +162,7 @@ impl Pin<5, Pwm> {
             // Disable PWM by clearing COM0B bits
             let tccr0a = read_volatile(TCCR0A);
             write_volatile(TCCR0A, tccr0a & !(0b11 << 4));
+            power::release(PowerPeripheral::Timer0, || {});
             Pin::new()
         }
     }
@@ -170,6 +175,7 @@ impl Pin<6, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(6);
+            power::acquire(PowerPeripheral::Timer0);
             init_timer0(freq);
             // Set COM0A1 to enable PWM on OC0A
             let tccr0a = read_volatile(TCCR0A);
@@ -193,6 +199,7 @@ impl Pin<6, Pwm> {
             // Disable PWM by clearing COM0A bits
             let tccr0a = read_volatile(TCCR0A);
             write_volatile(TCCR0A, tccr0a & !(0b11 << 6));
+            power::release(PowerPeripheral::Timer0, || {});
             Pin::new()
         }
     }
@@ -205,6 +212,7 @@ impl Pin<9, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(9);
+            power::acquire(PowerPeripheral::Timer1);
             init_timer1(freq);
             // Set COM1A1 to enable PWM on OC1A
             let tccr1a = read_volatile(TCCR1A);
@@ -229,6 +237,7 @@ impl Pin<9, Pwm> {
             // Disable PWM by clearing COM1A bits
             let tccr1a = read_volatile(TCCR1A);
             write_volatile(TCCR1A, tccr1a & !(0b11 << 6));
+            power::release(PowerPeripheral::Timer1, || {});
             Pin::new()
         }
     }
@@ -241,6 +250,7 @@ impl Pin<10, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(10);
+            power::acquire(PowerPeripheral::Timer1);
             init_timer1(freq);
             // Set COM1B1 to enable PWM on OC1B
             let tccr1a = read_volatile(TCCR1A);
@@ -265,6 +275,7 @@ impl Pin<10, Pwm> {
             // Disable PWM by clearing COM1B bits
             let tccr1a = read_volatile(TCCR1A);
             write_volatile(TCCR1A, tccr1a & !(0b11 << 4));
+            power::release(PowerPeripheral::Timer1, || {});
             Pin::new()
         }
     }
@@ -277,6 +288,7 @@ impl Pin<11, mode::Output> {
         unsafe {
             // Ensure pin is configured as output
             gpio_impl::set_pin_output(11);
+            power::acquire(PowerPeripheral::Timer2);
             init_timer2(freq);
             // Set COM2A1 to enable PWM on OC2A
             let tccr2a = read_volatile(TCCR2A);
@@ -300,6 +312,7 @@ impl Pin<11, Pwm> {
             // Disable PWM by clearing COM2A bits
             let tccr2a = read_volatile(TCCR2A);
             write_volatile(TCCR2A, tccr2a & !(0b11 << 6));
+            power::release(PowerPeripheral::Timer2, || {});
             Pin::new()
         }
     }
@@ -1,4 +1,4 @@
-//! Real-Time Clock (RTC) driver for DS1307, DS3231, and PCF8523
+//! Real-Time Clock (RTC) driver for DS1307, DS3231, PCF8563, and MCP7940N
 //!
 //! This module provides a generic RTC interface that works with multiple
 //! RTC chips commonly used with Arduino.
@@ -6,11 +6,26 @@
 //! Supported chips:
 //! - DS1307: Basic RTC with 56 bytes NVRAM (address 0x68)
 //! - DS3231: High-precision RTC with temperature sensor and alarms (address 0x68)
-//! - PCF8523: Low-power RTC with countdown timers (address 0x68)
+//! - PCF8563: Low-power RTC with voltage-low flag and CLKOUT (address 0x51)
+//! - MCP7940N: RTC with battery switchover and power-fail flag (address 0x6F)
+//!
+//! The drivers are generic over `embedded_hal::i2c::I2c`, so they also work
+//! on a shared bus; `DS1307::new(I2c::new())` uses the on-chip TWI directly.
 //!
 //! All chips use I2C and BCD (Binary Coded Decimal) encoding for time values.
+//! Times are exchanged as `ossidata_core::datetime::DateTime`, which also
+//! provides Unix timestamps, arithmetic, time zones and ISO-8601 formatting.
+//!
+//! DS3231 alarms drive its open-drain INT/SQW pin low. Wire it to D2 (INT0)
+//! or D3 (INT1) to wake the MCU with [`DS3231::sleep_until_alarm`].
 
+use crate::constants::INPUT_PULLUP;
+use crate::gpio::pin_mode;
+use embedded_hal::i2c::{I2c as I2cBus, Operation};
 use crate::i2c::{I2c, I2cError};
+use crate::interrupt::{attach_interrupt, detach_interrupt, ExternalInterrupt, InterruptMode};
+use crate::sleep::{Sleep, SleepMode, WakeConfig, WakeError, WakeSource};
+use ossidata_core::datetime::DateTime;
 
 /// BCD to binary conversion
 #[inline]
@@ -24,123 +39,87 @@ fn bin2bcd(val: u8) -> u8 {
     ((val / 10) << 4) | (val % 10)
 }
 
-/// Date and time representation
-///
-/// Represents a specific point in time with no timezone information.
-/// Year range: 2000-2099
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct DateTime {
-    year_offset: u8,  // 0-99 (representing 2000-2099)
-    month: u8,        // 1-12
-    day: u8,          // 1-31
-    hour: u8,         // 0-23
-    minute: u8,       // 0-59
-    second: u8,       // 0-59
+/// Map an embedded-hal bus error onto the crate's I2C error
+fn bus_error<E: embedded_hal::i2c::Error>(error: E) -> RtcError {
+    RtcError::I2C(I2cError::from_bus(error))
 }
 
-impl DateTime {
-    /// Create a new DateTime
-    ///
-    /// # Arguments
-    /// * `year` - Full year (2000-2099)
-    /// * `month` - Month (1-12)
-    /// * `day` - Day of month (1-31)
-    /// * `hour` - Hour (0-23)
-    /// * `minute` - Minute (0-59)
-    /// * `second` - Second (0-59)
-    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
-        DateTime {
-            year_offset: ((year - 2000) & 0xFF) as u8,
-            month,
-            day,
-            hour,
-            minute,
-            second,
-        }
-    }
+/// Register access to an RTC chip over any embedded-hal I2C bus
+struct Registers<I2C> {
+    i2c: I2C,
+    address: u8,
+}
 
-    /// Get the full year (2000-2099)
-    pub fn year(&self) -> u16 {
-        2000 + self.year_offset as u16
+impl<I2C: I2cBus> Registers<I2C> {
+    /// Read consecutive registers starting at `register`
+    fn read(&mut self, register: u8, buffer: &mut [u8]) -> Result<(), RtcError> {
+        self.i2c.write_read(self.address, &[register], buffer).map_err(bus_error)
     }
 
-    /// Get the month (1-12)
-    pub fn month(&self) -> u8 {
-        self.month
+    /// Write consecutive registers starting at `register`
+    fn write(&mut self, register: u8, data: &[u8]) -> Result<(), RtcError> {
+        self.i2c
+            .transaction(self.address, &mut [Operation::Write(&[register]), Operation::Write(data)])
+            .map_err(bus_error)
     }
 
-    /// Get the day of month (1-31)
-    pub fn day(&self) -> u8 {
-        self.day
+    fn read_reg(&mut self, register: u8) -> Result<u8, RtcError> {
+        let mut buf = [0u8; 1];
+        self.read(register, &mut buf)?;
+        Ok(buf[0])
     }
 
-    /// Get the hour (0-23)
-    pub fn hour(&self) -> u8 {
-        self.hour
+    fn write_reg(&mut self, register: u8, value: u8) -> Result<(), RtcError> {
+        self.write(register, &[value])
     }
 
-    /// Get the minute (0-59)
-    pub fn minute(&self) -> u8 {
-        self.minute
+    /// Read-modify-write: clear the `clear` bits, then set the `set` bits
+    fn update_reg(&mut self, register: u8, clear: u8, set: u8) -> Result<(), RtcError> {
+        let value = self.read_reg(register)?;
+        self.write_reg(register, (value & !clear) | set)
     }
+}
 
-    /// Get the second (0-59)
-    pub fn second(&self) -> u8 {
-        self.second
+/// Build a DateTime from decoded register fields, checking it is valid
+fn checked_datetime(year: u8, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<DateTime, RtcError> {
+    let dt = DateTime::new(2000 + year as u16, month, day, hour, minute, second);
+    if !dt.is_valid() {
+        return Err(RtcError::InvalidDateTime);
     }
+    Ok(dt)
+}
 
-    /// Get day of week (0=Sunday, 6=Saturday)
-    ///
-    /// Uses Zeller's congruence algorithm
-    pub fn day_of_week(&self) -> u8 {
-        let mut y = self.year() as u16;
-        let mut m = self.month as u16;
-
-        // Adjust for Zeller's (Jan/Feb are month 13/14 of previous year)
-        if m < 3 {
-            m += 12;
-            y -= 1;
-        }
-
-        let century = y / 100;
-        let year_of_century = y % 100;
-
-        let dow = (self.day as u16 +
-                  (13 * (m + 1)) / 5 +
-                  year_of_century +
-                  year_of_century / 4 +
-                  century / 4 +
-                  5 * century) % 7;
-
-        // Convert to 0=Sunday format
-        ((dow + 6) % 7) as u8
-    }
-
-    /// Check if the date is valid
-    pub fn is_valid(&self) -> bool {
-        if self.year_offset > 99 { return false; }
-        if self.month < 1 || self.month > 12 { return false; }
-        if self.day < 1 || self.day > 31 { return false; }
-        if self.hour > 23 { return false; }
-        if self.minute > 59 { return false; }
-        if self.second > 59 { return false; }
-
-        // Check days in month
-        let days_in_month = match self.month {
-            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
-            4 | 6 | 9 | 11 => 30,
-            2 => {
-                let year = self.year();
-                if (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0) {
-                    29  // Leap year
-                } else {
-                    28
-                }
-            }
-            _ => return false,
-        };
+/// Unix timestamp (UTC) of when this crate was compiled
+///
+/// Taken from `SOURCE_DATE_EPOCH` if set. The crate is rebuilt whenever a
+/// file in the package changes, including the example binaries.
+pub const BUILD_TIMESTAMP: u32 = parse_timestamp(env!("OSSIDATA_BUILD_TIMESTAMP"));
+
+const fn parse_timestamp(text: &str) -> u32 {
+    let bytes = text.as_bytes();
+    let mut value: u32 = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value.saturating_mul(10).saturating_add((bytes[i] - b'0') as u32);
+        i += 1;
+    }
+    value
+}
 
-        self.day <= days_in_month
+/// Build time as a UTC [`DateTime`], for setting an RTC that lost power
+///
+/// Falls back to 2000-01-01 00:00:00 if the build timestamp is out of range.
+///
+/// # Example
+/// ```no_run
+/// if rtc.lost_power()? {
+///     rtc.adjust(&build_time())?;
+/// }
+/// ```
+pub const fn build_time() -> DateTime {
+    match DateTime::from_unix_timestamp(BUILD_TIMESTAMP) {
+        Some(dt) => dt,
+        None => DateTime::new(2000, 1, 1, 0, 0, 0),
     }
 }
 
@@ -153,6 +132,10 @@ pub enum RtcError {
     InvalidDateTime,
     /// RTC oscillator stopped (power loss)
     PowerLoss,
+    /// NVRAM access past the end of the memory
+    OutOfRange,
+    /// Sleeping until an alarm failed
+    Wake(WakeError),
 }
 
 impl From<I2cError> for RtcError {
@@ -162,6 +145,9 @@ impl From<I2cError> for RtcError {
 }
 
 /// Generic RTC trait
+///
+/// Implemented by the I2C chips in this module and by `SoftRtc`, so code
+/// can be written once for any of them.
 pub trait Rtc {
     /// Initialize the RTC
     fn begin(&mut self) -> Result<(), RtcError>;
@@ -170,10 +156,10 @@ pub trait Rtc {
     fn adjust(&mut self, dt: &DateTime) -> Result<(), RtcError>;
 
     /// Get the current date and time
-    fn now(&self) -> Result<DateTime, RtcError>;
+    fn now(&mut self) -> Result<DateTime, RtcError>;
 
     /// Check if RTC is running
-    fn is_running(&self) -> Result<bool, RtcError>;
+    fn is_running(&mut self) -> Result<bool, RtcError>;
 }
 
 /// DS1307 Real-Time Clock
@@ -182,29 +168,119 @@ pub trait Rtc {
 /// - I2C address: 0x68
 /// - 56 bytes battery-backed NVRAM
 /// - Square wave output
-pub struct DS1307 {
-    i2c: I2c,
-    address: u8,
+pub struct DS1307<I2C = I2c> {
+    regs: Registers<I2C>,
+}
+
+/// DS1307 SQW/OUT pin configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ds1307SquareWave {
+    /// Output disabled, pin held low
+    Low,
+    /// Output disabled, pin held high (needs a pull-up)
+    High,
+    /// 1 Hz square wave
+    Hz1,
+    /// 4.096 kHz square wave
+    Hz4096,
+    /// 8.192 kHz square wave
+    Hz8192,
+    /// 32.768 kHz square wave
+    Hz32768,
 }
 
-impl DS1307 {
+/// Size of the DS1307 battery-backed NVRAM in bytes
+pub const DS1307_NVRAM_SIZE: u8 = 56;
+
+impl<I2C: I2cBus> DS1307<I2C> {
     const ADDRESS: u8 = 0x68;
     const SECONDS_REG: u8 = 0x00;
+    const CONTROL_REG: u8 = 0x07;
+    const NVRAM_REG: u8 = 0x08;
+
+    // Control register bits
+    const OUT: u8 = 0x80;   // Output level when SQWE is clear
+    const SQWE: u8 = 0x10;  // Square wave enable
 
-    /// Create a new DS1307 instance
-    pub fn new(i2c: I2c) -> Self {
+    /// Create a new DS1307 instance on any embedded-hal I2C bus
+    pub fn new(i2c: I2C) -> Self {
         DS1307 {
-            i2c,
-            address: Self::ADDRESS,
+            regs: Registers { i2c, address: Self::ADDRESS },
+        }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.regs.i2c
+    }
+
+    /// Read from the battery-backed NVRAM
+    ///
+    /// # Arguments
+    /// * `offset` - Start address within the NVRAM (0-55)
+    /// * `buffer` - Bytes to read; `offset + buffer.len()` must not exceed 56
+    pub fn read_nvram(&mut self, offset: u8, buffer: &mut [u8]) -> Result<(), RtcError> {
+        Self::check_nvram_range(offset, buffer.len())?;
+        self.regs.read(Self::NVRAM_REG + offset, buffer)?;
+        Ok(())
+    }
+
+    /// Write to the battery-backed NVRAM
+    ///
+    /// # Arguments
+    /// * `offset` - Start address within the NVRAM (0-55)
+    /// * `data` - Bytes to write; `offset + data.len()` must not exceed 56
+    pub fn write_nvram(&mut self, offset: u8, data: &[u8]) -> Result<(), RtcError> {
+        Self::check_nvram_range(offset, data.len())?;
+        self.regs.write(Self::NVRAM_REG + offset, data)?;
+        Ok(())
+    }
+
+    fn check_nvram_range(offset: u8, len: usize) -> Result<(), RtcError> {
+        if offset as usize + len > DS1307_NVRAM_SIZE as usize {
+            return Err(RtcError::OutOfRange);
         }
+        Ok(())
+    }
+
+    /// Configure the SQW/OUT pin
+    pub fn set_square_wave(&mut self, mode: Ds1307SquareWave) -> Result<(), RtcError> {
+        let control = match mode {
+            Ds1307SquareWave::Low => 0,
+            Ds1307SquareWave::High => Self::OUT,
+            Ds1307SquareWave::Hz1 => Self::SQWE,
+            Ds1307SquareWave::Hz4096 => Self::SQWE | 0x01,
+            Ds1307SquareWave::Hz8192 => Self::SQWE | 0x02,
+            Ds1307SquareWave::Hz32768 => Self::SQWE | 0x03,
+        };
+        self.regs.write(Self::CONTROL_REG, &[control])?;
+        Ok(())
+    }
+
+    /// Read the SQW/OUT pin configuration
+    pub fn square_wave(&mut self) -> Result<Ds1307SquareWave, RtcError> {
+        let mut buf = [0u8; 1];
+        self.regs.read(Self::CONTROL_REG, &mut buf)?;
+
+        let control = buf[0];
+        Ok(if control & Self::SQWE == 0 {
+            if control & Self::OUT != 0 { Ds1307SquareWave::High } else { Ds1307SquareWave::Low }
+        } else {
+            match control & 0x03 {
+                0 => Ds1307SquareWave::Hz1,
+                1 => Ds1307SquareWave::Hz4096,
+                2 => Ds1307SquareWave::Hz8192,
+                _ => Ds1307SquareWave::Hz32768,
+            }
+        })
     }
 }
 
-impl Rtc for DS1307 {
+impl<I2C: I2cBus> Rtc for DS1307<I2C> {
     fn begin(&mut self) -> Result<(), RtcError> {
         // Check if we can communicate with the device
         let mut buf = [0u8; 1];
-        self.i2c.read_register(self.address, Self::SECONDS_REG, &mut buf)?;
+        self.regs.read(Self::SECONDS_REG, &mut buf)?;
         Ok(())
     }
 
@@ -215,23 +291,23 @@ impl Rtc for DS1307 {
 
         // Prepare 7 bytes: seconds, minutes, hours, day-of-week, date, month, year
         let data = [
-            bin2bcd(dt.second),          // Clear CH bit (start oscillator)
-            bin2bcd(dt.minute),
-            bin2bcd(dt.hour),            // 24-hour format
+            bin2bcd(dt.second()),          // Clear CH bit (start oscillator)
+            bin2bcd(dt.minute()),
+            bin2bcd(dt.hour()),            // 24-hour format
             bin2bcd(dt.day_of_week() + 1), // 1-7 format
-            bin2bcd(dt.day),
-            bin2bcd(dt.month),
-            bin2bcd(dt.year_offset),
+            bin2bcd(dt.day()),
+            bin2bcd(dt.month()),
+            bin2bcd((dt.year() - 2000) as u8),
         ];
 
-        self.i2c.write_register(self.address, Self::SECONDS_REG, &data)?;
+        self.regs.write(Self::SECONDS_REG, &data)?;
         Ok(())
     }
 
-    fn now(&self) -> Result<DateTime, RtcError> {
+    fn now(&mut self) -> Result<DateTime, RtcError> {
         // Read 7 bytes starting at seconds register
         let mut buffer = [0u8; 7];
-        self.i2c.read_register(self.address, Self::SECONDS_REG, &mut buffer)?;
+        self.regs.read(Self::SECONDS_REG, &mut buffer)?;
 
         let second = bcd2bin(buffer[0] & 0x7F); // Mask CH bit
         let minute = bcd2bin(buffer[1]);
@@ -240,25 +316,12 @@ impl Rtc for DS1307 {
         let month = bcd2bin(buffer[5]);
         let year = bcd2bin(buffer[6]);
 
-        let dt = DateTime::new(
-            2000 + year as u16,
-            month,
-            day,
-            hour,
-            minute,
-            second,
-        );
-
-        if !dt.is_valid() {
-            return Err(RtcError::InvalidDateTime);
-        }
-
-        Ok(dt)
+        checked_datetime(year, month, day, hour, minute, second)
     }
 
-    fn is_running(&self) -> Result<bool, RtcError> {
+    fn is_running(&mut self) -> Result<bool, RtcError> {
         let mut buf = [0u8; 1];
-        self.i2c.read_register(self.address, Self::SECONDS_REG, &mut buf)?;
+        self.regs.read(Self::SECONDS_REG, &mut buf)?;
 
         // CH bit (bit 7) = 0 means running
         Ok((buf[0] & 0x80) == 0)
@@ -272,39 +335,343 @@ impl Rtc for DS1307 {
 /// - Temperature-compensated crystal oscillator
 /// - Two alarms
 /// - Temperature sensor
-pub struct DS3231 {
-    i2c: I2c,
-    address: u8,
+pub struct DS3231<I2C = I2c> {
+    regs: Registers<I2C>,
 }
 
-impl DS3231 {
+/// DS3231 alarm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ds3231Alarm {
+    /// Alarm 1 (seconds resolution)
+    Alarm1,
+    /// Alarm 2 (minutes resolution, fires at second 00)
+    Alarm2,
+}
+
+impl Ds3231Alarm {
+    /// Interrupt enable bit in the control register, flag bit in the status register
+    const fn bit(self) -> u8 {
+        match self {
+            Ds3231Alarm::Alarm1 => 0x01,
+            Ds3231Alarm::Alarm2 => 0x02,
+        }
+    }
+}
+
+/// Alarm 1 match mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alarm1Mode {
+    /// Once per second
+    EverySecond,
+    /// When the seconds match
+    Seconds,
+    /// When minutes and seconds match
+    MinutesSeconds,
+    /// When hours, minutes and seconds match (daily)
+    HoursMinutesSeconds,
+    /// When date, hours, minutes and seconds match (monthly)
+    DateHoursMinutesSeconds,
+    /// When day of week, hours, minutes and seconds match (weekly)
+    DayHoursMinutesSeconds,
+}
+
+/// Alarm 2 match mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alarm2Mode {
+    /// Once per minute (at second 00)
+    EveryMinute,
+    /// When the minutes match
+    Minutes,
+    /// When hours and minutes match (daily)
+    HoursMinutes,
+    /// When date, hours and minutes match (monthly)
+    DateHoursMinutes,
+    /// When day of week, hours and minutes match (weekly)
+    DayHoursMinutes,
+}
+
+/// DS3231 INT/SQW pin square-wave frequency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ds3231SquareWave {
+    /// Square wave off; the pin signals alarms
+    Off,
+    /// 1 Hz
+    Hz1,
+    /// 1.024 kHz
+    Hz1024,
+    /// 4.096 kHz
+    Hz4096,
+    /// 8.192 kHz
+    Hz8192,
+}
+
+/// Alarm register values: `matched` fields (seconds first) are compared,
+/// the rest have their mask bit set; `day` selects day of week over date
+fn alarm_registers<const N: usize>(values: [u8; N], matched: usize, day: bool) -> [u8; N] {
+    let mut registers = [0u8; N];
+    for (i, (register, value)) in registers.iter_mut().zip(values).enumerate() {
+        *register = bin2bcd(value);
+        if i >= matched {
+            *register |= 0x80;
+        }
+    }
+    if day {
+        registers[N - 1] |= 0x40;
+    }
+    registers
+}
+
+impl<I2C: I2cBus> DS3231<I2C> {
     const ADDRESS: u8 = 0x68;
     const SECONDS_REG: u8 = 0x00;
+    const ALARM1_REG: u8 = 0x07;
+    const ALARM2_REG: u8 = 0x0B;
+    const CONTROL_REG: u8 = 0x0E;
     const STATUS_REG: u8 = 0x0F;
+    const AGING_REG: u8 = 0x10;
+    const TEMP_MSB_REG: u8 = 0x11;
+
+    // Control register bits
+    const CONV: u8 = 0x20;   // Start temperature conversion
+    const RS_MASK: u8 = 0x18; // Square-wave rate select
+    const INTCN: u8 = 0x04;  // INT/SQW pin signals alarms
 
-    /// Create a new DS3231 instance
-    pub fn new(i2c: I2c) -> Self {
+    // Status register bits
+    const OSF: u8 = 0x80;    // Oscillator stop flag
+    const EN32KHZ: u8 = 0x08; // 32 kHz output enable
+    const BSY: u8 = 0x04;    // Temperature conversion busy
+
+    /// Create a new DS3231 instance on any embedded-hal I2C bus
+    pub fn new(i2c: I2C) -> Self {
         DS3231 {
-            i2c,
-            address: Self::ADDRESS,
+            regs: Registers { i2c, address: Self::ADDRESS },
         }
     }
 
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.regs.i2c
+    }
+
     /// Check if power was lost (OSF bit in status register)
-    pub fn lost_power(&self) -> Result<bool, RtcError> {
-        let mut buf = [0u8; 1];
-        self.i2c.read_register(self.address, Self::STATUS_REG, &mut buf)?;
+    pub fn lost_power(&mut self) -> Result<bool, RtcError> {
+        Ok(self.regs.read_reg(Self::STATUS_REG)? & Self::OSF != 0)
+    }
+
+    // ===== Alarms =====
+
+    /// Set alarm 1 and enable its interrupt
+    ///
+    /// Fields of `dt` not used by `mode` are ignored. The INT/SQW pin is
+    /// switched to alarm output, which stops any square wave.
+    ///
+    /// # Example
+    /// ```no_run
+    /// // Every day at 07:30:00
+    /// rtc.set_alarm1(&DateTime::new(2025, 1, 1, 7, 30, 0), Alarm1Mode::HoursMinutesSeconds)?;
+    /// ```
+    pub fn set_alarm1(&mut self, dt: &DateTime, mode: Alarm1Mode) -> Result<(), RtcError> {
+        let (matched, day) = match mode {
+            Alarm1Mode::EverySecond => (0, false),
+            Alarm1Mode::Seconds => (1, false),
+            Alarm1Mode::MinutesSeconds => (2, false),
+            Alarm1Mode::HoursMinutesSeconds => (3, false),
+            Alarm1Mode::DateHoursMinutesSeconds => (4, false),
+            Alarm1Mode::DayHoursMinutesSeconds => (4, true),
+        };
+        let date = if day { dt.day_of_week() + 1 } else { dt.day() };
+        let registers = alarm_registers([dt.second(), dt.minute(), dt.hour(), date], matched, day);
+
+        self.regs.write(Self::ALARM1_REG, &registers)?;
+        self.enable_alarm(Ds3231Alarm::Alarm1)
+    }
+
+    /// Set alarm 2 and enable its interrupt
+    ///
+    /// Fields of `dt` not used by `mode` are ignored (alarm 2 has no seconds).
+    /// The INT/SQW pin is switched to alarm output, which stops any square wave.
+    pub fn set_alarm2(&mut self, dt: &DateTime, mode: Alarm2Mode) -> Result<(), RtcError> {
+        let (matched, day) = match mode {
+            Alarm2Mode::EveryMinute => (0, false),
+            Alarm2Mode::Minutes => (1, false),
+            Alarm2Mode::HoursMinutes => (2, false),
+            Alarm2Mode::DateHoursMinutes => (3, false),
+            Alarm2Mode::DayHoursMinutes => (3, true),
+        };
+        let date = if day { dt.day_of_week() + 1 } else { dt.day() };
+        let registers = alarm_registers([dt.minute(), dt.hour(), date], matched, day);
+
+        self.regs.write(Self::ALARM2_REG, &registers)?;
+        self.enable_alarm(Ds3231Alarm::Alarm2)
+    }
+
+    /// Clear the alarm's flag and route it to the INT/SQW pin
+    fn enable_alarm(&mut self, alarm: Ds3231Alarm) -> Result<(), RtcError> {
+        self.clear_alarm(alarm)?;
+        self.regs.update_reg(Self::CONTROL_REG, 0, Self::INTCN | alarm.bit())
+    }
+
+    /// Disable an alarm's interrupt
+    ///
+    /// The alarm flag still sets on a match but no longer drives INT/SQW.
+    pub fn disable_alarm(&mut self, alarm: Ds3231Alarm) -> Result<(), RtcError> {
+        self.regs.update_reg(Self::CONTROL_REG, alarm.bit(), 0)
+    }
+
+    /// Check whether an alarm has fired since its flag was last cleared
+    pub fn alarm_fired(&mut self, alarm: Ds3231Alarm) -> Result<bool, RtcError> {
+        Ok(self.regs.read_reg(Self::STATUS_REG)? & alarm.bit() != 0)
+    }
+
+    /// Clear an alarm's flag, releasing the INT/SQW pin if no other alarm is pending
+    pub fn clear_alarm(&mut self, alarm: Ds3231Alarm) -> Result<(), RtcError> {
+        self.regs.update_reg(Self::STATUS_REG, alarm.bit(), 0)
+    }
+
+    /// Sleep until an enabled alarm pulls INT/SQW low
+    ///
+    /// INT/SQW must be wired to the pin of `interrupt` (D2 for INT0, D3 for
+    /// INT1); its internal pull-up is enabled. The interrupt is attached as
+    /// level-triggered for the duration of the sleep, so this works from
+    /// power-down. Other wake-ups (such as Timer0 in idle mode) put the MCU
+    /// back to sleep.
+    ///
+    /// Returns the alarm that fired, with its flag cleared. If both alarms
+    /// fired, alarm 1 is reported and the next call returns alarm 2 at once.
+    ///
+    /// # Example
+    /// ```no_run
+    /// rtc.set_alarm2(&DateTime::new(2025, 1, 1, 0, 0, 0), Alarm2Mode::EveryMinute)?;
+    /// loop {
+    ///     rtc.sleep_until_alarm(ExternalInterrupt::Int0, SleepMode::PowerDown)?;
+    ///     // Once a minute
+    /// }
+    /// ```
+    pub fn sleep_until_alarm(
+        &mut self,
+        interrupt: ExternalInterrupt,
+        mode: SleepMode,
+    ) -> Result<Ds3231Alarm, RtcError> {
+        fn alarm_handler() {}
+
+        let (pin, detach): (u8, fn()) = match interrupt {
+            ExternalInterrupt::Int0 => (2, || detach_interrupt(ExternalInterrupt::Int0)),
+            ExternalInterrupt::Int1 => (3, || detach_interrupt(ExternalInterrupt::Int1)),
+        };
+        pin_mode(pin, INPUT_PULLUP);
+
+        // The low level keeps firing while the alarm flag is set
+        let config = WakeConfig::new(mode).external(interrupt).after_wake(detach);
+        loop {
+            attach_interrupt(interrupt, InterruptMode::Low, alarm_handler);
+            let source = Sleep::sleep_with(&config).map_err(RtcError::Wake)?;
+            if source == Some(WakeSource::External(interrupt)) {
+                break;
+            }
+            detach();
+        }
+
+        let status = self.regs.read_reg(Self::STATUS_REG)?;
+        let alarm = if status & Ds3231Alarm::Alarm1.bit() != 0 {
+            Ds3231Alarm::Alarm1
+        } else {
+            Ds3231Alarm::Alarm2
+        };
+        self.clear_alarm(alarm)?;
+        Ok(alarm)
+    }
+
+    // ===== Temperature =====
+
+    /// Read the temperature in degrees Celsius (0.25 degree resolution)
+    ///
+    /// The DS3231 converts every 64 seconds; see `convert_temperature()`.
+    pub fn temperature(&mut self) -> Result<f32, RtcError> {
+        Ok(self.temperature_quarters()? as f32 * 0.25)
+    }
+
+    /// Read the temperature in units of 0.25 degrees Celsius
+    pub fn temperature_quarters(&mut self) -> Result<i16, RtcError> {
+        let mut buf = [0u8; 2];
+        self.regs.read(Self::TEMP_MSB_REG, &mut buf)?;
+
+        // 10-bit two's complement: integer part in MSB, fraction in LSB bits 7-6
+        Ok(i16::from_be_bytes(buf) >> 6)
+    }
+
+    /// Start a temperature conversion and wait for it to finish
+    ///
+    /// Also updates the oscillator compensation.
+    pub fn convert_temperature(&mut self) -> Result<(), RtcError> {
+        // Wait for any automatic conversion to complete first
+        while self.regs.read_reg(Self::STATUS_REG)? & Self::BSY != 0 {}
+
+        self.regs.update_reg(Self::CONTROL_REG, 0, Self::CONV)?;
+        while self.regs.read_reg(Self::CONTROL_REG)? & Self::CONV != 0 {}
+        Ok(())
+    }
+
+    // ===== Calibration and outputs =====
+
+    /// Read the aging offset
+    pub fn aging_offset(&mut self) -> Result<i8, RtcError> {
+        Ok(self.regs.read_reg(Self::AGING_REG)? as i8)
+    }
+
+    /// Set the aging offset used to trim the oscillator
+    ///
+    /// Each step is about 0.1 ppm at 25 degrees; positive values slow the
+    /// clock down. Takes effect at the next temperature conversion, which
+    /// this starts.
+    pub fn set_aging_offset(&mut self, offset: i8) -> Result<(), RtcError> {
+        self.regs.write_reg(Self::AGING_REG, offset as u8)?;
+        self.convert_temperature()
+    }
+
+    /// Enable or disable the 32 kHz output pin
+    pub fn set_32khz_output(&mut self, enabled: bool) -> Result<(), RtcError> {
+        if enabled {
+            self.regs.update_reg(Self::STATUS_REG, 0, Self::EN32KHZ)
+        } else {
+            self.regs.update_reg(Self::STATUS_REG, Self::EN32KHZ, 0)
+        }
+    }
 
-        // OSF bit (bit 7)
-        Ok((buf[0] & 0x80) != 0)
+    /// Set the INT/SQW square-wave frequency
+    ///
+    /// Any frequency other than `Off` takes the pin away from the alarms;
+    /// `Off` routes the alarms back to it.
+    pub fn set_square_wave(&mut self, frequency: Ds3231SquareWave) -> Result<(), RtcError> {
+        let bits = match frequency {
+            Ds3231SquareWave::Off => Self::INTCN,
+            Ds3231SquareWave::Hz1 => 0x00,
+            Ds3231SquareWave::Hz1024 => 0x08,
+            Ds3231SquareWave::Hz4096 => 0x10,
+            Ds3231SquareWave::Hz8192 => 0x18,
+        };
+        self.regs.update_reg(Self::CONTROL_REG, Self::RS_MASK | Self::INTCN, bits)
+    }
+
+    /// Read the INT/SQW square-wave frequency
+    pub fn square_wave(&mut self) -> Result<Ds3231SquareWave, RtcError> {
+        let control = self.regs.read_reg(Self::CONTROL_REG)?;
+        if control & Self::INTCN != 0 {
+            return Ok(Ds3231SquareWave::Off);
+        }
+        Ok(match control & Self::RS_MASK {
+            0x00 => Ds3231SquareWave::Hz1,
+            0x08 => Ds3231SquareWave::Hz1024,
+            0x10 => Ds3231SquareWave::Hz4096,
+            _ => Ds3231SquareWave::Hz8192,
+        })
     }
 }
 
-impl Rtc for DS3231 {
+impl<I2C: I2cBus> Rtc for DS3231<I2C> {
     fn begin(&mut self) -> Result<(), RtcError> {
         // Check if we can communicate with the device
         let mut buf = [0u8; 1];
-        self.i2c.read_register(self.address, Self::SECONDS_REG, &mut buf)?;
+        self.regs.read(Self::SECONDS_REG, &mut buf)?;
         Ok(())
     }
 
@@ -315,30 +682,25 @@ impl Rtc for DS3231 {
 
         // Prepare 7 bytes: seconds, minutes, hours, day-of-week, date, month, year
         let data = [
-            bin2bcd(dt.second),
-            bin2bcd(dt.minute),
-            bin2bcd(dt.hour),            // 24-hour format
+            bin2bcd(dt.second()),
+            bin2bcd(dt.minute()),
+            bin2bcd(dt.hour()),            // 24-hour format
             bin2bcd(dt.day_of_week() + 1), // 1-7 format
-            bin2bcd(dt.day),
-            bin2bcd(dt.month),
-            bin2bcd(dt.year_offset),
+            bin2bcd(dt.day()),
+            bin2bcd(dt.month()),
+            bin2bcd((dt.year() - 2000) as u8),
         ];
 
-        self.i2c.write_register(self.address, Self::SECONDS_REG, &data)?;
+        self.regs.write(Self::SECONDS_REG, &data)?;
 
         // Clear OSF bit after setting time
-        let mut status = [0u8; 1];
-        self.i2c.read_register(self.address, Self::STATUS_REG, &mut status)?;
-        status[0] &= !0x80;
-        self.i2c.write_register(self.address, Self::STATUS_REG, &status)?;
-
-        Ok(())
+        self.regs.update_reg(Self::STATUS_REG, Self::OSF, 0)
     }
 
-    fn now(&self) -> Result<DateTime, RtcError> {
+    fn now(&mut self) -> Result<DateTime, RtcError> {
         // Read 7 bytes starting at seconds register
         let mut buffer = [0u8; 7];
-        self.i2c.read_register(self.address, Self::SECONDS_REG, &mut buffer)?;
+        self.regs.read(Self::SECONDS_REG, &mut buffer)?;
 
         let second = bcd2bin(buffer[0] & 0x7F);
         let minute = bcd2bin(buffer[1]);
@@ -347,24 +709,249 @@ impl Rtc for DS3231 {
         let month = bcd2bin(buffer[5]);
         let year = bcd2bin(buffer[6]);
 
-        let dt = DateTime::new(
-            2000 + year as u16,
-            month,
-            day,
-            hour,
-            minute,
-            second,
-        );
+        checked_datetime(year, month, day, hour, minute, second)
+    }
+
+    fn is_running(&mut self) -> Result<bool, RtcError> {
+        // DS3231 doesn't have CH bit, check if OSF bit indicates power loss
+        Ok(!self.lost_power()?)
+    }
+}
+
+/// PCF8563 Real-Time Clock
+///
+/// Features:
+/// - I2C address: 0x51
+/// - Voltage-low flag marking the time as unreliable after a power loss
+/// - Programmable CLKOUT pin (open-drain)
+/// - Weekday stored as 0-6 and a century bit in the month register
+pub struct PCF8563<I2C = I2c> {
+    regs: Registers<I2C>,
+}
+
+/// PCF8563 CLKOUT pin frequency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pcf8563Clkout {
+    /// Output disabled (high impedance)
+    Off,
+    /// 32.768 kHz square wave (power-on default)
+    Hz32768,
+    /// 1.024 kHz square wave
+    Hz1024,
+    /// 32 Hz square wave
+    Hz32,
+    /// 1 Hz square wave
+    Hz1,
+}
+
+impl<I2C: I2cBus> PCF8563<I2C> {
+    const ADDRESS: u8 = 0x51;
+    const CONTROL1_REG: u8 = 0x00;
+    const SECONDS_REG: u8 = 0x02;
+    const CLKOUT_REG: u8 = 0x0D;
+
+    // Register bits
+    const STOP: u8 = 0x20;   // Control 1: clock stopped
+    const VL: u8 = 0x80;     // Seconds: voltage low, integrity not guaranteed
+    const CENTURY: u8 = 0x80; // Month: century bit
+    const FE: u8 = 0x80;     // CLKOUT: output enable
+
+    /// Create a new PCF8563 instance on any embedded-hal I2C bus
+    pub fn new(i2c: I2C) -> Self {
+        PCF8563 {
+            regs: Registers { i2c, address: Self::ADDRESS },
+        }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.regs.i2c
+    }
+
+    /// Check if the supply dropped too low to keep time (VL bit)
+    ///
+    /// The flag stays set until the time is set with `adjust()`.
+    pub fn lost_power(&mut self) -> Result<bool, RtcError> {
+        Ok(self.regs.read_reg(Self::SECONDS_REG)? & Self::VL != 0)
+    }
+
+    /// Configure the CLKOUT pin
+    pub fn set_clkout(&mut self, clkout: Pcf8563Clkout) -> Result<(), RtcError> {
+        let value = match clkout {
+            Pcf8563Clkout::Off => 0x00,
+            Pcf8563Clkout::Hz32768 => Self::FE,
+            Pcf8563Clkout::Hz1024 => Self::FE | 0x01,
+            Pcf8563Clkout::Hz32 => Self::FE | 0x02,
+            Pcf8563Clkout::Hz1 => Self::FE | 0x03,
+        };
+        self.regs.write_reg(Self::CLKOUT_REG, value)
+    }
+}
+
+impl<I2C: I2cBus> Rtc for PCF8563<I2C> {
+    fn begin(&mut self) -> Result<(), RtcError> {
+        // Check if we can communicate with the device
+        self.regs.read_reg(Self::CONTROL1_REG)?;
+        Ok(())
+    }
 
+    fn adjust(&mut self, dt: &DateTime) -> Result<(), RtcError> {
         if !dt.is_valid() {
             return Err(RtcError::InvalidDateTime);
         }
 
-        Ok(dt)
+        // Writing the seconds register clears VL
+        let data = [
+            bin2bcd(dt.second()),
+            bin2bcd(dt.minute()),
+            bin2bcd(dt.hour()),
+            bin2bcd(dt.day()),
+            dt.day_of_week(),              // 0-6 format
+            bin2bcd(dt.month()),           // Century bit clear for 20xx
+            bin2bcd((dt.year() - 2000) as u8),
+        ];
+
+        self.regs.write(Self::SECONDS_REG, &data)?;
+
+        // Make sure the clock is running
+        self.regs.update_reg(Self::CONTROL1_REG, Self::STOP, 0)
     }
 
-    fn is_running(&self) -> Result<bool, RtcError> {
-        // DS3231 doesn't have CH bit, check if OSF bit indicates power loss
-        Ok(!self.lost_power()?)
+    fn now(&mut self) -> Result<DateTime, RtcError> {
+        // Read 7 bytes starting at seconds register
+        let mut buffer = [0u8; 7];
+        self.regs.read(Self::SECONDS_REG, &mut buffer)?;
+
+        if buffer[5] & Self::CENTURY != 0 {
+            // 21xx is outside the range of DateTime's two-digit year
+            return Err(RtcError::InvalidDateTime);
+        }
+
+        let second = bcd2bin(buffer[0] & 0x7F); // Mask VL bit
+        let minute = bcd2bin(buffer[1] & 0x7F);
+        let hour = bcd2bin(buffer[2] & 0x3F);
+        let day = bcd2bin(buffer[3] & 0x3F);
+        let month = bcd2bin(buffer[5] & 0x1F);
+        let year = bcd2bin(buffer[6]);
+
+        checked_datetime(year, month, day, hour, minute, second)
+    }
+
+    fn is_running(&mut self) -> Result<bool, RtcError> {
+        let stopped = self.regs.read_reg(Self::CONTROL1_REG)? & Self::STOP != 0;
+        Ok(!stopped && !self.lost_power()?)
+    }
+}
+
+/// MCP7940N Real-Time Clock
+///
+/// Features:
+/// - I2C address: 0x6F
+/// - Oscillator started with the ST bit, status reported by OSCRUN
+/// - Battery switchover enabled by VBATEN, with a power-fail flag
+/// - 64 bytes SRAM (not battery-backed unless VBATEN is set)
+pub struct MCP7940N<I2C = I2c> {
+    regs: Registers<I2C>,
+}
+
+impl<I2C: I2cBus> MCP7940N<I2C> {
+    const ADDRESS: u8 = 0x6F;
+    const SECONDS_REG: u8 = 0x00;
+    const WEEKDAY_REG: u8 = 0x03;
+
+    // Register bits
+    const ST: u8 = 0x80;     // Seconds: start oscillator
+    const OSCRUN: u8 = 0x20; // Weekday: oscillator running
+    const PWRFAIL: u8 = 0x10; // Weekday: power failed, timestamps captured
+    const VBATEN: u8 = 0x08; // Weekday: battery backup enabled
+
+    /// Register reads to wait for the oscillator to stop before setting the time
+    const STOP_POLLS: u8 = 10;
+
+    /// Create a new MCP7940N instance on any embedded-hal I2C bus
+    pub fn new(i2c: I2C) -> Self {
+        MCP7940N {
+            regs: Registers { i2c, address: Self::ADDRESS },
+        }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.regs.i2c
+    }
+
+    /// Check if main power failed while running from the battery (PWRFAIL bit)
+    pub fn power_failed(&mut self) -> Result<bool, RtcError> {
+        Ok(self.regs.read_reg(Self::WEEKDAY_REG)? & Self::PWRFAIL != 0)
+    }
+
+    /// Clear the power-fail flag
+    pub fn clear_power_failed(&mut self) -> Result<(), RtcError> {
+        self.regs.update_reg(Self::WEEKDAY_REG, Self::PWRFAIL, 0)
+    }
+
+    /// Enable or disable switching to the backup battery on VBAT
+    ///
+    /// `adjust()` enables it, so this is only needed to turn it off.
+    pub fn set_battery_backup(&mut self, enabled: bool) -> Result<(), RtcError> {
+        let set = if enabled { Self::VBATEN } else { 0 };
+        self.regs.update_reg(Self::WEEKDAY_REG, Self::VBATEN, set)
+    }
+}
+
+impl<I2C: I2cBus> Rtc for MCP7940N<I2C> {
+    fn begin(&mut self) -> Result<(), RtcError> {
+        // Check if we can communicate with the device
+        self.regs.read_reg(Self::SECONDS_REG)?;
+        Ok(())
+    }
+
+    fn adjust(&mut self, dt: &DateTime) -> Result<(), RtcError> {
+        if !dt.is_valid() {
+            return Err(RtcError::InvalidDateTime);
+        }
+
+        // Stop the oscillator so the counters cannot roll over mid-write
+        self.regs.update_reg(Self::SECONDS_REG, Self::ST, 0)?;
+        for _ in 0..Self::STOP_POLLS {
+            if self.regs.read_reg(Self::WEEKDAY_REG)? & Self::OSCRUN == 0 {
+                break;
+            }
+        }
+
+        // The leap-year bit in the month register is read-only
+        let data = [
+            bin2bcd(dt.second()),          // ST clear until everything is written
+            bin2bcd(dt.minute()),
+            bin2bcd(dt.hour()),            // 24-hour format
+            bin2bcd(dt.day_of_week() + 1) | Self::VBATEN, // 1-7 format
+            bin2bcd(dt.day()),
+            bin2bcd(dt.month()),
+            bin2bcd((dt.year() - 2000) as u8),
+        ];
+
+        self.regs.write(Self::SECONDS_REG, &data)?;
+
+        // Restart the oscillator
+        self.regs.write_reg(Self::SECONDS_REG, data[0] | Self::ST)
+    }
+
+    fn now(&mut self) -> Result<DateTime, RtcError> {
+        // Read 7 bytes starting at seconds register
+        let mut buffer = [0u8; 7];
+        self.regs.read(Self::SECONDS_REG, &mut buffer)?;
+
+        let second = bcd2bin(buffer[0] & 0x7F); // Mask ST bit
+        let minute = bcd2bin(buffer[1] & 0x7F);
+        let hour = bcd2bin(buffer[2] & 0x3F);   // Mask for 24-hour format
+        let day = bcd2bin(buffer[4] & 0x3F);
+        let month = bcd2bin(buffer[5] & 0x1F);  // Mask LPYR bit
+        let year = bcd2bin(buffer[6]);
+
+        checked_datetime(year, month, day, hour, minute, second)
+    }
+
+    fn is_running(&mut self) -> Result<bool, RtcError> {
+        Ok(self.regs.read_reg(Self::WEEKDAY_REG)? & Self::OSCRUN != 0)
     }
 }
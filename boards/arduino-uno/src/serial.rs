@@ -7,6 +7,7 @@ use core::ptr::{read_volatile, write_volatile};
 use core::cell::Cell;
 use critical_section::Mutex;
 use ufmt::uWrite;
+use crate::power::{self, PowerPeripheral};
 
 // USART0 register addresses for ATmega328P
 const UDR0: *mut u8 = 0xC6 as *mut u8;   // USART Data Register
@@ -22,7 +23,11 @@ const NO_SKIP_CHAR: u8 = 1;  // For parseInt/parseFloat - don't skip any char
 // Global timeout for stream operations (in milliseconds)
 static STREAM_TIMEOUT: Mutex<Cell<u32>> = Mutex::new(Cell::new(1000));
 
+// Baud rate of the active port (0 = not initialized), reapplied on clock changes
+static SERIAL_BAUD: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
+
 // UCSR0A bits
+const U2X0: u8 = 1;   // Double the USART Transmission Speed
 const UDRE0: u8 = 5;  // USART Data Register Empty
 const RXC0: u8 = 7;   // Receive Complete
 const TXC0: u8 = 6;   // Transmit Complete
@@ -43,31 +48,25 @@ pub struct Serial {
 impl Serial {
     /// Initialize the serial port with the specified baud rate
     ///
+    /// The UBRR divider is derived from the current CPU clock (see `cpu_frequency()`).
+    /// Both normal and double-speed (U2X) modes are evaluated and the one with
+    /// the smaller baud rate error is used.
+    ///
     /// For 16 MHz clock:
     /// - 9600 baud: UBRR = 103
-    /// - 115200 baud: UBRR = 8
-    /// - 57600 baud: UBRR = 16
+    /// - 57600 baud: UBRR = 34 (U2X)
+    /// - 115200 baud: UBRR = 16 (U2X)
     ///
-    /// Formula: UBRR = (F_CPU / (16 * BAUD)) - 1
+    /// Formula: UBRR = (F_CPU / (16 * BAUD)) - 1, or (F_CPU / (8 * BAUD)) - 1 with U2X
     pub fn new(baud_rate: u32) -> Self {
-        unsafe {
-            // Calculate UBRR value for 16MHz clock
-            let ubrr = match baud_rate {
-                9600 => 103u16,
-                19200 => 51u16,
-                38400 => 25u16,
-                57600 => 16u16,
-                115200 => 8u16,
-                _ => {
-                    // Generic formula (may have rounding errors)
-                    ((16_000_000u32 / (16 * baud_rate)) - 1) as u16
-                }
-            };
+        critical_section::with(|cs| {
+            SERIAL_BAUD.borrow(cs).set(baud_rate);
+        });
 
-            // Set baud rate
-            write_volatile(UBRR0H, (ubrr >> 8) as u8);
-            write_volatile(UBRR0L, (ubrr & 0xFF) as u8);
+        power::acquire(PowerPeripheral::Usart0);
+        apply_baud_rate(baud_rate);
 
+        unsafe {
             // Enable receiver and transmitter
             write_volatile(UCSR0B, (1 << RXEN0) | (1 << TXEN0));
 
@@ -776,6 +775,84 @@ impl Serial {
 
         result
     }
+
+    /// Disable the serial port
+    ///
+    /// Pending output is transmitted first. USART0 is powered down once no
+    /// other `Serial` instance is alive, and pins 0/1 become regular I/O.
+    pub fn end(self) {}
+}
+
+impl Drop for Serial {
+    fn drop(&mut self) {
+        power::release(PowerPeripheral::Usart0, || {
+            let baud_rate = critical_section::with(|cs| SERIAL_BAUD.borrow(cs).replace(0));
+            unsafe {
+                // Let the last frame leave the shift register (TXC0 is not
+                // set if nothing was ever sent, so the wait is bounded)
+                while read_volatile(UCSR0A) & (1 << UDRE0) == 0 {}
+                if baud_rate != 0 {
+                    let frame_us = 11_000_000 / baud_rate;
+                    let start = crate::micros();
+                    while read_volatile(UCSR0A) & (1 << TXC0) == 0
+                        && crate::micros().wrapping_sub(start) < frame_us
+                    {}
+                }
+
+                write_volatile(UCSR0B, 0);
+            }
+        });
+    }
+}
+
+/// Calculate the UBRR divider for a baud rate at a given clock
+///
+/// Returns (ubrr, use_u2x). Rounds to the nearest divider instead of truncating.
+fn baud_settings(f_cpu: u32, baud_rate: u32) -> (u16, bool) {
+    if baud_rate == 0 {
+        return (0, false);
+    }
+
+    // Rounded divider and resulting baud rate for a given samples-per-bit
+    let divider = |samples: u32| -> (u32, u32) {
+        let div = (f_cpu / (samples / 2 * baud_rate)).div_ceil(2);
+        let div = div.clamp(1, 4096);
+        (div, f_cpu / (samples * div))
+    };
+
+    let (div_normal, actual_normal) = divider(16);
+    let (div_u2x, actual_u2x) = divider(8);
+
+    let error_normal = actual_normal.abs_diff(baud_rate);
+    let error_u2x = actual_u2x.abs_diff(baud_rate);
+
+    if error_u2x < error_normal {
+        ((div_u2x - 1) as u16, true)
+    } else {
+        ((div_normal - 1) as u16, false)
+    }
+}
+
+/// Program UBRR0 and U2X0 for a baud rate at the current CPU clock
+fn apply_baud_rate(baud_rate: u32) {
+    let (ubrr, u2x) = baud_settings(crate::clock::cpu_frequency(), baud_rate);
+
+    unsafe {
+        // Only U2X0 (and MPCM0, unused) are writable; writing 0 to TXC0 leaves it untouched
+        write_volatile(UCSR0A, if u2x { 1 << U2X0 } else { 0 });
+
+        // Set baud rate
+        write_volatile(UBRR0H, (ubrr >> 8) as u8);
+        write_volatile(UBRR0L, (ubrr & 0xFF) as u8);
+    }
+}
+
+/// Reapply the active baud rate after a CPU clock change
+pub(crate) fn recalibrate() {
+    let baud_rate = critical_section::with(|cs| SERIAL_BAUD.borrow(cs).get());
+    if baud_rate != 0 {
+        apply_baud_rate(baud_rate);
+    }
 }
 
 // Implement uWrite trait for ufmt compatibility
@@ -9,36 +9,98 @@
 //! - MAX_PULSE_WIDTH: 2400 microseconds (180°)
 //! - REFRESH_INTERVAL: 20000 microseconds (20ms)
 //!
-//! This implementation uses Timer1 with interrupts to generate servo pulses
-//! in the background without blocking the main program.
+//! Timer1 runs in one of two modes, chosen automatically from the attached pins:
+//!
+//! - Hardware PWM: while only D9 (OC1A) and D10 (OC1B) are in use, Timer1
+//!   generates the pulses itself in fast PWM mode with a 50 Hz frame (TOP in
+//!   ICR1) and 0.5 us resolution. Other interrupts cannot delay the pulse
+//!   edges, so the servos do not jitter.
+//! - ISR multiplexer: as soon as a servo is attached to any other pin, all
+//!   servos (up to 12) are pulsed one after another from the Timer1 compare
+//!   interrupt. Pulse edges move slightly when Timer0 (millis) or Serial
+//!   interrupts delay the handler.
+//!
+//! Either way, motion profiles started with [`Servo::move_to`] and
+//! [`Servo::move_to_with_acceleration`] are advanced once per frame by the
+//! interrupt handler, so servos move smoothly without blocking the main program.
 
 use core::ptr::{read_volatile, write_volatile};
 use core::cell::Cell;
 use critical_section::Mutex;
+use ossidata_core::irq::IrqCell;
+use crate::power::{self, PowerPeripheral};
 
 // Timer1 registers (16-bit timer)
 const TCCR1A: *mut u8 = 0x80 as *mut u8;
 const TCCR1B: *mut u8 = 0x81 as *mut u8;
 const TCNT1H: *mut u8 = 0x85 as *mut u8;
 const TCNT1L: *mut u8 = 0x84 as *mut u8;
+const ICR1H: *mut u8 = 0x87 as *mut u8;
+const ICR1L: *mut u8 = 0x86 as *mut u8;
 const OCR1AH: *mut u8 = 0x89 as *mut u8;
 const OCR1AL: *mut u8 = 0x88 as *mut u8;
+const OCR1BH: *mut u8 = 0x8B as *mut u8;
+const OCR1BL: *mut u8 = 0x8A as *mut u8;
 const TIMSK1: *mut u8 = 0x6F as *mut u8;
 const TIFR1: *mut u8 = 0x36 as *mut u8;
 
+// TCCR1A bits
+const COM1A1: u8 = 7;   // Clear OC1A on compare match (non-inverting PWM)
+const COM1B1: u8 = 5;   // Clear OC1B on compare match (non-inverting PWM)
+const WGM11: u8 = 1;
+
+// TCCR1B bits
+const WGM13: u8 = 4;
+const WGM12: u8 = 3;
+const CS11: u8 = 1;     // Prescaler 8
+
+// TIMSK1 bits
+const OCIE1A: u8 = 1;   // Compare A interrupt (multiplexer)
+const TOIE1: u8 = 0;    // Overflow interrupt (hardware PWM frame)
+
 // Servo timing constants (in microseconds)
 const MIN_PULSE_WIDTH: u16 = 544;   // Minimum pulse width (0 degrees)
 const MAX_PULSE_WIDTH: u16 = 2400;  // Maximum pulse width (180 degrees)
 const DEFAULT_PULSE_WIDTH: u16 = 1500; // Center position (90 degrees)
 const REFRESH_INTERVAL: u32 = 20000; // Standard servo refresh interval (20ms)
+const FRAMES_PER_SECOND: u32 = 1_000_000 / REFRESH_INTERVAL;
 const SERVOS_PER_TIMER: usize = 12; // Maximum servos on one timer
 
-// Timer1 prescaler: 8 at 16MHz gives 0.5us per tick
-const TIMER_PRESCALER: u16 = 8;
-const CPU_FREQ_MHZ: u16 = 16;
-// Calculate ticks per microsecond: (CPU_FREQ_MHZ * 1000000 / TIMER_PRESCALER) / 1000000
-// = CPU_FREQ_MHZ / TIMER_PRESCALER = 16 / 8 = 2 ticks per microsecond
-const TICKS_PER_US: u16 = CPU_FREQ_MHZ / TIMER_PRESCALER;
+// Multiplexer index while waiting for the next frame
+const FRAME_GAP: usize = SERVOS_PER_TIMER;
+// Delay before the first pulse when the multiplexer starts (in microseconds)
+const FRAME_START_DELAY: u32 = 100;
+
+// Pins driven directly by Timer1 in hardware PWM mode
+const OC1A_PIN: u8 = 9;
+const OC1B_PIN: u8 = 10;
+
+// Motion profile positions are kept in 1/256 us for smooth slow moves
+const POSITION_SHIFT: u32 = 8;
+
+// Timer1 prescaler: 8 at 16MHz gives 0.5us per tick (1us at 8MHz)
+const TIMER_PRESCALER: u32 = 8;
+
+/// Convert microseconds to Timer1 ticks at the current CPU clock
+#[inline]
+fn us_to_ticks(us: u32) -> u16 {
+    (crate::clock::micros_to_cycles(us) / TIMER_PRESCALER) as u16
+}
+
+/// Write a 16-bit Timer1 register (high byte first)
+#[inline]
+unsafe fn write_u16(high: *mut u8, low: *mut u8, value: u16) {
+    write_volatile(high, (value >> 8) as u8);
+    write_volatile(low, value as u8);
+}
+
+/// How Timer1 currently generates the servo pulses
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TimerMode {
+    Off,
+    Hardware,
+    Multiplexed,
+}
 
 /// Servo state
 #[derive(Clone, Copy)]
@@ -48,30 +110,93 @@ struct ServoState {
     min_pulse: u16,    // Minimum pulse width
     max_pulse: u16,    // Maximum pulse width
     is_attached: bool,
+    motion: Motion,
+}
+
+/// Motion profile towards a target pulse width, advanced once per frame
+#[derive(Clone, Copy)]
+struct Motion {
+    position: u32,      // Current pulse width in 1/256 us
+    target: u32,        // Target pulse width in 1/256 us
+    max_speed: u32,     // 1/256 us per frame, 0 = jump immediately
+    acceleration: u32,  // 1/256 us per frame², 0 = constant speed
+    speed: u32,         // Current speed in 1/256 us per frame
+}
+
+impl Motion {
+    const fn at(pulse_width: u16) -> Self {
+        let position = (pulse_width as u32) << POSITION_SHIFT;
+        Motion { position, target: position, max_speed: 0, acceleration: 0, speed: 0 }
+    }
+
+    fn is_moving(&self) -> bool {
+        self.position != self.target
+    }
+
+    /// Advance one frame and return the new pulse width in microseconds
+    fn step(&mut self) -> u16 {
+        let distance = self.position.abs_diff(self.target);
+        if distance == 0 {
+            self.speed = 0;
+        } else if self.max_speed == 0 {
+            self.position = self.target;
+        } else {
+            if self.acceleration == 0 {
+                self.speed = self.max_speed;
+            } else {
+                // Start braking once the stopping distance reaches the target
+                let stopping = self.speed * self.speed / (2 * self.acceleration);
+                self.speed = if stopping >= distance {
+                    self.speed.saturating_sub(self.acceleration).max(self.acceleration)
+                } else {
+                    (self.speed + self.acceleration).min(self.max_speed)
+                };
+            }
+
+            let step = self.speed.min(distance);
+            if self.position < self.target {
+                self.position += step;
+            } else {
+                self.position -= step;
+            }
+            if self.position == self.target {
+                self.speed = 0;
+            }
+        }
+        ((self.position + (1 << (POSITION_SHIFT - 1))) >> POSITION_SHIFT) as u16
+    }
 }
 
 /// Global servo instances
-static SERVOS: Mutex<Cell<[Option<ServoState>; SERVOS_PER_TIMER]>> =
-    Mutex::new(Cell::new([None; SERVOS_PER_TIMER]));
+static SERVOS: IrqCell<[Option<ServoState>; SERVOS_PER_TIMER]> =
+    IrqCell::new([None; SERVOS_PER_TIMER]);
 
 static SERVO_COUNT: Mutex<Cell<usize>> = Mutex::new(Cell::new(0));
-static TIMER_INITIALIZED: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
+static TIMER_MODE: Mutex<Cell<TimerMode>> = Mutex::new(Cell::new(TimerMode::Off));
 static CURRENT_SERVO_INDEX: Mutex<Cell<usize>> = Mutex::new(Cell::new(0));
-static SERVO_FRAME_CYCLE_ACTIVE: Mutex<Cell<bool>> = Mutex::new(Cell::new(false));
 
 /// Servo motor controller
 ///
-/// Controls RC servo motors using Timer1 interrupts. Supports up to 12 servos.
-/// Uses interrupt-driven PWM generation for smooth, non-blocking operation.
+/// Controls RC servo motors using Timer1. Supports up to 12 servos.
+///
+/// While only D9 and D10 are attached, the pulses come from Timer1's
+/// hardware PWM outputs (OC1A/OC1B) with 0.5 us resolution and no jitter.
+/// Attaching any other pin switches all servos to the interrupt-driven
+/// multiplexer, whose pulse edges can be delayed by other interrupts.
 ///
 /// # Example
 /// ```no_run
 /// use arduino_uno::Servo;
 ///
 /// let mut servo = Servo::new();
-/// servo.attach(9);  // Attach to pin 9
+/// servo.attach(9);  // Attach to pin 9 (hardware PWM)
 /// servo.write(90);  // Move to 90 degrees
-/// // Servo continues to hold position via interrupts
+///
+/// // Sweep to 180 degrees at 60 degrees per second in the background
+/// servo.move_to(180, 60);
+/// while servo.is_moving() {
+///     // Do other work
+/// }
 /// ```
 pub struct Servo {
     index: usize,
@@ -90,15 +215,16 @@ impl Servo {
             SERVO_COUNT.borrow(cs).set(count + 1);
 
             // Initialize servo state
-            let mut servos = SERVOS.borrow(cs).get();
-            servos[idx] = Some(ServoState {
-                pin: 0,
-                pulse_width: DEFAULT_PULSE_WIDTH,
-                min_pulse: MIN_PULSE_WIDTH,
-                max_pulse: MAX_PULSE_WIDTH,
-                is_attached: false,
+            SERVOS.lock(|servos| {
+                servos[idx] = Some(ServoState {
+                    pin: 0,
+                    pulse_width: DEFAULT_PULSE_WIDTH,
+                    min_pulse: MIN_PULSE_WIDTH,
+                    max_pulse: MAX_PULSE_WIDTH,
+                    is_attached: false,
+                    motion: Motion::at(DEFAULT_PULSE_WIDTH),
+                });
             });
-            SERVOS.borrow(cs).set(servos);
 
             idx
         });
@@ -112,31 +238,40 @@ impl Servo {
     }
 
     /// Attach servo to a pin with custom pulse width limits
+    ///
+    /// Limits given in the wrong order are swapped.
     pub fn attach_with_limits(&mut self, pin: u8, min: u16, max: u16) -> u8 {
+        // Every user of the limits, including the Timer1 ISR, relies on min <= max
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
         critical_section::with(|cs| {
-            // Initialize timer if needed
-            if !TIMER_INITIALIZED.borrow(cs).get() {
-                init_timer1_for_servos();
-                TIMER_INITIALIZED.borrow(cs).set(true);
-            }
+            let was_attached = SERVOS.lock(|servos| {
+                let Some(servo) = &mut servos[self.index] else {
+                    return true;
+                };
+                let was_attached = servo.is_attached;
+                if was_attached && servo.pin != pin {
+                    crate::digital_write(servo.pin, crate::PinState::Low);
+                }
 
-            let mut servos = SERVOS.borrow(cs).get();
-            if let Some(servo) = &mut servos[self.index] {
                 servo.pin = pin;
                 servo.min_pulse = min;
                 servo.max_pulse = max;
+                servo.pulse_width = servo.pulse_width.clamp(min, max);
+                servo.motion = Motion::at(servo.pulse_width);
                 servo.is_attached = true;
+                was_attached
+            });
 
-                // Set pin as output
-                crate::pin_mode(pin, crate::OUTPUT);
-
-                // Start servo frame generation if not already active
-                if !SERVO_FRAME_CYCLE_ACTIVE.borrow(cs).get() {
-                    SERVO_FRAME_CYCLE_ACTIVE.borrow(cs).set(true);
-                    start_servo_cycle();
-                }
+            // Each attached servo holds Timer1
+            if !was_attached {
+                power::acquire(PowerPeripheral::Timer1);
             }
-            SERVOS.borrow(cs).set(servos);
+
+            // Set pin as output
+            crate::digital_write(pin, crate::PinState::Low);
+            crate::pin_mode(pin, crate::OUTPUT);
+
+            update_timer_mode(cs);
         });
 
         pin
@@ -145,51 +280,98 @@ impl Servo {
     /// Detach servo from its pin
     pub fn detach(&mut self) {
         critical_section::with(|cs| {
-            let mut servos = SERVOS.borrow(cs).get();
-            if let Some(servo) = &mut servos[self.index] {
-                servo.is_attached = false;
-                crate::digital_write(servo.pin, crate::PinState::Low);
+            let detached = SERVOS.lock(|servos| match &mut servos[self.index] {
+                Some(servo) if servo.is_attached => {
+                    servo.is_attached = false;
+                    Some(servo.pin)
+                }
+                _ => None,
+            });
+
+            if let Some(pin) = detached {
+                update_timer_mode(cs);
+                crate::digital_write(pin, crate::PinState::Low);
+                power::release(PowerPeripheral::Timer1, || {});
             }
-            SERVOS.borrow(cs).set(servos);
         });
     }
 
     /// Write angle to servo (0-180 degrees)
     pub fn write(&mut self, angle: u16) {
-        let angle = angle.min(180);
-
-        let pulse_width = critical_section::with(|cs| {
-            let servos = SERVOS.borrow(cs).get();
-            if let Some(servo) = &servos[self.index] {
-                // Map angle (0-180) to pulse width using servo's min/max limits
-                let range = (servo.max_pulse - servo.min_pulse) as u32;
-                Some(servo.min_pulse + ((angle as u32 * range) / 180) as u16)
-            } else {
-                None
-            }
-        });
-
-        if let Some(pw) = pulse_width {
+        if let Some(pw) = self.angle_to_pulse(angle) {
             self.write_microseconds(pw);
         }
     }
 
     /// Write pulse width to servo in microseconds
+    ///
+    /// Jumps straight to the new position, cancelling any motion in progress.
     pub fn write_microseconds(&mut self, microseconds: u16) {
-        critical_section::with(|cs| {
-            let mut servos = SERVOS.borrow(cs).get();
+        SERVOS.lock(|servos| {
             if let Some(servo) = &mut servos[self.index] {
                 // Constrain to servo's min/max limits
                 servo.pulse_width = microseconds.max(servo.min_pulse).min(servo.max_pulse);
+                servo.motion = Motion::at(servo.pulse_width);
+            }
+        });
+    }
+
+    /// Move to `angle` (0-180 degrees) at a constant speed in degrees per second
+    ///
+    /// Returns immediately; the position is advanced every 20ms frame in the
+    /// background. A speed of 0 jumps straight to the target like [`write`](Self::write).
+    pub fn move_to(&mut self, angle: u16, speed: u16) {
+        self.move_to_with_acceleration(angle, speed, 0);
+    }
+
+    /// Move to `angle` with a trapezoidal speed profile
+    ///
+    /// The servo speeds up by `acceleration` degrees per second² up to `speed`
+    /// degrees per second, then slows down to stop on the target. An
+    /// `acceleration` of 0 moves at constant speed.
+    pub fn move_to_with_acceleration(&mut self, angle: u16, speed: u16, acceleration: u16) {
+        let Some(target) = self.angle_to_pulse(angle) else {
+            return;
+        };
+
+        SERVOS.lock(|servos| {
+            if let Some(servo) = &mut servos[self.index] {
+                // Convert degrees to 1/256 us using this servo's pulse range
+                let per_degree = (((servo.max_pulse - servo.min_pulse) as u32) << POSITION_SHIFT) / 180;
+                let max_speed = speed as u32 * per_degree / FRAMES_PER_SECOND;
+                let acceleration_step = acceleration as u32 * per_degree / (FRAMES_PER_SECOND * FRAMES_PER_SECOND);
+
+                let motion = &mut servo.motion;
+                motion.target = (target as u32) << POSITION_SHIFT;
+                // Keep the speed squared within u32 for the braking distance
+                motion.max_speed = if speed > 0 { max_speed.clamp(1, u16::MAX as u32) } else { 0 };
+                motion.acceleration = if acceleration > 0 { acceleration_step.max(1) } else { 0 };
+                motion.speed = motion.speed.min(motion.max_speed);
+            }
+        });
+    }
+
+    /// Check whether a motion started with [`move_to`](Self::move_to) is still in progress
+    pub fn is_moving(&self) -> bool {
+        SERVOS.lock(|servos| {
+            servos[self.index]
+                .as_ref()
+                .is_some_and(|servo| servo.motion.is_moving())
+        })
+    }
+
+    /// Stop a motion in progress at the current position
+    pub fn stop(&mut self) {
+        SERVOS.lock(|servos| {
+            if let Some(servo) = &mut servos[self.index] {
+                servo.motion = Motion::at(servo.pulse_width);
             }
-            SERVOS.borrow(cs).set(servos);
         });
     }
 
     /// Read current angle from servo
     pub fn read(&self) -> u16 {
-        critical_section::with(|cs| {
-            let servos = SERVOS.borrow(cs).get();
+        SERVOS.lock(|servos| {
             if let Some(servo) = &servos[self.index] {
                 // Map pulse width back to angle using servo's min/max limits
                 let range = (servo.max_pulse - servo.min_pulse) as u32;
@@ -203,8 +385,7 @@ impl Servo {
 
     /// Read current pulse width in microseconds
     pub fn read_microseconds(&self) -> u16 {
-        critical_section::with(|cs| {
-            let servos = SERVOS.borrow(cs).get();
+        SERVOS.lock(|servos| {
             if let Some(servo) = &servos[self.index] {
                 servo.pulse_width
             } else {
@@ -215,14 +396,29 @@ impl Servo {
 
     /// Check if servo is attached to a pin
     pub fn attached(&self) -> bool {
-        critical_section::with(|cs| {
-            let servos = SERVOS.borrow(cs).get();
+        SERVOS.lock(|servos| {
             servos[self.index]
                 .as_ref()
                 .map(|s| s.is_attached)
                 .unwrap_or(false)
         })
     }
+
+    /// Check if the pulses for this servo are generated by Timer1 hardware PWM
+    pub fn is_hardware_pwm(&self) -> bool {
+        self.attached() && critical_section::with(|cs| TIMER_MODE.borrow(cs).get() == TimerMode::Hardware)
+    }
+
+    /// Map an angle (0-180) to a pulse width using this servo's limits
+    fn angle_to_pulse(&self, angle: u16) -> Option<u16> {
+        let angle = angle.min(180);
+        SERVOS.lock(|servos| {
+            servos[self.index].as_ref().map(|servo| {
+                let range = (servo.max_pulse - servo.min_pulse) as u32;
+                servo.min_pulse + ((angle as u32 * range) / 180) as u16
+            })
+        })
+    }
 }
 
 impl Default for Servo {
@@ -231,113 +427,222 @@ impl Default for Servo {
     }
 }
 
+/// Reapply the hardware PWM frame length after a CPU clock change
+pub(crate) fn recalibrate() {
+    critical_section::with(|cs| {
+        if TIMER_MODE.borrow(cs).get() == TimerMode::Hardware {
+            unsafe { write_u16(ICR1H, ICR1L, us_to_ticks(REFRESH_INTERVAL) - 1) };
+        }
+    });
+}
+
+/// Check if servos own Timer1, which keeps `tone()` off it
+pub(crate) fn timer1_in_use() -> bool {
+    critical_section::with(|cs| TIMER_MODE.borrow(cs).get() != TimerMode::Off)
+}
+
+/// Switch Timer1 to the mode required by the attached servos
+fn update_timer_mode(cs: critical_section::CriticalSection<'_>) {
+    let (any_attached, all_hardware) = SERVOS.lock(|servos| {
+        let mut attached = servos.iter().flatten().filter(|servo| servo.is_attached);
+        let any = attached.clone().next().is_some();
+        let hardware = attached.all(|servo| servo.pin == OC1A_PIN || servo.pin == OC1B_PIN);
+        (any, hardware)
+    });
+
+    let mode = match (any_attached, all_hardware) {
+        (false, _) => TimerMode::Off,
+        (true, true) => TimerMode::Hardware,
+        (true, false) => TimerMode::Multiplexed,
+    };
+
+    let previous = TIMER_MODE.borrow(cs).replace(mode);
+    if mode != TimerMode::Off {
+        crate::tone::release_timer1();
+        crate::pcm::release_timer1();
+    }
+    match mode {
+        TimerMode::Off => stop_timer1(),
+        TimerMode::Hardware => {
+            if previous != TimerMode::Hardware {
+                init_timer1_hardware_pwm();
+            }
+            // Connect the outputs of the attached servos
+            update_hardware_outputs();
+        }
+        TimerMode::Multiplexed => {
+            if previous != TimerMode::Multiplexed {
+                init_timer1_for_servos();
+                start_servo_cycle();
+            }
+        }
+    }
+}
+
+/// Stop Timer1 and its servo interrupts
+fn stop_timer1() {
+    unsafe {
+        write_volatile(TCCR1B, 0);
+        write_volatile(TCCR1A, 0);
+        let timsk1 = read_volatile(TIMSK1);
+        write_volatile(TIMSK1, timsk1 & !((1 << OCIE1A) | (1 << TOIE1)));
+    }
+}
+
+/// Initialize Timer1 for hardware PWM on OC1A/OC1B
+///
+/// Fast PWM mode 14 with TOP in ICR1: the counter restarts every 20ms and
+/// each output goes high at BOTTOM and low when the counter reaches its
+/// compare register.
+fn init_timer1_hardware_pwm() {
+    unsafe {
+        stop_timer1();
+
+        // Clear any pending interrupt flags
+        write_volatile(TIFR1, 0xFF);  // Write 1 to clear flags
+
+        write_u16(ICR1H, ICR1L, us_to_ticks(REFRESH_INTERVAL) - 1);
+        write_u16(TCNT1H, TCNT1L, 0);
+        write_volatile(TCCR1A, 1 << WGM11);
+        write_volatile(TCCR1B, (1 << WGM13) | (1 << WGM12) | (1 << CS11));
+
+        // Update compare registers and motion profiles once per frame
+        let timsk1 = read_volatile(TIMSK1);
+        write_volatile(TIMSK1, timsk1 | (1 << TOIE1));
+    }
+}
+
+/// Connect OC1A/OC1B to the attached servos and load their pulse widths
+fn update_hardware_outputs() {
+    let mut com_bits = 0;
+    SERVOS.lock(|servos| {
+        for servo in servos.iter().flatten().filter(|servo| servo.is_attached) {
+            let ticks = us_to_ticks(servo.pulse_width as u32);
+            unsafe {
+                if servo.pin == OC1A_PIN {
+                    write_u16(OCR1AH, OCR1AL, ticks);
+                    com_bits |= 1 << COM1A1;
+                } else {
+                    write_u16(OCR1BH, OCR1BL, ticks);
+                    com_bits |= 1 << COM1B1;
+                }
+            }
+        }
+    });
+
+    unsafe { write_volatile(TCCR1A, (1 << WGM11) | com_bits) };
+}
+
 /// Initialize Timer1 for servo pulse generation
 fn init_timer1_for_servos() {
     unsafe {
         // Stop timer
-        write_volatile(TCCR1B, 0);
+        stop_timer1();
 
         // Clear any pending interrupt flags
         write_volatile(TIFR1, 0xFF);  // Write 1 to clear flags
 
         // Set CTC mode (Clear Timer on Compare Match) - WGM12 = 1
-        let tccr1a = read_volatile(TCCR1A);
-        write_volatile(TCCR1A, tccr1a & 0xFC);  // WGM11:10 = 00
+        // with the OC1A/OC1B outputs disconnected
 
         // Set prescaler to TIMER_PRESCALER (8): CS11 = 1, CS12:CS10 = 0
-        // This gives us TICKS_PER_US = CPU_FREQ_MHZ / TIMER_PRESCALER = 2 ticks/microsecond
-        write_volatile(TCCR1B, (1 << 3) | (1 << 1));  // WGM12 = 1, CS11 = 1 (prescaler 8)
+        // This gives 2 ticks/microsecond at 16MHz (see us_to_ticks)
+        write_volatile(TCCR1B, (1 << WGM12) | (1 << CS11));
 
         // Enable Timer1 Compare A interrupt
         let timsk1 = read_volatile(TIMSK1);
-        write_volatile(TIMSK1, timsk1 | (1 << 1));  // OCIE1A = 1
+        write_volatile(TIMSK1, timsk1 | (1 << OCIE1A));
     }
 }
 
 /// Start the servo refresh cycle
+///
+/// The interrupt handler starts the first pulse shortly afterwards.
 fn start_servo_cycle() {
     critical_section::with(|cs| {
-        CURRENT_SERVO_INDEX.borrow(cs).set(0);
+        CURRENT_SERVO_INDEX.borrow(cs).set(FRAME_GAP);
 
         unsafe {
-            // Set compare match for first servo
-            let servos = SERVOS.borrow(cs).get();
-            if let Some(servo) = &servos[0] {
-                if servo.is_attached {
-                    // Set pin high to start pulse
-                    crate::digital_write(servo.pin, crate::PinState::High);
-
-                    // Set timer to fire when pulse should end
-                    let ticks = servo.pulse_width * TICKS_PER_US;
-                    write_volatile(OCR1AH, (ticks >> 8) as u8);
-                    write_volatile(OCR1AL, (ticks & 0xFF) as u8);
-
-                    // Reset timer
-                    write_volatile(TCNT1H, 0);
-                    write_volatile(TCNT1L, 0);
-                }
-            }
+            write_u16(OCR1AH, OCR1AL, us_to_ticks(FRAME_START_DELAY));
+            write_u16(TCNT1H, TCNT1L, 0);
         }
     });
 }
 
+/// Advance the motion profiles of all attached servos by one frame
+fn step_motion(servos: &mut [Option<ServoState>; SERVOS_PER_TIMER]) {
+    for servo in servos.iter_mut().flatten().filter(|servo| servo.is_attached) {
+        if servo.motion.is_moving() {
+            servo.pulse_width = servo.motion.step().clamp(servo.min_pulse, servo.max_pulse);
+        }
+    }
+}
+
 /// Timer1 Compare A interrupt handler for servo pulse generation
 #[no_mangle]
 pub unsafe extern "avr-interrupt" fn __vector_11() {
     critical_section::with(|cs| {
         let current_index = CURRENT_SERVO_INDEX.borrow(cs).get();
-        let servos = SERVOS.borrow(cs).get();
 
-        // End current servo's pulse
-        if let Some(servo) = &servos[current_index] {
-            if servo.is_attached {
-                crate::digital_write(servo.pin, crate::PinState::Low);
+        SERVOS.lock(|servos| {
+            // End current servo's pulse (nothing to end after the frame gap)
+            if let Some(Some(servo)) = servos.get(current_index) {
+                if servo.is_attached {
+                    crate::digital_write(servo.pin, crate::PinState::Low);
+                }
             }
-        }
 
-        // Move to next servo
-        let mut next_index = current_index + 1;
+            // Move to next servo, starting from the first after the frame gap
+            let mut next_index = if current_index == FRAME_GAP { 0 } else { current_index + 1 };
 
-        // Find next attached servo or wrap around
-        while next_index < SERVOS_PER_TIMER {
-            if let Some(servo) = &servos[next_index] {
-                if servo.is_attached {
-                    // Start pulse for next servo
-                    crate::digital_write(servo.pin, crate::PinState::High);
-
-                    // Set timer for pulse width
-                    let ticks = servo.pulse_width * TICKS_PER_US;
-                    write_volatile(OCR1AH, (ticks >> 8) as u8);
-                    write_volatile(OCR1AL, (ticks & 0xFF) as u8);
-                    write_volatile(TCNT1H, 0);
-                    write_volatile(TCNT1L, 0);
-
-                    CURRENT_SERVO_INDEX.borrow(cs).set(next_index);
-                    return;
+            // Find next attached servo or wrap around
+            while next_index < SERVOS_PER_TIMER {
+                if let Some(servo) = &servos[next_index] {
+                    if servo.is_attached {
+                        // Start pulse for next servo
+                        crate::digital_write(servo.pin, crate::PinState::High);
+
+                        // Set timer for pulse width
+                        let ticks = us_to_ticks(servo.pulse_width as u32);
+                        write_u16(OCR1AH, OCR1AL, ticks);
+                        write_u16(TCNT1H, TCNT1L, 0);
+
+                        CURRENT_SERVO_INDEX.borrow(cs).set(next_index);
+                        return;
+                    }
                 }
+                next_index += 1;
             }
-            next_index += 1;
-        }
 
-        // All servos done, wait for next frame
-        // Calculate time remaining in 20ms frame
-        let total_pulse_time: u32 = (0..SERVOS_PER_TIMER)
-            .filter_map(|i| servos[i])
-            .filter(|s| s.is_attached)
-            .map(|s| s.pulse_width as u32)
-            .sum();
+            // All servos done, wait for next frame
+            // Calculate time remaining in 20ms frame
+            let total_pulse_time: u32 = servos
+                .iter()
+                .flatten()
+                .filter(|s| s.is_attached)
+                .map(|s| s.pulse_width as u32)
+                .sum();
+
+            let remaining_time = REFRESH_INTERVAL.saturating_sub(total_pulse_time);
+            let ticks = us_to_ticks(remaining_time);
 
-        let remaining_time = REFRESH_INTERVAL.saturating_sub(total_pulse_time);
-        let ticks = (remaining_time * TICKS_PER_US as u32) as u16;
+            write_u16(OCR1AH, OCR1AL, ticks);
+            write_u16(TCNT1H, TCNT1L, 0);
 
-        write_volatile(OCR1AH, (ticks >> 8) as u8);
-        write_volatile(OCR1AL, (ticks & 0xFF) as u8);
-        write_volatile(TCNT1H, 0);
-        write_volatile(TCNT1L, 0);
+            // Restart cycle
+            CURRENT_SERVO_INDEX.borrow(cs).set(FRAME_GAP);
 
-        // Restart cycle
-        CURRENT_SERVO_INDEX.borrow(cs).set(0);
+            // Between frames is the place to move servos along their profiles
+            step_motion(servos);
 
-        // Start first servo on next interrupt
+            // Start first servo on next interrupt
+        });
     });
 }
+
+/// Timer1 overflow interrupt handler, once per frame in hardware PWM mode
+#[no_mangle]
+pub unsafe extern "avr-interrupt" fn __vector_13() {
+    SERVOS.lock(step_motion);
+    update_hardware_outputs();
+}
@@ -0,0 +1,218 @@
+//! Sharing one I2C or SPI bus between several drivers
+//!
+//! Drivers such as [`Lcd`](crate::Lcd), [`DS3231`](crate::DS3231) and most
+//! embedded-hal sensor crates take ownership of their bus, and creating a
+//! second `I2c` or `Spi` re-initializes the hardware. Instead, move the
+//! peripheral into a [`SharedI2c`] or [`SharedSpi`] and give each driver a
+//! proxy from `device()`:
+//!
+//! - [`I2cDevice`] implements `embedded_hal::i2c::I2c`
+//! - [`SpiDevice`] implements `embedded_hal::spi::SpiDevice`, with its own
+//!   chip select pin and [`SpiSettings`] applied at the start of every
+//!   transaction
+//!
+//! The peripheral lives in a `RefCell`, and each transaction mutably borrows
+//! it. The borrow is taken and released inside a critical section, but the
+//! transfer itself runs with interrupts enabled so `millis()` and the I2C
+//! timeout keep working; a `critical_section::Mutex` alone would only lend
+//! the bus out with interrupts disabled. A proxy used while the bus is
+//! already borrowed, for example from an interrupt handler that preempted
+//! another transaction, fails with a `Busy` error instead of corrupting the
+//! transfer in progress.
+//!
+//! # Example
+//! ```no_run
+//! use arduino_uno::{I2c, Lcd, SharedI2c, DS3231};
+//!
+//! let bus = SharedI2c::new(I2c::new());
+//! let mut lcd = Lcd::new(bus.device(), 0x27);
+//! let mut rtc = DS3231::new(bus.device());
+//! ```
+
+use core::cell::RefCell;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::{i2c, spi};
+use crate::i2c::{I2c, I2cError};
+use crate::spi::{Spi, SpiSettings};
+use crate::Delay;
+
+/// A bus peripheral in a `RefCell` whose borrow flag is only touched in critical sections
+struct BusCell<T> {
+    bus: RefCell<T>,
+}
+
+impl<T> BusCell<T> {
+    const fn new(bus: T) -> Self {
+        BusCell { bus: RefCell::new(bus) }
+    }
+
+    /// Run `f` with exclusive access to the bus, or return `None` if it is borrowed
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut bus = critical_section::with(|_| self.bus.try_borrow_mut().ok())?;
+        let result = f(&mut bus);
+        critical_section::with(|_| drop(bus));
+        Some(result)
+    }
+
+    fn into_inner(self) -> T {
+        self.bus.into_inner()
+    }
+}
+
+// The borrow flag is only read and written with interrupts disabled
+unsafe impl<T: Send> Sync for BusCell<T> {}
+
+/// An I2C bus shared by several drivers
+pub struct SharedI2c {
+    bus: BusCell<I2c>,
+}
+
+impl SharedI2c {
+    /// Share an I2C bus
+    pub const fn new(i2c: I2c) -> Self {
+        SharedI2c { bus: BusCell::new(i2c) }
+    }
+
+    /// Create a proxy to hand to a driver
+    pub fn device(&self) -> I2cDevice<'_> {
+        I2cDevice { shared: self }
+    }
+
+    /// Take the bus back (all proxies must have been dropped)
+    pub fn into_inner(self) -> I2c {
+        self.bus.into_inner()
+    }
+}
+
+/// Proxy for a [`SharedI2c`] bus, usable wherever an `I2c` is expected
+#[derive(Clone, Copy)]
+pub struct I2cDevice<'a> {
+    shared: &'a SharedI2c,
+}
+
+impl i2c::ErrorType for I2cDevice<'_> {
+    type Error = I2cError;
+}
+
+impl i2c::I2c for I2cDevice<'_> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.shared
+            .bus
+            .with(|i2c| i2c.transaction(address, operations))
+            .unwrap_or(Err(I2cError::Busy))
+    }
+}
+
+/// An SPI bus shared by several devices with their own chip select pins
+pub struct SharedSpi {
+    bus: BusCell<Spi>,
+}
+
+impl SharedSpi {
+    /// Share an SPI bus
+    pub const fn new(spi: Spi) -> Self {
+        SharedSpi { bus: BusCell::new(spi) }
+    }
+
+    /// Create a proxy for the device selected by `cs` (active low)
+    ///
+    /// The chip select pin is driven high (deselected) immediately.
+    pub fn device<CS: OutputPin>(&self, mut cs: CS, settings: SpiSettings) -> SpiDevice<'_, CS> {
+        let _ = cs.set_high();
+        SpiDevice { shared: self, cs, settings }
+    }
+
+    /// Take the bus back (all proxies must have been dropped)
+    pub fn into_inner(self) -> Spi {
+        self.bus.into_inner()
+    }
+}
+
+/// Errors from an [`SpiDevice`] transaction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpiDeviceError {
+    /// Another transaction is using the bus
+    Busy,
+    /// The chip select pin could not be driven
+    ChipSelect,
+}
+
+impl spi::Error for SpiDeviceError {
+    fn kind(&self) -> spi::ErrorKind {
+        match self {
+            SpiDeviceError::Busy => spi::ErrorKind::Other,
+            SpiDeviceError::ChipSelect => spi::ErrorKind::ChipSelectFault,
+        }
+    }
+}
+
+/// Proxy for one device on a [`SharedSpi`] bus
+pub struct SpiDevice<'a, CS> {
+    shared: &'a SharedSpi,
+    cs: CS,
+    settings: SpiSettings,
+}
+
+impl<CS> SpiDevice<'_, CS> {
+    /// Release the chip select pin
+    pub fn release(self) -> CS {
+        self.cs
+    }
+}
+
+impl<CS: OutputPin> spi::ErrorType for SpiDevice<'_, CS> {
+    type Error = SpiDeviceError;
+}
+
+impl<CS: OutputPin> spi::SpiDevice for SpiDevice<'_, CS> {
+    fn transaction(&mut self, operations: &mut [spi::Operation<'_, u8>]) -> Result<(), Self::Error> {
+        let cs = &mut self.cs;
+        let settings = self.settings;
+
+        self.shared
+            .bus
+            .with(|spi| {
+                spi.begin_transaction(settings);
+
+                let selected = cs.set_low().map_err(|_| SpiDeviceError::ChipSelect);
+                if selected.is_ok() {
+                    for operation in operations.iter_mut() {
+                        match operation {
+                            spi::Operation::Read(buffer) => spi.read(buffer),
+                            spi::Operation::Write(buffer) => spi.write(buffer),
+                            spi::Operation::Transfer(read, write) => transfer(spi, read, write),
+                            spi::Operation::TransferInPlace(buffer) => {
+                                for byte in buffer.iter_mut() {
+                                    *byte = spi.transfer(*byte);
+                                }
+                            }
+                            spi::Operation::DelayNs(ns) => Delay::new().delay_ns(*ns),
+                        }
+                    }
+                }
+
+                // Deselect and restore the bus settings on every path
+                let deselected = cs.set_high().map_err(|_| SpiDeviceError::ChipSelect);
+                spi.end_transaction();
+                selected.and(deselected)
+            })
+            .unwrap_or(Err(SpiDeviceError::Busy))
+    }
+}
+
+/// Full-duplex transfer of buffers with different lengths
+///
+/// Sends 0x00 once `write` runs out and discards bytes past the end of `read`.
+fn transfer(spi: &mut Spi, read: &mut [u8], write: &[u8]) {
+    for i in 0..read.len().max(write.len()) {
+        let received = spi.transfer(write.get(i).copied().unwrap_or(0x00));
+        if let Some(byte) = read.get_mut(i) {
+            *byte = received;
+        }
+    }
+}
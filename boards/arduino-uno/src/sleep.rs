@@ -10,11 +10,18 @@
 //! - **Standby**: Like Power Down but keeps oscillator running
 //! - **Extended Standby**: Like Power Save but keeps oscillator running
 //!
+//! [`Sleep::power_down_for`] sleeps for a fixed duration using the watchdog
+//! timer as the wake source. [`Sleep::sleep_with`] checks a [`WakeConfig`]
+//! against the sleep mode, runs its hooks and reports what woke the MCU.
+//!
 //! # Safety
 //! Sleep modes require external events (interrupts, watchdog) to wake up.
 //! Ensure proper wake-up sources are configured before entering sleep.
 
 use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::{IrqCell, IrqFlag};
+use crate::watchdog::{self, WatchdogTimeout};
+use crate::{Duration, ExternalInterrupt, InterruptMode, PcintBank};
 
 // Sleep Mode Control Register
 const SMCR: *mut u8 = 0x53 as *mut u8;
@@ -25,6 +32,58 @@ const SM0: u8 = 1;   // Sleep Mode Select bit 0
 const SM1: u8 = 2;   // Sleep Mode Select bit 1
 const SM2: u8 = 3;   // Sleep Mode Select bit 2
 
+// MCU Control Register (BOD sleep control), I/O address 0x35
+const MCUCR: *mut u8 = 0x55 as *mut u8;
+const BODS: u8 = 6;  // BOD Sleep
+const BODSE: u8 = 5; // BOD Sleep Enable
+
+// Power Reduction Register: TWI, Timer2, Timer0, Timer1, SPI, USART0, ADC
+const PRR: *mut u8 = 0x64 as *mut u8;
+const PRR_ALL: u8 = 0b1110_1111;
+
+// ADC Control and Status Register A
+const ADCSRA: *mut u8 = 0x7A as *mut u8;
+const ADEN: u8 = 7;  // ADC Enable
+
+// TWI registers (address match wake-up)
+const TWCR: *mut u8 = 0xBC as *mut u8;
+const TWAR: *mut u8 = 0xBA as *mut u8;
+const TWINT: u8 = 7; // TWI Interrupt Flag
+const TWEA: u8 = 6;  // TWI Enable Acknowledge
+const TWEN: u8 = 2;  // TWI Enable
+const TWIE: u8 = 0;  // TWI Interrupt Enable
+
+// Timer2 (asynchronous wake-up)
+const ASSR: *mut u8 = 0xB6 as *mut u8;
+const AS2: u8 = 5;   // Timer2 Asynchronous
+const TIMSK2: *mut u8 = 0x70 as *mut u8;
+
+// ADC and EEPROM interrupt enables
+const ADIE: u8 = 3;  // ADC Interrupt Enable (ADCSRA)
+const EECR: *mut u8 = 0x3F as *mut u8;
+const EERIE: u8 = 3; // EEPROM Ready Interrupt Enable
+
+// Maximum number of hooks of each kind in a WakeConfig
+const MAX_HOOKS: usize = 4;
+
+// First wake source seen while `RECORDING` is set
+static WAKE_SOURCE: IrqCell<Option<WakeSource>> = IrqCell::new(None);
+static RECORDING: IrqFlag = IrqFlag::new();
+
+// Watchdog timeouts usable as wake-up steps, longest first
+const WAKE_STEPS: [WatchdogTimeout; 10] = [
+    WatchdogTimeout::S8,
+    WatchdogTimeout::S4,
+    WatchdogTimeout::S2,
+    WatchdogTimeout::S1,
+    WatchdogTimeout::Ms500,
+    WatchdogTimeout::Ms250,
+    WatchdogTimeout::Ms125,
+    WatchdogTimeout::Ms64,
+    WatchdogTimeout::Ms32,
+    WatchdogTimeout::Ms16,
+];
+
 /// Sleep modes available on ATmega328P
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -68,6 +127,314 @@ pub enum SleepMode {
     ExtendedStandby = 0b111,
 }
 
+/// Interrupt sources that can wake the MCU from sleep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WakeSource {
+    /// External interrupt INT0/INT1 (see `attach_interrupt`)
+    External(ExternalInterrupt),
+    /// Pin change interrupt bank (see `pcint_attach`)
+    PinChange(PcintBank),
+    /// Watchdog timeout
+    Watchdog,
+    /// TWI (I2C slave) address match
+    TwiAddressMatch,
+    /// Timer2 interrupt (asynchronous mode outside of Idle)
+    Timer2,
+    /// ADC conversion complete
+    Adc,
+    /// EEPROM ready
+    EepromReady,
+    /// Any other peripheral interrupt (USART, SPI, Timer0/1), Idle only
+    Peripheral,
+}
+
+impl WakeSource {
+    /// Whether this source can wake the MCU from a sleep mode
+    ///
+    /// Follows the wake-up source table of the ATmega328P datasheet. For INT0
+    /// and INT1 this does not check the trigger mode; see [`WakeConfig::validate`].
+    pub fn can_wake(&self, mode: SleepMode) -> bool {
+        use SleepMode::*;
+        match self {
+            WakeSource::External(_)
+            | WakeSource::PinChange(_)
+            | WakeSource::Watchdog
+            | WakeSource::TwiAddressMatch => true,
+            WakeSource::Timer2 => matches!(mode, Idle | AdcNoiseReduction | PowerSave | ExtendedStandby),
+            WakeSource::Adc | WakeSource::EepromReady => matches!(mode, Idle | AdcNoiseReduction),
+            WakeSource::Peripheral => mode == Idle,
+        }
+    }
+
+    /// Whether the interrupt behind this source is enabled in hardware
+    fn is_enabled(&self, mode: SleepMode) -> bool {
+        unsafe {
+            match self {
+                WakeSource::External(interrupt) => crate::interrupt::enabled_mode(*interrupt).is_some(),
+                WakeSource::PinChange(bank) => crate::pcint::bank_enabled(*bank),
+                // Armed by `Sleep::sleep_with`
+                WakeSource::Watchdog => true,
+                WakeSource::TwiAddressMatch => {
+                    let required = (1 << TWEA) | (1 << TWEN) | (1 << TWIE);
+                    read_volatile(TWCR) & required == required && read_volatile(TWAR) != 0
+                }
+                WakeSource::Timer2 => {
+                    read_volatile(TIMSK2) != 0
+                        && (mode == SleepMode::Idle || read_volatile(ASSR) & (1 << AS2) != 0)
+                }
+                WakeSource::Adc => read_volatile(ADCSRA) & (1 << ADIE) != 0,
+                WakeSource::EepromReady => read_volatile(EECR) & (1 << EERIE) != 0,
+                WakeSource::Peripheral => true,
+            }
+        }
+    }
+}
+
+/// Reasons a [`WakeConfig`] cannot wake the MCU
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WakeError {
+    /// No wake source was requested
+    NoWakeSource,
+    /// The source cannot wake the MCU from the chosen sleep mode
+    Unsupported(WakeSource),
+    /// The source's interrupt is not enabled
+    NotEnabled(WakeSource),
+    /// INT0/INT1 only wake from this mode with a LOW level trigger
+    LevelTriggerRequired(ExternalInterrupt),
+}
+
+/// Sleep mode, wake sources and hooks for [`Sleep::sleep_with`]
+///
+/// Wake sources are declared, not configured: attach the interrupts as usual
+/// (`attach_interrupt`, `pcint_attach`, ...) and the config checks that they
+/// are enabled and able to wake the MCU from the chosen mode. The watchdog is
+/// the exception; it is armed for the sleep by the config itself.
+///
+/// # Examples
+/// ```no_run
+/// use arduino_uno::{Sleep, SleepMode, WakeConfig, WakeSource, ExternalInterrupt,
+///                   InterruptMode, WatchdogTimeout, attach_interrupt};
+///
+/// fn on_button() {}
+/// fn before() { /* flush serial, power the ADC down */ }
+/// fn after() { /* power the ADC back up */ }
+///
+/// // INT0 only wakes from power-down on a LOW level
+/// attach_interrupt(ExternalInterrupt::Int0, InterruptMode::Low, on_button);
+///
+/// let config = WakeConfig::new(SleepMode::PowerDown)
+///     .external(ExternalInterrupt::Int0)
+///     .watchdog(WatchdogTimeout::S8)
+///     .before_sleep(before)
+///     .after_wake(after);
+///
+/// match Sleep::sleep_with(&config) {
+///     Ok(Some(WakeSource::Watchdog)) => { /* periodic wake-up */ }
+///     Ok(_) => { /* button or unknown source */ }
+///     Err(_) => { /* configuration cannot wake the MCU */ }
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct WakeConfig {
+    mode: SleepMode,
+    external: [bool; 2],
+    pin_change: [bool; 3],
+    watchdog: Option<WatchdogTimeout>,
+    twi_address_match: bool,
+    timer2: bool,
+    adc: bool,
+    eeprom_ready: bool,
+    peripheral: bool,
+    before_sleep: [Option<fn()>; MAX_HOOKS],
+    after_wake: [Option<fn()>; MAX_HOOKS],
+}
+
+impl WakeConfig {
+    /// Create a config for a sleep mode with no wake sources
+    pub const fn new(mode: SleepMode) -> Self {
+        Self {
+            mode,
+            external: [false; 2],
+            pin_change: [false; 3],
+            watchdog: None,
+            twi_address_match: false,
+            timer2: false,
+            adc: false,
+            eeprom_ready: false,
+            peripheral: false,
+            before_sleep: [None; MAX_HOOKS],
+            after_wake: [None; MAX_HOOKS],
+        }
+    }
+
+    /// Sleep mode used by this config
+    pub fn mode(&self) -> SleepMode {
+        self.mode
+    }
+
+    /// Wake on an external interrupt (INT0/INT1)
+    pub const fn external(mut self, interrupt: ExternalInterrupt) -> Self {
+        self.external[interrupt as usize] = true;
+        self
+    }
+
+    /// Wake on a pin change interrupt bank
+    pub const fn pin_change(mut self, bank: PcintBank) -> Self {
+        self.pin_change[bank as usize] = true;
+        self
+    }
+
+    /// Wake after a watchdog timeout
+    ///
+    /// The watchdog is switched to interrupt mode for the sleep and its
+    /// previous configuration restored afterwards.
+    pub const fn watchdog(mut self, timeout: WatchdogTimeout) -> Self {
+        self.watchdog = Some(timeout);
+        self
+    }
+
+    /// Wake on a TWI (I2C slave) address match
+    pub const fn twi_address_match(mut self) -> Self {
+        self.twi_address_match = true;
+        self
+    }
+
+    /// Wake on a Timer2 interrupt
+    pub const fn timer2(mut self) -> Self {
+        self.timer2 = true;
+        self
+    }
+
+    /// Wake when an ADC conversion completes
+    pub const fn adc(mut self) -> Self {
+        self.adc = true;
+        self
+    }
+
+    /// Wake when the EEPROM is ready
+    pub const fn eeprom_ready(mut self) -> Self {
+        self.eeprom_ready = true;
+        self
+    }
+
+    /// Wake on any other peripheral interrupt (USART, SPI, Timer0/1)
+    pub const fn peripheral(mut self) -> Self {
+        self.peripheral = true;
+        self
+    }
+
+    /// Run a hook before going to sleep (in registration order)
+    ///
+    /// # Panics
+    /// Panics if more than 4 pre-sleep hooks are registered.
+    pub fn before_sleep(mut self, hook: fn()) -> Self {
+        add_hook(&mut self.before_sleep, hook);
+        self
+    }
+
+    /// Run a hook after waking up (in registration order)
+    ///
+    /// # Panics
+    /// Panics if more than 4 post-wake hooks are registered.
+    pub fn after_wake(mut self, hook: fn()) -> Self {
+        add_hook(&mut self.after_wake, hook);
+        self
+    }
+
+    /// Requested wake sources
+    fn sources(&self) -> impl Iterator<Item = WakeSource> + '_ {
+        let external = [ExternalInterrupt::Int0, ExternalInterrupt::Int1]
+            .into_iter()
+            .filter(|interrupt| self.external[*interrupt as usize])
+            .map(WakeSource::External);
+        let pin_change = [PcintBank::Bank0, PcintBank::Bank1, PcintBank::Bank2]
+            .into_iter()
+            .filter(|bank| self.pin_change[*bank as usize])
+            .map(WakeSource::PinChange);
+        let others = [
+            (self.watchdog.is_some(), WakeSource::Watchdog),
+            (self.twi_address_match, WakeSource::TwiAddressMatch),
+            (self.timer2, WakeSource::Timer2),
+            (self.adc, WakeSource::Adc),
+            (self.eeprom_ready, WakeSource::EepromReady),
+            (self.peripheral, WakeSource::Peripheral),
+        ]
+        .into_iter()
+        .filter(|(requested, _)| *requested)
+        .map(|(_, source)| source);
+
+        external.chain(pin_change).chain(others)
+    }
+
+    /// Check that every requested source can wake the MCU from the sleep mode
+    ///
+    /// Checks the current hardware state: the interrupts must already be
+    /// enabled, and outside of Idle INT0/INT1 must trigger on LOW level.
+    pub fn validate(&self) -> Result<(), WakeError> {
+        let mut any = false;
+        for source in self.sources() {
+            any = true;
+            if !source.can_wake(self.mode) {
+                return Err(WakeError::Unsupported(source));
+            }
+            if !source.is_enabled(self.mode) {
+                return Err(WakeError::NotEnabled(source));
+            }
+            if let WakeSource::External(interrupt) = source {
+                if self.mode != SleepMode::Idle
+                    && crate::interrupt::enabled_mode(interrupt) != Some(InterruptMode::Low)
+                {
+                    return Err(WakeError::LevelTriggerRequired(interrupt));
+                }
+            }
+        }
+
+        if any { Ok(()) } else { Err(WakeError::NoWakeSource) }
+    }
+}
+
+fn add_hook(hooks: &mut [Option<fn()>; MAX_HOOKS], hook: fn()) {
+    match hooks.iter_mut().find(|slot| slot.is_none()) {
+        Some(slot) => *slot = Some(hook),
+        None => panic!("Too many sleep hooks"),
+    }
+}
+
+/// Record the interrupt that woke the MCU (called from the managed ISRs)
+pub(crate) fn record_wake(source: WakeSource) {
+    if RECORDING.is_set() {
+        WAKE_SOURCE.lock(|wake| {
+            if wake.is_none() {
+                *wake = Some(source);
+            }
+        });
+    }
+}
+
+/// What to switch off during [`Sleep::power_down_for_with`]
+///
+/// Everything is restored on wake-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerDownOptions {
+    /// Disable the ADC (ADEN) while asleep
+    pub disable_adc: bool,
+    /// Disable the brown-out detector while asleep (BODS)
+    pub disable_bod: bool,
+    /// Power down all peripherals through PRR while asleep
+    pub disable_peripherals: bool,
+}
+
+impl Default for PowerDownOptions {
+    /// Default options: ADC and BOD off, PRR untouched
+    fn default() -> Self {
+        Self {
+            disable_adc: true,
+            disable_bod: true,
+            disable_peripherals: false,
+        }
+    }
+}
+
 /// Sleep mode control
 pub struct Sleep;
 
@@ -161,4 +528,158 @@ impl Sleep {
             write_volatile(SMCR, smcr & !(1 << SE));
         }
     }
+
+    /// Sleep in power-down mode for a duration, using the watchdog to wake up
+    ///
+    /// Uses [`PowerDownOptions::default`]. See [`Sleep::power_down_for_with`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use arduino_uno::{Sleep, Duration};
+    ///
+    /// loop {
+    ///     // Take a reading...
+    ///     Sleep::power_down_for(Duration::secs(60));
+    /// }
+    /// ```
+    pub fn power_down_for(duration: Duration) -> Duration {
+        Self::power_down_for_with(duration, PowerDownOptions::default())
+    }
+
+    /// Sleep in power-down mode for a duration with the given options
+    ///
+    /// The sleep is chained from watchdog timeouts (8 s down to 16 ms), so the
+    /// duration is rounded down to a multiple of 16 ms. `millis()` and
+    /// `micros()` are advanced by the time slept, since Timer0 stops in
+    /// power-down. Returns the time actually slept.
+    ///
+    /// Another wake source (external or pin change interrupt) ends the sleep
+    /// early. The watchdog cannot tell how much of the interrupted step had
+    /// passed, so half of it is counted: the average for a wake at a random
+    /// time, off by at most half the step (4 s for an 8 s step).
+    ///
+    /// # Note
+    /// - The watchdog oscillator is only accurate to about ±10%.
+    /// - Any watchdog configuration is suspended while asleep and restored after.
+    /// - Flush serial output first; the USART stops in power-down.
+    /// - Global interrupts must be enabled.
+    pub fn power_down_for_with(duration: Duration, options: PowerDownOptions) -> Duration {
+        let mut remaining = duration.to_millis();
+        let mut slept: u64 = 0;
+
+        let saved_smcr = unsafe { read_volatile(SMCR) };
+        let saved_watchdog = watchdog::control();
+        Self::set_mode(SleepMode::PowerDown);
+
+        unsafe {
+            let adcsra = read_volatile(ADCSRA);
+            let prr = read_volatile(PRR);
+
+            if options.disable_adc || options.disable_peripherals {
+                // The ADC must be disabled before it is powered down
+                write_volatile(ADCSRA, adcsra & !(1 << ADEN));
+            }
+            if options.disable_peripherals {
+                write_volatile(PRR, PRR_ALL);
+            }
+
+            while let Some(&step) = WAKE_STEPS.iter().find(|step| step.millis() as u64 <= remaining) {
+                core::arch::asm!("cli");
+                watchdog::arm_wake(step);
+                write_volatile(SMCR, read_volatile(SMCR) | (1 << SE));
+
+                if options.disable_bod {
+                    // BODS must be set within 4 cycles of BODSE and the sleep
+                    // instruction must follow within 3 cycles
+                    let mcucr = read_volatile(MCUCR);
+                    core::arch::asm!(
+                        "out 0x35, {enable}",
+                        "out 0x35, {set}",
+                        "sei",
+                        "sleep",
+                        enable = in(reg) mcucr | (1 << BODS) | (1 << BODSE),
+                        set = in(reg) (mcucr | (1 << BODS)) & !(1 << BODSE),
+                    );
+                } else {
+                    // sei delays interrupts by one instruction, so no wake-up is missed
+                    core::arch::asm!("sei", "sleep");
+                }
+
+                write_volatile(SMCR, read_volatile(SMCR) & !(1 << SE));
+
+                if watchdog::wake_pending() {
+                    // Woken by another interrupt partway through the step
+                    slept += step.millis() as u64 / 2;
+                    break;
+                }
+                slept += step.millis() as u64;
+                remaining -= step.millis() as u64;
+            }
+
+            write_volatile(PRR, prr);
+            write_volatile(ADCSRA, adcsra);
+            write_volatile(SMCR, saved_smcr);
+        }
+
+        watchdog::restore_control(saved_watchdog);
+        crate::time::advance_millis(slept);
+        Duration::millis(slept)
+    }
+
+    /// Sleep with a validated [`WakeConfig`]
+    ///
+    /// Validates the config, runs the pre-sleep hooks, sleeps until one of the
+    /// wake sources fires, then runs the post-wake hooks. Returns the source
+    /// that woke the MCU, or `None` if it could not be identified (sources
+    /// whose interrupt handler is not managed by this crate, such as the ADC
+    /// or Timer2 outside of `SoftRtc`).
+    ///
+    /// Nothing is run and the MCU does not sleep if validation fails.
+    pub fn sleep_with(config: &WakeConfig) -> Result<Option<WakeSource>, WakeError> {
+        config.validate()?;
+
+        for hook in config.before_sleep.iter().flatten() {
+            hook();
+        }
+
+        let saved_smcr = unsafe { read_volatile(SMCR) };
+        let saved_watchdog = config.watchdog.map(|_| watchdog::control());
+        Self::set_mode(config.mode);
+
+        unsafe {
+            core::arch::asm!("cli");
+            WAKE_SOURCE.set(None);
+            RECORDING.set();
+            if let Some(timeout) = config.watchdog {
+                watchdog::arm_wake(timeout);
+            }
+
+            write_volatile(SMCR, read_volatile(SMCR) | (1 << SE));
+            // sei delays interrupts by one instruction, so no wake-up is missed
+            core::arch::asm!("sei", "sleep");
+            write_volatile(SMCR, saved_smcr);
+        }
+
+        RECORDING.clear();
+        let mut source = WAKE_SOURCE.replace(None);
+        if let Some(control) = saved_watchdog {
+            if source.is_none() && !watchdog::wake_pending() {
+                source = Some(WakeSource::Watchdog);
+            }
+            watchdog::restore_control(control);
+        }
+        if source.is_none()
+            && config.twi_address_match
+            && unsafe { read_volatile(TWCR) } & (1 << TWINT) != 0
+        {
+            // TWINT is not cleared by the interrupt handler
+            source = Some(WakeSource::TwiAddressMatch);
+        }
+
+        for hook in config.after_wake.iter().flatten() {
+            hook();
+        }
+
+        Ok(source)
+    }
 }
@@ -0,0 +1,265 @@
+//! Software real-time clock
+//!
+//! [`SoftRtc`] implements the [`Rtc`] trait without an RTC chip, so it can
+//! stand in for a DS1307/DS3231 in code that is generic over `Rtc`. It keeps
+//! time from one of two sources:
+//!
+//! - [`SoftRtcSource::Crystal`]: Timer2 in asynchronous mode, clocked by a
+//!   32.768 kHz watch crystal on TOSC1/TOSC2 (PB6/PB7). Timer2 overflows
+//!   once per second and keeps running in `SleepMode::PowerSave`, so the
+//!   clock survives deep sleep. TOSC1/TOSC2 are the main crystal pins on the
+//!   Uno, so this needs a board running from the internal RC oscillator.
+//! - [`SoftRtcSource::Millis`]: the `millis()` counter. Works on any board,
+//!   but the 16 MHz resonator drifts by up to 0.5% and Timer0 stops during
+//!   power-down sleep (except in `Sleep::power_down_for`, which compensates).
+//!
+//! Both sources support drift correction in parts per million; measure the
+//! error against a reference over a day or more and pass it to
+//! [`SoftRtc::set_trim_ppm`].
+//!
+//! Time is lost on reset or power loss: `is_running()` returns `false` until
+//! the clock has been set with `adjust()`.
+
+use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::datetime::DateTime;
+use ossidata_core::irq::{IrqCell, IrqFlag};
+use crate::power::{self, PowerPeripheral};
+use crate::rtc::{Rtc, RtcError};
+use crate::sleep::{record_wake, WakeSource};
+
+// Timer2 registers (ATmega328P)
+const TCCR2A: *mut u8 = 0xB0 as *mut u8;  // Timer/Counter2 Control Register A
+const TCCR2B: *mut u8 = 0xB1 as *mut u8;  // Timer/Counter2 Control Register B
+const TCNT2: *mut u8 = 0xB2 as *mut u8;   // Timer/Counter2 (counter value)
+const TIMSK2: *mut u8 = 0x70 as *mut u8;  // Timer/Counter2 Interrupt Mask Register
+const TIFR2: *mut u8 = 0x37 as *mut u8;   // Timer/Counter2 Interrupt Flag Register
+const ASSR: *mut u8 = 0xB6 as *mut u8;    // Asynchronous Status Register
+
+// ASSR bits
+const AS2: u8 = 5;      // Timer2 clocked from TOSC1
+const ASSR_BUSY: u8 = 0x1F;  // TCN2UB, OCR2AUB, OCR2BUB, TCR2AUB, TCR2BUB
+
+// TCCR2B clock select: 32768 Hz / 128 = 256 Hz, one overflow per second
+const CS_DIV128: u8 = 0b101;
+
+// TIMSK2/TIFR2 bits
+const TOIE2: u8 = 0;    // Overflow Interrupt Enable
+const TOV2: u8 = 0;     // Overflow Flag
+
+/// Microseconds per second, the unit of the trim accumulator
+const PPM_SCALE: i32 = 1_000_000;
+
+// Crystal mode state, advanced by the Timer2 overflow interrupt
+static SECONDS: IrqCell<u32> = IrqCell::new(0);
+static TRIM_PPM: IrqCell<i32> = IrqCell::new(0);
+static TRIM_ACCUMULATOR: IrqCell<i32> = IrqCell::new(0);
+static CRYSTAL_RUNNING: IrqFlag = IrqFlag::new();
+
+/// Time source of a [`SoftRtc`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoftRtcSource {
+    /// Timer2 with a 32.768 kHz crystal on TOSC1/TOSC2
+    Crystal,
+    /// The `millis()` counter driven by Timer0
+    Millis,
+}
+
+/// Software RTC implementing [`Rtc`]
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::{DateTime, Rtc, SoftRtc, SoftRtcSource};
+///
+/// let mut rtc = SoftRtc::new(SoftRtcSource::Millis);
+/// rtc.begin()?;
+/// rtc.set_trim_ppm(-120);  // This board's resonator runs 120 ppm fast
+/// rtc.adjust(&DateTime::new(2025, 1, 5, 12, 0, 0))?;
+///
+/// let now = rtc.now()?;
+/// ```
+pub struct SoftRtc {
+    source: SoftRtcSource,
+    set: bool,
+    // Millis mode: milliseconds since 2000 at `base_millis`
+    base_ms: u64,
+    base_millis: u64,
+    trim_ppm: i32,
+}
+
+impl SoftRtc {
+    /// Create a software RTC (call `begin()` to start it)
+    pub fn new(source: SoftRtcSource) -> Self {
+        SoftRtc {
+            source,
+            set: false,
+            base_ms: 0,
+            base_millis: 0,
+            trim_ppm: 0,
+        }
+    }
+
+    /// Time source
+    pub fn source(&self) -> SoftRtcSource {
+        self.source
+    }
+
+    /// Drift correction in parts per million
+    pub fn trim_ppm(&self) -> i32 {
+        self.trim_ppm
+    }
+
+    /// Set the drift correction in parts per million
+    ///
+    /// Positive values speed the clock up, for a time source that runs slow.
+    /// 1 ppm is about 86 ms per day. Applies from now on; time already kept
+    /// is not corrected retroactively.
+    pub fn set_trim_ppm(&mut self, ppm: i32) {
+        let ppm = ppm.clamp(-PPM_SCALE / 2, PPM_SCALE / 2);
+        match self.source {
+            SoftRtcSource::Crystal => TRIM_PPM.set(ppm),
+            SoftRtcSource::Millis => {
+                self.base_ms = self.millis_now();
+                self.base_millis = crate::millis64();
+            }
+        }
+        self.trim_ppm = ppm;
+    }
+
+    /// Seconds since 2000-01-01 00:00:00
+    pub fn seconds_since_2000(&self) -> u32 {
+        match self.source {
+            SoftRtcSource::Crystal => SECONDS.get(),
+            SoftRtcSource::Millis => (self.millis_now() / 1000) as u32,
+        }
+    }
+
+    /// Milliseconds since 2000 in millis mode, with the trim applied
+    fn millis_now(&self) -> u64 {
+        let elapsed = crate::millis64() - self.base_millis;
+        let correction = elapsed as i64 * self.trim_ppm as i64 / PPM_SCALE as i64;
+        self.base_ms + (elapsed as i64 + correction) as u64
+    }
+
+    /// Wait until Timer2 register writes have reached the asynchronous clock domain
+    ///
+    /// After waking from power-save, the overflow that woke the MCU must be
+    /// synchronized before sleeping again, or the next overflow may not wake
+    /// it. Call this before re-entering `SleepMode::PowerSave` in crystal mode.
+    pub fn sync(&self) {
+        if self.source == SoftRtcSource::Crystal && CRYSTAL_RUNNING.is_set() {
+            unsafe {
+                // Rewriting a control register sets its busy flag for one TOSC cycle
+                write_volatile(TCCR2A, read_volatile(TCCR2A));
+                wait_for_async_update();
+            }
+        }
+    }
+
+    fn start_crystal(&mut self) {
+        power::acquire(PowerPeripheral::Timer2);
+
+        critical_section::with(|_| unsafe {
+            // Sequence from the datasheet for switching to the asynchronous clock
+            write_volatile(TIMSK2, 0);
+            write_volatile(ASSR, 1 << AS2);
+            write_volatile(TCNT2, 0);
+            write_volatile(TCCR2A, 0);
+            write_volatile(TCCR2B, CS_DIV128);
+        });
+
+        // The crystal may take up to a second to start
+        unsafe {
+            wait_for_async_update();
+            write_volatile(TIFR2, 1 << TOV2);
+            write_volatile(TIMSK2, 1 << TOIE2);
+        }
+        CRYSTAL_RUNNING.set();
+    }
+}
+
+/// Wait for all pending Timer2 register writes in asynchronous mode
+unsafe fn wait_for_async_update() {
+    while read_volatile(ASSR) & ASSR_BUSY != 0 {}
+}
+
+impl Rtc for SoftRtc {
+    fn begin(&mut self) -> Result<(), RtcError> {
+        if self.source == SoftRtcSource::Crystal && !CRYSTAL_RUNNING.is_set() {
+            self.start_crystal();
+        }
+        Ok(())
+    }
+
+    fn adjust(&mut self, dt: &DateTime) -> Result<(), RtcError> {
+        if !dt.is_valid() {
+            return Err(RtcError::InvalidDateTime);
+        }
+
+        let seconds = dt.seconds_since_2000();
+        match self.source {
+            SoftRtcSource::Crystal => {
+                critical_section::with(|_| unsafe {
+                    // Restart the current second
+                    write_volatile(TCNT2, 0);
+                    SECONDS.set(seconds);
+                    TRIM_ACCUMULATOR.set(0);
+                });
+                unsafe { wait_for_async_update() };
+            }
+            SoftRtcSource::Millis => {
+                self.base_ms = seconds as u64 * 1000;
+                self.base_millis = crate::millis64();
+            }
+        }
+        self.set = true;
+        Ok(())
+    }
+
+    fn now(&mut self) -> Result<DateTime, RtcError> {
+        DateTime::from_seconds_since_2000(self.seconds_since_2000())
+            .ok_or(RtcError::InvalidDateTime)
+    }
+
+    fn is_running(&mut self) -> Result<bool, RtcError> {
+        let started = match self.source {
+            SoftRtcSource::Crystal => CRYSTAL_RUNNING.is_set(),
+            SoftRtcSource::Millis => true,
+        };
+        Ok(started && self.set)
+    }
+}
+
+impl Drop for SoftRtc {
+    fn drop(&mut self) {
+        if self.source == SoftRtcSource::Crystal && CRYSTAL_RUNNING.take() {
+            power::release(PowerPeripheral::Timer2, || unsafe {
+                write_volatile(TIMSK2, 0);
+                write_volatile(TCCR2B, 0);
+                write_volatile(ASSR, 0);
+            });
+        }
+    }
+}
+
+/// Timer2 overflow interrupt handler (once per second in crystal mode)
+#[link_section = ".text"]
+#[export_name = "__vector_9"]
+pub unsafe extern "avr-interrupt" fn timer2_overflow() {
+    // Accumulate the trim in microseconds and insert or drop a whole second
+    let mut step = 1;
+    let trim = TRIM_PPM.get();
+    if trim != 0 {
+        let mut accumulator = TRIM_ACCUMULATOR.get() + trim;
+        if accumulator >= PPM_SCALE {
+            accumulator -= PPM_SCALE;
+            step = 2;
+        } else if accumulator <= -PPM_SCALE {
+            accumulator += PPM_SCALE;
+            step = 0;
+        }
+        TRIM_ACCUMULATOR.set(accumulator);
+    }
+
+    SECONDS.update(|seconds| seconds.wrapping_add(step));
+    record_wake(WakeSource::Timer2);
+}
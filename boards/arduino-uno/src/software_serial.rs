@@ -6,26 +6,18 @@
 //! Note: Only one SoftwareSerial instance can actively receive at a time.
 
 use core::ptr::{read_volatile, write_volatile};
-use core::cell::Cell;
-use critical_section::Mutex;
-use crate::ports::{digital_pin_to_port, digital_pin_to_bit_mask, port_input_register, port_output_register, port_mode_register};
+use ossidata_core::irq::{ByteQueue, IrqCell, IrqCounter, IrqFlag};
+use crate::ports::{Port, digital_pin_to_port, digital_pin_to_bit_mask, port_input_register, port_output_register, port_mode_register};
 
-// Receive buffer size
+// Receive buffer size (holds RX_BUFFER_SIZE - 1 bytes)
 const RX_BUFFER_SIZE: usize = 64;
 
-// Pin Change Interrupt registers
-const PCICR: *mut u8 = 0x68 as *mut u8;
-const PCMSK0: *mut u8 = 0x6B as *mut u8;
-const PCMSK1: *mut u8 = 0x6C as *mut u8;
-const PCMSK2: *mut u8 = 0x6D as *mut u8;
+// Maximum number of SoftwareSerial instances
+const MAX_INSTANCES: usize = 4;
 
-/// Software Serial state
+/// Software Serial configuration, fixed after `begin()`
+#[derive(Clone, Copy)]
 struct SoftwareSerialState {
-    rx_buffer: [u8; RX_BUFFER_SIZE],
-    rx_buffer_head: usize,
-    rx_buffer_tail: usize,
-    buffer_overflow: bool,
-
     // Timing delays (in 4-cycle units for tunedDelay)
     rx_delay_centering: u16,
     rx_delay_intrabit: u16,
@@ -34,19 +26,37 @@ struct SoftwareSerialState {
 
     // Pin configuration
     rx_pin: u8,
-    tx_pin: u8,
     rx_bit_mask: u8,
     tx_bit_mask: u8,
-    rx_port: *const u8,
-    tx_port: *mut u8,
+    rx_port: Port,
+    tx_port: Port,
+    // PINx register of the RX pin, cached for the receive ISR
+    rx_register: *const u8,
 
     inverse_logic: bool,
-    is_listening: bool,
 }
 
-static ACTIVE_INSTANCE: Mutex<Cell<Option<usize>>> = Mutex::new(Cell::new(None));
-static mut INSTANCES: [Option<SoftwareSerialState>; 4] = [None, None, None, None];
-static mut INSTANCE_COUNT: usize = 0;
+// The register pointer is a fixed I/O address, valid in any context
+unsafe impl Send for SoftwareSerialState {}
+
+/// Configuration of the listening instance
+///
+/// Kept in one cell so the receive ISR reaches everything it needs before
+/// the start bit in a single critical section.
+#[derive(Clone, Copy)]
+struct Receiver {
+    instance_id: usize,
+    state: SoftwareSerialState,
+}
+
+static RECEIVER: IrqCell<Option<Receiver>> = IrqCell::new(None);
+static INSTANCES: IrqCell<[Option<SoftwareSerialState>; MAX_INSTANCES]> = IrqCell::new([None; MAX_INSTANCES]);
+static INSTANCE_COUNT: IrqCounter<u8> = IrqCounter::<u8>::new();
+
+// Receive buffers: filled by the PCINT ISR, drained by read()
+static RX_BUFFERS: [ByteQueue<RX_BUFFER_SIZE>; MAX_INSTANCES] =
+    [const { ByteQueue::new() }; MAX_INSTANCES];
+static RX_OVERFLOW: [IrqFlag; MAX_INSTANCES] = [const { IrqFlag::new() }; MAX_INSTANCES];
 
 /// Software Serial instance
 pub struct SoftwareSerial {
@@ -69,61 +79,57 @@ impl SoftwareSerial {
     /// sw_serial.begin(9600);
     /// ```
     pub fn new(rx_pin: u8, tx_pin: u8, inverse_logic: bool) -> Self {
-        let instance_id = unsafe {
-            let id = INSTANCE_COUNT;
-            INSTANCE_COUNT += 1;
-
-            let rx_port = digital_pin_to_port(rx_pin);
-            let tx_port = digital_pin_to_port(tx_pin);
-            let rx_bit_mask = digital_pin_to_bit_mask(rx_pin);
-            let tx_bit_mask = digital_pin_to_bit_mask(tx_pin);
-
-            INSTANCES[id] = Some(SoftwareSerialState {
-                rx_buffer: [0; RX_BUFFER_SIZE],
-                rx_buffer_head: 0,
-                rx_buffer_tail: 0,
-                buffer_overflow: false,
-                rx_delay_centering: 0,
-                rx_delay_intrabit: 0,
-                rx_delay_stopbit: 0,
-                tx_delay: 0,
-                rx_pin,
-                tx_pin,
-                rx_bit_mask,
-                tx_bit_mask,
-                rx_port: port_input_register(rx_port),
-                tx_port: port_output_register(tx_port),
-                inverse_logic,
-                is_listening: false,
-            });
-
-            id
+        let instance_id = INSTANCE_COUNT.increment() as usize;
+
+        let state = SoftwareSerialState {
+            rx_delay_centering: 0,
+            rx_delay_intrabit: 0,
+            rx_delay_stopbit: 0,
+            tx_delay: 0,
+            rx_pin,
+            rx_bit_mask: digital_pin_to_bit_mask(rx_pin),
+            tx_bit_mask: digital_pin_to_bit_mask(tx_pin),
+            rx_port: digital_pin_to_port(rx_pin),
+            tx_port: digital_pin_to_port(tx_pin),
+            rx_register: port_input_register(digital_pin_to_port(rx_pin)),
+            inverse_logic,
         };
+        INSTANCES.lock(|instances| instances[instance_id] = Some(state));
 
         Self { instance_id }
     }
 
+    /// Get a copy of this instance's configuration
+    fn state(&self) -> Option<SoftwareSerialState> {
+        INSTANCES.lock(|instances| instances[self.instance_id])
+    }
+
     /// Initialize the software serial port at the specified baud rate
     ///
     /// # Arguments
     /// * `baud` - Baud rate (e.g., 9600, 19200, 38400)
     pub fn begin(&mut self, baud: u32) {
-        unsafe {
-            if let Some(state) = &mut INSTANCES[self.instance_id] {
-                // Calculate timing delays based on CPU frequency (16MHz) and baud rate
-                // Each cycle is 62.5ns at 16MHz
-                let bit_delay = (16_000_000 / baud) as u16;
+        let state = INSTANCES.lock(|instances| {
+            let state = instances[self.instance_id].as_mut()?;
+
+            // Calculate timing delays based on CPU frequency and baud rate
+            // Each cycle is 62.5ns at 16MHz
+            let bit_delay = (crate::clock::cpu_frequency() / baud) as u16;
 
-                // Convert to 4-cycle delays for tunedDelay
-                state.tx_delay = (bit_delay / 4).saturating_sub(15);
-                state.rx_delay_centering = (bit_delay / 2 / 4).saturating_sub(5);
-                state.rx_delay_intrabit = (bit_delay / 4).saturating_sub(15);
-                state.rx_delay_stopbit = (bit_delay / 4).saturating_sub(15);
+            // Convert to 4-cycle delays for tunedDelay
+            state.tx_delay = (bit_delay / 4).saturating_sub(15);
+            state.rx_delay_centering = (bit_delay / 2 / 4).saturating_sub(5);
+            state.rx_delay_intrabit = (bit_delay / 4).saturating_sub(15);
+            state.rx_delay_stopbit = (bit_delay / 4).saturating_sub(15);
+
+            Some(*state)
+        });
 
+        if let Some(state) = state {
+            unsafe {
                 // Set TX pin as output, idle high (or low if inverse)
-                let tx_port = digital_pin_to_port(state.tx_pin);
-                let tx_ddr = port_mode_register(tx_port);
-                let tx_port_reg = state.tx_port;
+                let tx_ddr = port_mode_register(state.tx_port);
+                let tx_port_reg = port_output_register(state.tx_port);
 
                 // Set pin mode to output
                 let ddr_val = read_volatile(tx_ddr);
@@ -138,8 +144,7 @@ impl SoftwareSerial {
                 }
 
                 // Set RX pin as input
-                let rx_port = digital_pin_to_port(state.rx_pin);
-                let rx_ddr = port_mode_register(rx_port);
+                let rx_ddr = port_mode_register(state.rx_port);
                 let ddr_val = read_volatile(rx_ddr);
                 write_volatile(rx_ddr, ddr_val & !state.rx_bit_mask);
             }
@@ -150,52 +155,50 @@ impl SoftwareSerial {
 
     /// Enable this instance to receive data
     pub fn listen(&mut self) {
-        critical_section::with(|cs| {
-            ACTIVE_INSTANCE.borrow(cs).set(Some(self.instance_id));
+        if let Some(state) = self.state() {
+            // Stop the ISR from filling the buffer while it is reset
+            RECEIVER.set(None);
 
-            unsafe {
-                if let Some(state) = &mut INSTANCES[self.instance_id] {
-                    state.is_listening = true;
-                    state.buffer_overflow = false;
-                    state.rx_buffer_head = 0;
-                    state.rx_buffer_tail = 0;
-
-                    // Enable PCINT for RX pin
-                    self.enable_pcint(state.rx_pin);
-                }
-            }
-        });
+            RX_OVERFLOW[self.instance_id].clear();
+            RX_BUFFERS[self.instance_id].clear();
+
+            RECEIVER.set(Some(Receiver { instance_id: self.instance_id, state }));
+
+            // Route PCINT on the RX pin to the receiver
+            crate::pcint::set_software_serial_rx(Some(state.rx_pin));
+        }
     }
 
     /// Stop listening for data
     pub fn end(&mut self) {
-        unsafe {
-            if let Some(state) = &mut INSTANCES[self.instance_id] {
-                state.is_listening = false;
-                self.disable_pcint(state.rx_pin);
+        let was_active = RECEIVER.lock(|receiver| {
+            if receiver.is_some_and(|receiver| receiver.instance_id == self.instance_id) {
+                *receiver = None;
+                true
+            } else {
+                false
             }
+        });
+
+        if was_active {
+            crate::pcint::set_software_serial_rx(None);
         }
     }
 
     /// Check if this instance is currently listening
     pub fn is_listening(&self) -> bool {
-        unsafe {
-            INSTANCES[self.instance_id]
-                .as_ref()
-                .map(|s| s.is_listening)
-                .unwrap_or(false)
-        }
+        RECEIVER.lock(|receiver| receiver.is_some_and(|receiver| receiver.instance_id == self.instance_id))
     }
 
     /// Write a byte
     pub fn write_byte(&mut self, byte: u8) {
         unsafe {
-            if let Some(state) = &INSTANCES[self.instance_id] {
+            if let Some(state) = self.state() {
                 // Disable interrupts for precise timing
                 let sreg = read_volatile(0x5F as *const u8);
                 core::arch::asm!("cli", options(nomem, nostack));
 
-                let tx_port = state.tx_port;
+                let tx_port = port_output_register(state.tx_port);
                 let bit_mask = state.tx_bit_mask;
                 let inverse = state.inverse_logic;
 
@@ -256,96 +259,22 @@ impl SoftwareSerial {
     ///
     /// Returns -1 if no data available
     pub fn read(&mut self) -> i16 {
-        unsafe {
-            if let Some(state) = &mut INSTANCES[self.instance_id] {
-                if state.rx_buffer_head == state.rx_buffer_tail {
-                    -1
-                } else {
-                    let byte = state.rx_buffer[state.rx_buffer_tail];
-                    state.rx_buffer_tail = (state.rx_buffer_tail + 1) % RX_BUFFER_SIZE;
-                    byte as i16
-                }
-            } else {
-                -1
-            }
-        }
+        RX_BUFFERS[self.instance_id].pop().map_or(-1, |byte| byte as i16)
     }
 
     /// Get number of bytes available in receive buffer
     pub fn available(&self) -> usize {
-        unsafe {
-            if let Some(state) = &INSTANCES[self.instance_id] {
-                (RX_BUFFER_SIZE + state.rx_buffer_head - state.rx_buffer_tail) % RX_BUFFER_SIZE
-            } else {
-                0
-            }
-        }
+        RX_BUFFERS[self.instance_id].len()
     }
 
     /// Peek at the next byte without removing it
     pub fn peek(&self) -> i16 {
-        unsafe {
-            if let Some(state) = &INSTANCES[self.instance_id] {
-                if state.rx_buffer_head == state.rx_buffer_tail {
-                    -1
-                } else {
-                    state.rx_buffer[state.rx_buffer_tail] as i16
-                }
-            } else {
-                -1
-            }
-        }
+        RX_BUFFERS[self.instance_id].peek().map_or(-1, |byte| byte as i16)
     }
 
     /// Check if buffer overflow occurred
     pub fn overflow(&mut self) -> bool {
-        unsafe {
-            if let Some(state) = &mut INSTANCES[self.instance_id] {
-                let overflow = state.buffer_overflow;
-                state.buffer_overflow = false;
-                overflow
-            } else {
-                false
-            }
-        }
-    }
-
-    // Helper to enable PCINT for a pin
-    fn enable_pcint(&self, pin: u8) {
-        unsafe {
-            let (pcie_bit, pcmsk) = match pin {
-                0..=7 => (2, PCMSK2),   // PORTD
-                8..=13 => (0, PCMSK0),  // PORTB
-                14..=19 => (1, PCMSK1), // PORTC
-                _ => return,
-            };
-
-            let bit = digital_pin_to_bit_mask(pin);
-
-            // Enable pin in mask
-            let mask = read_volatile(pcmsk);
-            write_volatile(pcmsk, mask | bit);
-
-            // Enable PCIE
-            let pcicr = read_volatile(PCICR);
-            write_volatile(PCICR, pcicr | (1 << pcie_bit));
-        }
-    }
-
-    // Helper to disable PCINT for a pin
-    fn disable_pcint(&self, pin: u8) {
-        unsafe {
-            let pcmsk = match pin {
-                0..=7 => PCMSK2,
-                8..=13 => PCMSK0,
-                14..=19 => PCMSK1,
-                _ => return,
-            };
-
-            let bit = digital_pin_to_bit_mask(pin);
-            let mask = read_volatile(pcmsk);
-            write_volatile(pcmsk, mask & !bit);
-        }
+        RX_OVERFLOW[self.instance_id].take()
     }
 }
 
@@ -371,26 +300,31 @@ fn tuned_delay(cycles: u16) {
 
 /// Internal receive handler called from PCINT ISR
 pub fn handle_interrupt() {
-    critical_section::with(|cs| {
-        if let Some(instance_id) = ACTIVE_INSTANCE.borrow(cs).get() {
-            unsafe {
-                if let Some(state) = &mut INSTANCES[instance_id] {
-                    recv_data(state);
-                }
+    RECEIVER.lock(|receiver| {
+        let Some(receiver) = receiver.as_ref() else {
+            return;
+        };
+        if let Some(data) = unsafe { recv_data(&receiver.state) } {
+            if RX_BUFFERS[receiver.instance_id].push(data).is_err() {
+                RX_OVERFLOW[receiver.instance_id].set();
             }
         }
     });
 }
 
 /// Receive data (called from interrupt)
-unsafe fn recv_data(state: &mut SoftwareSerialState) {
+///
+/// Returns None if the pin change was not a start bit.
+unsafe fn recv_data(state: &SoftwareSerialState) -> Option<u8> {
+    let rx_port = state.rx_register;
+
     // Check for start bit
-    let rx_val = read_volatile(state.rx_port);
+    let rx_val = read_volatile(rx_port);
     let start_bit = (rx_val & state.rx_bit_mask) != 0;
 
     let expected_start = if state.inverse_logic { true } else { false };
     if start_bit != expected_start {
-        return;  // Not a valid start bit
+        return None;  // Not a valid start bit
     }
 
     // Wait to center of first data bit
@@ -402,7 +336,7 @@ unsafe fn recv_data(state: &mut SoftwareSerialState) {
     for i in 0..8 {
         tuned_delay(state.rx_delay_intrabit);
 
-        let rx_val = read_volatile(state.rx_port);
+        let rx_val = read_volatile(rx_port);
         let bit = ((rx_val & state.rx_bit_mask) != 0) as u8;
 
         let bit_val = if state.inverse_logic { !bit & 0x01 } else { bit };
@@ -412,18 +346,5 @@ unsafe fn recv_data(state: &mut SoftwareSerialState) {
     // Wait for stop bit
     tuned_delay(state.rx_delay_stopbit);
 
-    // Store in buffer
-    let next_head = (state.rx_buffer_head + 1) % RX_BUFFER_SIZE;
-    if next_head != state.rx_buffer_tail {
-        state.rx_buffer[state.rx_buffer_head] = data;
-        state.rx_buffer_head = next_head;
-    } else {
-        state.buffer_overflow = true;
-    }
-}
-
-// Export interrupt handler hooks for PCINT
-#[no_mangle]
-pub unsafe extern "C" fn software_serial_pcint_hook() {
-    handle_interrupt();
+    Some(data)
 }
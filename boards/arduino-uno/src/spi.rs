@@ -10,6 +10,7 @@
 //! transaction-based API for safe multi-device bus sharing.
 
 use core::ptr::{read_volatile, write_volatile};
+use crate::power::{self, PowerPeripheral};
 
 // SPI registers
 const SPCR: *mut u8 = 0x4C as *mut u8;  // SPI Control Register
@@ -149,6 +150,8 @@ impl Spi {
     /// This configures the SPI pins and enables the SPI peripheral.
     /// Note: SS pin (D10) must be controlled manually by the application.
     pub fn new() -> Self {
+        power::acquire(PowerPeripheral::Spi);
+
         unsafe {
             // Set SS high (deselect) before configuring as output
             let portb = read_volatile(PORTB);
@@ -256,6 +259,8 @@ impl Spi {
     }
 
     /// Disable SPI peripheral
+    ///
+    /// The SPI is powered down once no other `Spi` instance is alive.
     pub fn end(self) {
         unsafe {
             let spcr = read_volatile(SPCR);
@@ -263,3 +268,12 @@ impl Spi {
         }
     }
 }
+
+impl Drop for Spi {
+    fn drop(&mut self) {
+        power::release(PowerPeripheral::Spi, || unsafe {
+            let spcr = read_volatile(SPCR);
+            write_volatile(SPCR, spcr & !(1 << SPE));
+        });
+    }
+}
@@ -0,0 +1,460 @@
+//! Stepper motor driver with acceleration
+//!
+//! Supports two kinds of hardware:
+//!
+//! - STEP/DIR driver boards (A4988, DRV8825, TMC2208, ...): one pulse on
+//!   STEP per (micro)step, direction on DIR, optional active-low ENABLE
+//! - 4-wire motors driven directly through an H-bridge (bipolar, e.g.
+//!   L298N) or a transistor array (unipolar, e.g. ULN2003 with a 28BYJ-48),
+//!   in full-step or half-step sequence
+//!
+//! Step pulses are generated in the background by a 10 kHz Timer2 interrupt,
+//! so motors keep moving while the main program runs. Each motor follows a
+//! trapezoidal speed profile from [`ossidata_core::stepper::StepperRamp`]:
+//! accelerate, cruise at the maximum speed, decelerate onto the target.
+//! Up to 4 steppers can run at once, each at up to 2000 steps per second.
+//!
+//! The limit comes from the interrupt budget rather than the 100 us tick: a
+//! step that changes speed costs one 32-bit division in the ramp, about
+//! 50 us on a 16 MHz Uno, and cruising steps about 10 us. Four motors
+//! accelerating at 2000 steps per second keep the CPU about 40% busy in the
+//! interrupt, leaving time for `millis()`, serial and the main program.
+//!
+//! Timer2 is shared with PWM on D3/D11 (including `PcmPlayer`), `tone()` and
+//! `SoftRtc` in crystal mode. Starting a move while one of them owns the
+//! timer fails with [`StepperError::TimerBusy`] instead of taking it over.
+
+use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::IrqCell;
+use ossidata_core::stepper::{Direction, StepperRamp};
+use crate::constants::OUTPUT;
+use crate::gpio::pin_mode;
+use crate::ports::fast_digital_write;
+use crate::power::{self, PowerPeripheral};
+
+// Timer2 registers (ATmega328P)
+const TCCR2A: *mut u8 = 0xB0 as *mut u8;  // Timer/Counter2 Control Register A
+const TCCR2B: *mut u8 = 0xB1 as *mut u8;  // Timer/Counter2 Control Register B
+const TCNT2: *mut u8 = 0xB2 as *mut u8;   // Timer/Counter2 (counter value)
+const OCR2A: *mut u8 = 0xB3 as *mut u8;   // Output Compare Register 2 A (TOP)
+const OCR2B: *mut u8 = 0xB4 as *mut u8;   // Output Compare Register 2 B
+const TIMSK2: *mut u8 = 0x70 as *mut u8;  // Timer/Counter2 Interrupt Mask Register
+const TIFR2: *mut u8 = 0x37 as *mut u8;   // Timer/Counter2 Interrupt Flag Register
+const ASSR: *mut u8 = 0xB6 as *mut u8;    // Asynchronous Status Register
+
+// Timer2 bits
+const WGM21: u8 = 1;    // CTC mode
+const CS21: u8 = 1;     // Prescaler 8
+const OCIE2B: u8 = 2;   // Compare Match B Interrupt Enable
+const OCF2B: u8 = 2;    // Compare Match B Flag
+const AS2: u8 = 5;      // Timer2 clocked from TOSC1 (SoftRtc crystal)
+
+/// Interrupt period in microseconds
+const TICK_US: u32 = 100;
+
+/// Maximum number of steppers running at once
+const MAX_STEPPERS: usize = 4;
+
+/// Maximum speed in steps per second, limited by the interrupt time per step
+const MAX_SPEED: u32 = 2000;
+
+/// STEP pulse width in microseconds (A4988: 1 us, DRV8825: 1.9 us)
+const STEP_PULSE_US: u16 = 2;
+
+/// Default maximum speed and acceleration of a new stepper
+const DEFAULT_MAX_SPEED: u32 = 500;
+const DEFAULT_ACCELERATION: u32 = 1000;
+
+// Coil patterns, bit 0 = first pin. Bipolar pins: A+, A-, B+, B-.
+// Unipolar pins: the four coils in rotation order (ULN2003 IN1-IN4).
+const BIPOLAR_FULL: [u8; 4] = [0b0101, 0b0110, 0b1010, 0b1001];
+const BIPOLAR_HALF: [u8; 8] = [0b0001, 0b0101, 0b0100, 0b0110, 0b0010, 0b1010, 0b1000, 0b1001];
+const UNIPOLAR_FULL: [u8; 4] = [0b0011, 0b0110, 0b1100, 0b1001];
+const UNIPOLAR_HALF: [u8; 8] = [0b0001, 0b0011, 0b0010, 0b0110, 0b0100, 0b1100, 0b1000, 0b1001];
+
+/// Stepper error types
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepperError {
+    /// PWM on D3/D11, a tone or a crystal `SoftRtc` is using Timer2
+    TimerBusy,
+}
+
+/// Coil arrangement of a 4-wire stepper
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    /// Two coils driven in both directions through H-bridges (pins A+, A-, B+, B-)
+    Bipolar,
+    /// Four center-tapped half coils switched to ground (pins in rotation order)
+    Unipolar,
+}
+
+/// Step sequence of a 4-wire stepper
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepSequence {
+    /// Two phases on at a time: full torque, one step per pattern
+    FullStep,
+    /// Alternating one and two phases: twice the resolution
+    HalfStep,
+}
+
+/// How step pulses reach the motor
+#[derive(Clone, Copy)]
+enum Driver {
+    StepDir { step: u8, dir: u8 },
+    FourWire { pins: [u8; 4], sequence: &'static [u8] },
+}
+
+/// State of one stepper, shared with the interrupt handler
+#[derive(Clone, Copy)]
+struct Channel {
+    ramp: StepperRamp,
+    driver: Driver,
+    enable_pin: Option<u8>,
+    invert_direction: bool,
+    enabled: bool,
+    // Microseconds until the next step
+    wait_us: i32,
+    // Index into the coil sequence of a 4-wire motor
+    phase: u8,
+}
+
+impl Channel {
+    /// Drive the outputs for one step
+    ///
+    /// Returns the STEP pin of a STEP/DIR driver, which is left high for the
+    /// caller to end the pulse.
+    fn step(&mut self, direction: Direction) -> Option<u8> {
+        match self.driver {
+            Driver::StepDir { step, dir } => {
+                let forward = direction == Direction::Forward;
+                fast_digital_write(dir, forward != self.invert_direction);
+                fast_digital_write(step, true);
+                Some(step)
+            }
+            Driver::FourWire { sequence, .. } => {
+                let len = sequence.len() as u8;
+                let forward = (direction == Direction::Forward) != self.invert_direction;
+                self.phase = if forward { (self.phase + 1) % len } else { (self.phase + len - 1) % len };
+                self.write_coils();
+                None
+            }
+        }
+    }
+
+    /// Energize the coils of the current phase (or release them when disabled)
+    fn write_coils(&self) {
+        if let Driver::FourWire { pins, sequence } = self.driver {
+            let pattern = if self.enabled { sequence[self.phase as usize] } else { 0 };
+            for (bit, &pin) in pins.iter().enumerate() {
+                fast_digital_write(pin, pattern & (1 << bit) != 0);
+            }
+        }
+    }
+
+    /// Apply the enabled state to the ENABLE pin or the coils
+    fn apply_enabled(&self) {
+        if let Some(pin) = self.enable_pin {
+            // A4988/DRV8825/TMC2208 ENABLE is active low
+            fast_digital_write(pin, !self.enabled);
+        }
+        self.write_coils();
+    }
+}
+
+static CHANNELS: IrqCell<[Option<Channel>; MAX_STEPPERS]> = IrqCell::new([None; MAX_STEPPERS]);
+
+/// Stepper motor controller
+///
+/// Positions and speeds are in steps of the driver, i.e. microsteps for a
+/// STEP/DIR driver with microstepping enabled and half steps in
+/// [`StepSequence::HalfStep`].
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::Stepper;
+///
+/// // A4988 with STEP on D3, DIR on D4 and ENABLE on D8
+/// let mut stepper = Stepper::step_dir(3, 4).with_enable_pin(8);
+/// stepper.set_max_speed(2000);
+/// stepper.set_acceleration(4000);
+/// stepper.move_to(3200).unwrap();  // One turn at 1/16 microstepping
+///
+/// while stepper.is_running() {
+///     // Free to do other work
+/// }
+/// ```
+pub struct Stepper {
+    index: usize,
+}
+
+impl Stepper {
+    /// Create a stepper on a STEP/DIR driver board
+    ///
+    /// # Panics
+    /// Panics if 4 steppers already exist.
+    pub fn step_dir(step_pin: u8, dir_pin: u8) -> Self {
+        pin_mode(step_pin, OUTPUT);
+        pin_mode(dir_pin, OUTPUT);
+        Self::new(Driver::StepDir { step: step_pin, dir: dir_pin })
+    }
+
+    /// Create a stepper driven directly on 4 pins
+    ///
+    /// The coils are energized immediately, holding the motor in place.
+    ///
+    /// # Panics
+    /// Panics if 4 steppers already exist.
+    pub fn four_wire(pins: [u8; 4], winding: Winding, sequence: StepSequence) -> Self {
+        let sequence: &'static [u8] = match (winding, sequence) {
+            (Winding::Bipolar, StepSequence::FullStep) => &BIPOLAR_FULL,
+            (Winding::Bipolar, StepSequence::HalfStep) => &BIPOLAR_HALF,
+            (Winding::Unipolar, StepSequence::FullStep) => &UNIPOLAR_FULL,
+            (Winding::Unipolar, StepSequence::HalfStep) => &UNIPOLAR_HALF,
+        };
+        for &pin in &pins {
+            pin_mode(pin, OUTPUT);
+        }
+        Self::new(Driver::FourWire { pins, sequence })
+    }
+
+    fn new(driver: Driver) -> Self {
+        let channel = Channel {
+            ramp: StepperRamp::new(DEFAULT_MAX_SPEED, DEFAULT_ACCELERATION),
+            driver,
+            enable_pin: None,
+            invert_direction: false,
+            enabled: true,
+            wait_us: 0,
+            phase: 0,
+        };
+
+        let index = CHANNELS.lock(|channels| {
+            let index = channels
+                .iter()
+                .position(Option::is_none)
+                .expect("Maximum number of steppers exceeded");
+            channels[index] = Some(channel);
+            index
+        });
+
+        power::acquire(PowerPeripheral::Timer2);
+        channel.apply_enabled();
+        Self { index }
+    }
+
+    /// Use an active-low ENABLE pin, enabling the driver now
+    pub fn with_enable_pin(self, pin: u8) -> Self {
+        pin_mode(pin, OUTPUT);
+        self.with_channel(|channel| {
+            channel.enable_pin = Some(pin);
+            channel.apply_enabled();
+        });
+        self
+    }
+
+    /// Swap the meaning of forward and backward
+    pub fn with_inverted_direction(self) -> Self {
+        self.with_channel(|channel| channel.invert_direction = true);
+        self
+    }
+
+    /// Set the maximum speed in steps per second (up to 2000)
+    pub fn set_max_speed(&mut self, steps_per_second: u32) {
+        self.with_channel(|channel| channel.ramp.set_max_speed(steps_per_second.min(MAX_SPEED)));
+    }
+
+    /// Maximum speed in steps per second
+    pub fn max_speed(&self) -> u32 {
+        self.with_channel(|channel| channel.ramp.max_speed())
+    }
+
+    /// Set the acceleration and deceleration in steps per second²
+    pub fn set_acceleration(&mut self, steps_per_second2: u32) {
+        self.with_channel(|channel| channel.ramp.set_acceleration(steps_per_second2));
+    }
+
+    /// Move to an absolute position in the background
+    ///
+    /// A new target while moving is approached without stopping first,
+    /// decelerating and reversing if it lies behind.
+    ///
+    /// Fails with [`StepperError::TimerBusy`] if no stepper is moving and
+    /// Timer2 is in use elsewhere.
+    pub fn move_to(&mut self, position: i32) -> Result<(), StepperError> {
+        self.start(|ramp| ramp.move_to(position))
+    }
+
+    /// Move relative to the current position in the background
+    ///
+    /// Fails like [`move_to`](Self::move_to).
+    pub fn move_by(&mut self, steps: i32) -> Result<(), StepperError> {
+        self.start(|ramp| ramp.move_by(steps))
+    }
+
+    /// Run continuously at a constant speed in steps per second
+    ///
+    /// Negative speeds run backward. There is no acceleration, so keep the
+    /// speed below the motor's start/stop rate. Stop with [`stop`](Self::stop).
+    /// Fails like [`move_to`](Self::move_to).
+    pub fn run_speed(&mut self, steps_per_second: i32) -> Result<(), StepperError> {
+        self.start(|ramp| ramp.run_speed(steps_per_second))
+    }
+
+    /// Decelerate to a stop
+    pub fn stop(&mut self) {
+        self.with_channel(|channel| channel.ramp.stop());
+    }
+
+    /// Stop immediately without decelerating
+    ///
+    /// The motor may lose steps if it was moving fast.
+    pub fn halt(&mut self) {
+        self.with_channel(|channel| channel.ramp.halt());
+    }
+
+    /// Check if the motor is moving
+    pub fn is_running(&self) -> bool {
+        self.with_channel(|channel| channel.ramp.is_running())
+    }
+
+    /// Current position in steps
+    pub fn position(&self) -> i32 {
+        self.with_channel(|channel| channel.ramp.position())
+    }
+
+    /// Set the current position (e.g. after homing), stopping the motor
+    pub fn set_position(&mut self, position: i32) {
+        self.with_channel(|channel| channel.ramp.set_position(position));
+    }
+
+    /// Target position in steps
+    pub fn target(&self) -> i32 {
+        self.with_channel(|channel| channel.ramp.target())
+    }
+
+    /// Steps left to the target position
+    pub fn distance_to_go(&self) -> i32 {
+        self.with_channel(|channel| channel.ramp.distance_to_go())
+    }
+
+    /// Current speed in steps per second, negative when moving backward
+    pub fn speed(&self) -> i32 {
+        self.with_channel(|channel| channel.ramp.speed())
+    }
+
+    /// Enable the driver (or energize the coils of a 4-wire motor)
+    pub fn enable_outputs(&mut self) {
+        self.with_channel(|channel| {
+            channel.enabled = true;
+            channel.apply_enabled();
+        });
+    }
+
+    /// Disable the driver (or release the coils), letting the motor turn freely
+    ///
+    /// Saves power while idle, but the motor no longer holds its position.
+    pub fn disable_outputs(&mut self) {
+        self.with_channel(|channel| {
+            channel.enabled = false;
+            channel.apply_enabled();
+        });
+    }
+
+    fn with_channel<R>(&self, f: impl FnOnce(&mut Channel) -> R) -> R {
+        CHANNELS.lock(|channels| f(channels[self.index].as_mut().unwrap()))
+    }
+
+    /// Update the ramp and start the step interrupt if it was idle
+    fn start(&mut self, f: impl FnOnce(&mut StepperRamp)) -> Result<(), StepperError> {
+        critical_section::with(|_| {
+            // Claim Timer2 before touching the ramp, so a busy timer leaves
+            // the motor as it was
+            start_ticker()?;
+            self.with_channel(|channel| {
+                if !channel.ramp.is_running() {
+                    // First step on the next tick
+                    channel.wait_us = 0;
+                }
+                f(&mut channel.ramp);
+            });
+            Ok(())
+        })
+    }
+}
+
+impl Drop for Stepper {
+    fn drop(&mut self) {
+        CHANNELS.lock(|channels| channels[self.index] = None);
+        power::release(PowerPeripheral::Timer2, || unsafe {
+            write_volatile(TIMSK2, read_volatile(TIMSK2) & !(1 << OCIE2B));
+            write_volatile(TCCR2B, 0);
+        });
+    }
+}
+
+/// Start the 10 kHz step interrupt (CTC mode, compare B at the start of each period)
+///
+/// Leaves Timer2 alone if PWM outputs, a tone interrupt or the asynchronous
+/// clock of a crystal `SoftRtc` are using it.
+fn start_ticker() -> Result<(), StepperError> {
+    critical_section::with(|_| unsafe {
+        let interrupts = read_volatile(TIMSK2);
+        if interrupts & (1 << OCIE2B) != 0 {
+            return Ok(());
+        }
+        if read_volatile(TCCR2A) & 0xF0 != 0 || read_volatile(ASSR) & (1 << AS2) != 0 || interrupts != 0 {
+            return Err(StepperError::TimerBusy);
+        }
+
+        let top = crate::clock::micros_to_cycles(TICK_US) / 8 - 1;
+        write_volatile(TCCR2B, 0);
+        write_volatile(TCCR2A, 1 << WGM21);
+        write_volatile(OCR2A, top.min(255) as u8);
+        write_volatile(OCR2B, 0);
+        write_volatile(TCNT2, 0);
+        write_volatile(TIFR2, 1 << OCF2B);
+        write_volatile(TIMSK2, read_volatile(TIMSK2) | (1 << OCIE2B));
+        write_volatile(TCCR2B, 1 << CS21);
+        Ok(())
+    })
+}
+
+/// Timer2 Compare Match B interrupt handler: step all running motors
+#[link_section = ".text"]
+#[export_name = "__vector_8"]
+pub unsafe extern "avr-interrupt" fn stepper_tick() {
+    let mut pulses = [None; MAX_STEPPERS];
+    let active = CHANNELS.lock(|channels| {
+        let mut active = false;
+        for (channel, pulse) in channels.iter_mut().zip(pulses.iter_mut()) {
+            let Some(channel) = channel.as_mut().filter(|channel| channel.ramp.is_running()) else {
+                continue;
+            };
+            active = true;
+
+            channel.wait_us -= TICK_US as i32;
+            if channel.wait_us <= 0 {
+                if let Some(direction) = channel.ramp.next_step() {
+                    *pulse = channel.step(direction);
+                    // Carry the remainder so the average rate stays exact
+                    channel.wait_us += channel.ramp.step_interval_us() as i32;
+                }
+            }
+        }
+        active
+    });
+
+    // End the STEP pulses after the lock is released, with one pulse width
+    // for all motors that stepped on this tick
+    if pulses.iter().any(Option::is_some) {
+        crate::delay_micros(STEP_PULSE_US);
+        for pin in pulses.into_iter().flatten() {
+            fast_digital_write(pin, false);
+        }
+    }
+
+    // Nothing left to do: stop interrupting until the next move
+    if !active {
+        write_volatile(TIMSK2, read_volatile(TIMSK2) & !(1 << OCIE2B));
+    }
+}
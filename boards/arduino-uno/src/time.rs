@@ -1,12 +1,18 @@
 //! Time tracking using Timer0 overflow interrupt
 //!
 //! This module implements millis() and micros() functions similar to Arduino.
-//! Timer0 is configured with a prescaler of 64, giving 1024 microseconds per overflow.
+//! Timer0 is configured with a prescaler of 64, giving 1024 microseconds per overflow
+//! at 16 MHz. The increments are derived from the configured CPU clock (see `clock`).
+//!
+//! On top of the 32-bit counters, a 64-bit microsecond counter backs `micros64()`,
+//! `millis64()` and the `SystemClock` monotonic clock, which never wrap in practice.
 //!
 //! Note: Timer0 is shared with PWM on pins D5 and D6. The overflow interrupt
 //! for timekeeping does not interfere with PWM operation.
 
 use core::ptr::{read_volatile, write_volatile};
+use ossidata_core::irq::{IrqCell, IrqCounter};
+use ossidata_core::time::{Clock, Duration, Instant};
 
 // Timer0 registers
 const TCCR0B: *mut u8 = 0x45 as *mut u8;  // Timer/Counter Control Register B
@@ -20,31 +26,71 @@ const TOIE0: u8 = 0;  // Timer/Counter0 Overflow Interrupt Enable
 // TIFR0 bits
 const TOV0: u8 = 0;   // Timer/Counter0 Overflow Flag
 
-// Timing constants for 16 MHz clock with prescaler 64
-// Timer0 overflows every 256 ticks * 64 prescaler / 16MHz = 1024 microseconds
-const MICROSECONDS_PER_TIMER0_OVERFLOW: u32 = 1024;
-const MILLIS_INC: u32 = 1;  // Whole milliseconds per overflow
-const FRACT_INC: u8 = 3;    // Fractional milliseconds (1024 - 1000 = 24; 24/8 = 3)
+// Timer0 runs with prescaler 64 and overflows every 256 ticks, i.e. every
+// 64 * 256 = 16384 CPU cycles. At 16 MHz that is 1024 microseconds.
+const CYCLES_PER_TIMER0_OVERFLOW: u64 = 64 * 256;
+
+// Microseconds per overflow at the undivided oscillator frequency
+// (1024 at 16 MHz, 2048 at 8 MHz). Doubles with each CLKPR prescaler step.
+const BASE_MICROS_PER_OVERFLOW: u32 =
+    (CYCLES_PER_TIMER0_OVERFLOW * 1_000_000 / crate::clock::F_CPU as u64) as u32;
+
+// Fractional milliseconds are tracked in units of 8 microseconds so they fit in a u8
 const FRACT_MAX: u8 = 125;  // 1000 / 8 = 125
 
-// Global timing variables
-// These are accessed in the ISR and main code, so we need them to be static mut
-// On AVR, interrupts provide the necessary synchronization
-static mut TIMER0_OVERFLOW_COUNT: u32 = 0;
-static mut TIMER0_MILLIS: u32 = 0;
-static mut TIMER0_FRACT: u8 = 0;
+// Global timing variables, shared between the overflow ISR and the main code
+// The 64-bit microsecond counter backs both micros() (low half) and micros64()
+static TIMER0_MICROS: IrqCell<u64> = IrqCell::new(0);
+static TIMER0_MILLIS: IrqCounter<u32> = IrqCounter::<u32>::new();
+static TIMER0_FRACT: IrqCell<u8> = IrqCell::new(0);
+
+/// Per-overflow increments, derived from the current CPU clock
+#[derive(Clone, Copy)]
+struct Increments {
+    micros: u32,
+    millis: u32,
+    fract: u8,
+}
+
+impl Increments {
+    const fn for_micros(us: u32) -> Self {
+        Self {
+            micros: us,
+            millis: us / 1000,
+            fract: ((us % 1000) >> 3) as u8,
+        }
+    }
+}
+
+// Defaults: 16 MHz -> 1024us = 1 ms + 24us (24/8 = 3)
+static INCREMENTS: IrqCell<Increments> =
+    IrqCell::new(Increments::for_micros(BASE_MICROS_PER_OVERFLOW));
+
+/// Recalculate the per-overflow increments for the current CPU clock
+///
+/// Called by `set_clock_prescaler()` with interrupts disabled.
+pub(crate) fn recalibrate() {
+    let us = BASE_MICROS_PER_OVERFLOW << (crate::clock::clock_prescaler() as u8);
+    INCREMENTS.set(Increments::for_micros(us));
+}
 
 /// Initialize Timer0 for timekeeping
 ///
 /// This sets up Timer0 with prescaler 64 and enables overflow interrupt.
 /// Must be called once at startup before using millis() or micros().
 pub fn init_timer() {
+    // Timer0 stays powered for as long as the program runs
+    crate::power::acquire(crate::PowerPeripheral::Timer0);
+
     unsafe {
         // Set prescaler to 64 (CS01 = 1, CS00 = 1)
         // This is OR'd with existing value to preserve WGM bits if PWM is enabled
         let tccr0b = read_volatile(TCCR0B);
         write_volatile(TCCR0B, tccr0b | 0b011);
 
+        // Pick up a CKDIV8 fuse or earlier CLKPR setting
+        recalibrate();
+
         // Enable Timer0 overflow interrupt
         write_volatile(TIMSK0, 1 << TOIE0);
 
@@ -55,67 +101,154 @@ pub fn init_timer() {
 
 /// Timer0 overflow interrupt handler
 ///
-/// This is called approximately every 1.024 milliseconds.
+/// This is called every 16384 CPU cycles (1.024 milliseconds at 16 MHz).
 /// Updates the millisecond counter with fractional accumulation.
 #[no_mangle]
 #[link_section = ".text"]
 pub extern "avr-interrupt" fn __vector_16() {
-    unsafe {
-        // Update fractional milliseconds
-        TIMER0_FRACT = TIMER0_FRACT.wrapping_add(FRACT_INC);
-        TIMER0_MILLIS = TIMER0_MILLIS.wrapping_add(MILLIS_INC);
-
-        // If fractional part overflows, add an extra millisecond
-        if TIMER0_FRACT >= FRACT_MAX {
-            TIMER0_FRACT = TIMER0_FRACT.wrapping_sub(FRACT_MAX);
-            TIMER0_MILLIS = TIMER0_MILLIS.wrapping_add(1);
-        }
+    let inc = INCREMENTS.get();
+
+    // Update fractional milliseconds
+    let mut fract = TIMER0_FRACT.get().wrapping_add(inc.fract);
+    let mut millis = inc.millis;
 
-        // Increment overflow counter
-        TIMER0_OVERFLOW_COUNT = TIMER0_OVERFLOW_COUNT.wrapping_add(1);
+    // If fractional part overflows, add an extra millisecond
+    if fract >= FRACT_MAX {
+        fract = fract.wrapping_sub(FRACT_MAX);
+        millis += 1;
     }
+
+    TIMER0_FRACT.set(fract);
+    TIMER0_MILLIS.add(millis);
+
+    // Advance the microsecond base
+    TIMER0_MICROS.update(|us| us.wrapping_add(inc.micros as u64));
+}
+
+/// Advance the clocks by time that passed while Timer0 was stopped
+///
+/// Used after power-down sleep, where the Timer0 overflow interrupt cannot run.
+pub(crate) fn advance_millis(ms: u64) {
+    critical_section::with(|_| {
+        // millis() wraps every 2^32 ms, so only the low bits matter there
+        TIMER0_MILLIS.add(ms as u32);
+        TIMER0_MICROS.update(|us| us.wrapping_add(ms.saturating_mul(1000)));
+    });
 }
 
 /// Returns the number of milliseconds since the program started
 ///
 /// This counter will overflow (go back to zero) after approximately 50 days.
 pub fn millis() -> u32 {
-    unsafe {
-        // Disable interrupts to ensure atomic read of 32-bit value
-        core::arch::asm!("cli");
-        let m = TIMER0_MILLIS;
-        core::arch::asm!("sei");
-        m
-    }
+    TIMER0_MILLIS.get()
 }
 
 /// Returns the number of microseconds since the program started
 ///
 /// This counter will overflow (go back to zero) after approximately 70 minutes.
 pub fn micros() -> u32 {
-    unsafe {
-        // Disable interrupts to ensure consistent read
-        core::arch::asm!("cli");
+    // Snapshot the counter together with the timer so they are consistent
+    let (base, us_per_overflow, tcnt, tifr) = critical_section::with(|_| unsafe {
+        (
+            TIMER0_MICROS.get() as u32,
+            INCREMENTS.get().micros,
+            read_volatile(TCNT0),
+            read_volatile(TIFR0),
+        )
+    });
 
-        let overflows = TIMER0_OVERFLOW_COUNT;
-        let tcnt = read_volatile(TCNT0);
-        let tifr = read_volatile(TIFR0);
+    // Check if overflow is pending but hasn't been serviced yet
+    let adjusted_base = if (tifr & (1 << TOV0)) != 0 && tcnt < 255 {
+        base.wrapping_add(us_per_overflow)
+    } else {
+        base
+    };
 
-        // Re-enable interrupts
-        core::arch::asm!("sei");
+    // Calculate total microseconds
+    // Each tick is 1/256 of an overflow period (4 us at 16 MHz)
+    adjusted_base.wrapping_add((tcnt as u32 * us_per_overflow) >> 8)
+}
 
-        // Check if overflow is pending but hasn't been serviced yet
-        let adjusted_overflows = if (tifr & (1 << TOV0)) != 0 && tcnt < 255 {
-            overflows.wrapping_add(1)
-        } else {
-            overflows
-        };
-
-        // Calculate total microseconds
-        // Each overflow = 1024 us, each tick = 4 us (64 prescaler / 16 MHz)
-        adjusted_overflows
-            .wrapping_mul(MICROSECONDS_PER_TIMER0_OVERFLOW)
-            .wrapping_add((tcnt as u32) * 4)
+/// Returns the number of microseconds since the program started as a 64-bit value
+///
+/// Unlike `micros()`, this does not wrap around in any realistic uptime.
+///
+/// # Examples
+/// ```no_run
+/// use arduino_uno::micros64;
+///
+/// let start = micros64();
+/// // ... long-running work ...
+/// let elapsed = micros64() - start;  // Correct even after 70 minutes
+/// ```
+pub fn micros64() -> u64 {
+    let (mut base, us_per_overflow, tcnt, tifr) = critical_section::with(|_| unsafe {
+        (
+            TIMER0_MICROS.get(),
+            INCREMENTS.get().micros,
+            read_volatile(TCNT0),
+            read_volatile(TIFR0),
+        )
+    });
+
+    // Account for an overflow that is pending but hasn't been serviced yet
+    if (tifr & (1 << TOV0)) != 0 && tcnt < 255 {
+        base += us_per_overflow as u64;
+    }
+
+    base + ((tcnt as u32 * us_per_overflow) >> 8) as u64
+}
+
+/// Returns the number of milliseconds since the program started as a 64-bit value
+///
+/// Unlike `millis()`, this does not wrap around after 49 days.
+pub fn millis64() -> u64 {
+    micros64() / 1000
+}
+
+/// Returns the current instant of the system monotonic clock
+///
+/// # Examples
+/// ```no_run
+/// use arduino_uno::{now, elapsed_since, Duration};
+///
+/// let start = now();
+/// // ... work ...
+/// if elapsed_since(start) > Duration::millis(500) {
+///     // Took longer than 500ms
+/// }
+/// ```
+pub fn now() -> Instant {
+    Instant::from_ticks(micros64())
+}
+
+/// Returns the time elapsed since an earlier instant
+///
+/// Correct across `millis()`/`micros()` wraparound since it is based on the
+/// 64-bit counter. Returns zero if `earlier` is in the future.
+pub fn elapsed_since(earlier: Instant) -> Duration {
+    ossidata_core::time::elapsed_between(earlier, now())
+}
+
+/// Monotonic clock backed by the Timer0 overflow interrupt
+///
+/// Implements `ossidata_core::time::Clock` so it can be handed to code that is
+/// generic over the clock source.
+///
+/// # Examples
+/// ```no_run
+/// use arduino_uno::{SystemClock, Clock};
+///
+/// let clock = SystemClock;
+/// let start = clock.now();
+/// let elapsed = clock.elapsed_since(start);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        now()
     }
 }
 
@@ -136,31 +269,31 @@ pub fn micros() -> u32 {
 /// ```
 ///
 /// # Note
-/// At 16MHz, each cycle is 62.5ns. This implementation uses calibrated NOP loops
-/// to achieve microsecond precision. Accuracy is ±1-2 microseconds for delays > 10us.
+/// Each loop iteration takes 4 CPU cycles, so the resolution is 250ns at 16MHz
+/// and 500ns at 8MHz. Accuracy is ±1-2 microseconds for delays > 10us.
 pub fn delay_micros(us: u16) {
     if us == 0 {
         return;
     }
 
-    // At 16 MHz, each cycle is 62.5 nanoseconds
-    // For 1 microsecond = 16 cycles
-    // We need to account for function call overhead (~8 cycles)
-    //
-    // Loop structure (optimized by compiler):
-    // - Loop setup: ~4 cycles
-    // - Each iteration: ~4 cycles (decrement, compare, branch)
-    // - We aim for 4 cycles per iteration to get 4 iterations per microsecond
+    // Each loop iteration is 4 cycles, so iterations = cycles / 4
+    // (4 per microsecond at 16 MHz, 2 at 8 MHz)
+    let mut iterations = crate::clock::micros_to_cycles(us as u32) / 4;
 
-    unsafe {
-        // Inline assembly for precise timing
-        // Each loop iteration takes exactly 4 cycles at -O2/-O3 optimization
-        core::arch::asm!(
-            "1:",                    // Label for loop
-            "    sbiw {0}, 1",      // Subtract 1 from counter (2 cycles)
-            "    brne 1b",           // Branch if not zero (2 cycles when taken, 1 when not)
-            inout(reg_iw) (us * 4) => _,  // 4 iterations per microsecond
-            options(nostack)
-        );
+    while iterations > 0 {
+        let chunk = iterations.min(0xFFFF) as u16;
+        iterations -= chunk as u32;
+
+        unsafe {
+            // Inline assembly for precise timing
+            // Each loop iteration takes exactly 4 cycles at -O2/-O3 optimization
+            core::arch::asm!(
+                "1:",                    // Label for loop
+                "    sbiw {0}, 1",      // Subtract 1 from counter (2 cycles)
+                "    brne 1b",           // Branch if not zero (2 cycles when taken, 1 when not)
+                inout(reg_iw) chunk => _,
+                options(nostack)
+            );
+        }
     }
 }
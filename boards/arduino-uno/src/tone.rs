@@ -1,19 +1,34 @@
-//! Tone generation using Timer2
+//! Tone generation on up to three pins at once
 //!
 //! This module provides functions to generate square wave tones on Arduino Uno pins.
-//! It uses Timer2 in CTC (Clear Timer on Compare Match) mode with pin toggling in an ISR.
+//! Each playing pin gets its own timer channel:
+//!
+//! - Timer2 in CTC (Clear Timer on Compare Match) mode, used first
+//! - Timer1 in CTC mode with ICR1 as TOP, used while no servo is attached
+//! - Timer0 compare channel B, scheduled on top of the free-running `millis()`
+//!   counter without changing its rate
+//!
+//! D11 (OC2A) on Timer2 and D9 (OC1A) on Timer1 are toggled by the timer
+//! hardware, so their edges have no interrupt jitter; a pin asking for a tone
+//! gets its own hardware channel when it is free. Other pins are toggled in
+//! the compare interrupt.
 //!
 //! # Implementation Details
-//! - Uses Timer2 for tone generation
-//! - Frequencies: 31 Hz to 65535 Hz
+//! - Frequencies: 31 Hz to 65535 Hz on Timer2 and Timer1 (Timer0 tones are
+//!   less accurate above a few kHz)
 //! - Optional duration control
-//! - Pin toggling happens in Timer2 Compare Match A ISR
+//! - Optional volume control through the duty cycle of the square wave
+//! - The compare interrupts also advance melodies started with `play_melody()`
+//!
+//! Timer1 is skipped while servos, PCM playback or PWM on D9/D10 use it,
+//! Timer2 while PWM on D3/D11, a running `Stepper` or a crystal `SoftRtc`
+//! use it, and Timer0 while PWM on D5/D6 is enabled.
 //!
 //! Based on information from arduino/ArduinoCore-avr via deepwiki.
 
 use core::ptr::{read_volatile, write_volatile};
-use core::cell::Cell;
-use critical_section::Mutex;
+use ossidata_core::irq::IrqCell;
+use crate::power::{self, PowerPeripheral};
 
 // Timer2 registers (ATmega328P)
 const TCCR2A: *mut u8 = 0xB0 as *mut u8;  // Timer/Counter2 Control Register A
@@ -21,22 +36,61 @@ const TCCR2B: *mut u8 = 0xB1 as *mut u8;  // Timer/Counter2 Control Register B
 const TCNT2: *mut u8 = 0xB2 as *mut u8;   // Timer/Counter2 (counter value)
 const OCR2A: *mut u8 = 0xB3 as *mut u8;   // Output Compare Register 2 A
 const TIMSK2: *mut u8 = 0x70 as *mut u8;  // Timer/Counter2 Interrupt Mask Register
+const TIFR2: *mut u8 = 0x37 as *mut u8;   // Timer/Counter2 Interrupt Flag Register
+const ASSR: *mut u8 = 0xB6 as *mut u8;    // Asynchronous Status Register
+
+// Timer1 registers
+const TCCR1A: *mut u8 = 0x80 as *mut u8;  // Timer/Counter1 Control Register A
+const TCCR1B: *mut u8 = 0x81 as *mut u8;  // Timer/Counter1 Control Register B
+const TCCR1C: *mut u8 = 0x82 as *mut u8;  // Timer/Counter1 Control Register C
+const TCNT1L: *mut u8 = 0x84 as *mut u8;  // Timer/Counter1 Low
+const TCNT1H: *mut u8 = 0x85 as *mut u8;  // Timer/Counter1 High
+const ICR1L: *mut u8 = 0x86 as *mut u8;   // Input Capture Register 1 Low (TOP)
+const ICR1H: *mut u8 = 0x87 as *mut u8;   // Input Capture Register 1 High (TOP)
+const OCR1AL: *mut u8 = 0x88 as *mut u8;  // Output Compare Register 1 A Low
+const OCR1AH: *mut u8 = 0x89 as *mut u8;  // Output Compare Register 1 A High
+const OCR1BL: *mut u8 = 0x8A as *mut u8;  // Output Compare Register 1 B Low
+const OCR1BH: *mut u8 = 0x8B as *mut u8;  // Output Compare Register 1 B High
+const TIMSK1: *mut u8 = 0x6F as *mut u8;  // Timer/Counter1 Interrupt Mask Register
+const TIFR1: *mut u8 = 0x36 as *mut u8;   // Timer/Counter1 Interrupt Flag Register
+
+// Timer0 registers (the counter keeps running for millis())
+const TCCR0A: *mut u8 = 0x44 as *mut u8;  // Timer/Counter0 Control Register A
+const TCNT0: *mut u8 = 0x46 as *mut u8;   // Timer/Counter0 (counter value)
+const OCR0B: *mut u8 = 0x48 as *mut u8;   // Output Compare Register 0 B
+const TIMSK0: *mut u8 = 0x6E as *mut u8;  // Timer/Counter0 Interrupt Mask Register
+const TIFR0: *mut u8 = 0x35 as *mut u8;   // Timer/Counter0 Interrupt Flag Register
 
 // TCCR2A bits
 const WGM21: u8 = 1;  // Waveform Generation Mode bit 1 (CTC mode)
+const COM2A0: u8 = 6; // Toggle OC2A on compare match
+const COM2A1: u8 = 7; // Clear OC2A on compare match (with COM2A0 = 0)
 
 // TCCR2B bits
 const CS20: u8 = 0;   // Clock Select bit 0
 const CS21: u8 = 1;   // Clock Select bit 1
 const CS22: u8 = 2;   // Clock Select bit 2
-#[allow(dead_code)]
-const WGM22: u8 = 3;  // Waveform Generation Mode bit 2 (not used in CTC mode, defaults to 0)
-
-// TIMSK2 bits
-const OCIE2A: u8 = 1; // Output Compare Match A Interrupt Enable
-
-// CPU frequency
-const F_CPU: u32 = 16_000_000;
+const FOC2A: u8 = 7;  // Force Output Compare A
+
+// TCCR1A/TCCR1B/TCCR1C bits
+const COM1A0: u8 = 6; // Toggle OC1A on compare match
+const COM1A1: u8 = 7; // Clear OC1A on compare match (with COM1A0 = 0)
+const WGM12: u8 = 3;  // With WGM13: CTC mode, TOP = ICR1
+const WGM13: u8 = 4;
+const FOC1A: u8 = 7;  // Force Output Compare A
+
+// ASSR bits
+const AS2: u8 = 5;    // Timer2 clocked from TOSC1 (crystal SoftRtc)
+
+// Interrupt mask and flag bits
+const TOIE2: u8 = 0;  // Timer2 Overflow (crystal SoftRtc)
+const OCIE2A: u8 = 1; // Timer2 Output Compare Match A
+const OCIE2B: u8 = 2; // Timer2 Output Compare Match B (Stepper)
+const OCIE1B: u8 = 2; // Timer1 Output Compare Match B
+const OCIE0B: u8 = 2; // Timer0 Output Compare Match B
+const OCF2A: u8 = 1;
+const OCF1B: u8 = 2;
+const OCF0B: u8 = 2;
 
 // Prescaler values for Timer2
 const PRESCALERS: [(u8, u32); 7] = [
@@ -49,14 +103,100 @@ const PRESCALERS: [(u8, u32); 7] = [
     ((1 << CS22) | (1 << CS21) | (1 << CS20), 1024),   // /1024
 ];
 
-// Global state for tone generation (protected by critical section)
-static TONE_PIN: Mutex<Cell<Option<u8>>> = Mutex::new(Cell::new(None));
-static TONE_TOGGLE_COUNT: Mutex<Cell<u32>> = Mutex::new(Cell::new(0));
-static TONE_PORT: Mutex<Cell<usize>> = Mutex::new(Cell::new(0));
-static TONE_MASK: Mutex<Cell<u8>> = Mutex::new(Cell::new(0));
+// Prescaler values for Timer1
+const PRESCALERS_TIMER1: [(u8, u32); 5] = [(1, 1), (2, 8), (3, 64), (4, 256), (5, 1024)];
+
+// Timer0 keeps the /64 prescaler set up for millis()
+const PRESCALERS_TIMER0: [(u8, u32); 1] = [(0, 64)];
+
+/// Maximum number of simultaneous tones
+pub const MAX_TONES: usize = 3;
+
+/// Volume of a plain square wave (50% duty cycle)
+pub const MAX_VOLUME: u8 = 255;
+
+/// Shortest half period in CPU cycles, leaving the ISR time to load the next one
+const MIN_HALF_CYCLES: u32 = 160;
+
+// OC2A and OC1A pins
+const OC2A_PIN: u8 = 11;
+const OC1A_PIN: u8 = 9;
+
+/// Timer behind a tone channel, also its index in `CHANNELS`
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ToneTimer {
+    Timer2 = 0,
+    Timer1 = 1,
+    Timer0 = 2,
+}
+
+impl ToneTimer {
+    /// Allocation order when a pin has no preferred timer
+    const ALL: [ToneTimer; MAX_TONES] = [ToneTimer::Timer2, ToneTimer::Timer1, ToneTimer::Timer0];
+
+    fn prescalers(self) -> &'static [(u8, u32)] {
+        match self {
+            ToneTimer::Timer2 => &PRESCALERS,
+            ToneTimer::Timer1 => &PRESCALERS_TIMER1,
+            ToneTimer::Timer0 => &PRESCALERS_TIMER0,
+        }
+    }
+
+    /// Longest half period in timer ticks
+    fn max_ticks(self) -> u32 {
+        match self {
+            ToneTimer::Timer2 => 256,
+            ToneTimer::Timer1 => 65536,
+            // Long waits are split over several compare matches
+            ToneTimer::Timer0 => u32::MAX,
+        }
+    }
+
+    /// The output compare pin this timer can toggle in hardware
+    fn hardware_pin(self) -> Option<u8> {
+        match self {
+            ToneTimer::Timer2 => Some(OC2A_PIN),
+            ToneTimer::Timer1 => Some(OC1A_PIN),
+            ToneTimer::Timer0 => None,
+        }
+    }
+}
+
+/// State of one tone channel
+#[derive(Clone, Copy)]
+struct Channel {
+    pin: u8,
+    frequency: u16,
+    volume: u8,
+    /// The timer toggles the pin itself
+    hardware: bool,
+    /// Count toggles without driving the pin (melody rests and gaps)
+    silent: bool,
+    /// Remaining toggles, 0 = forever
+    toggles: u32,
+    /// Timer ticks spent high and low
+    high_ticks: u32,
+    low_ticks: u32,
+    /// Current output level
+    level: bool,
+    /// Timer0: ticks left until the next toggle
+    wait: u32,
+}
+
+impl Channel {
+    /// Check if the pin should be driven
+    fn audible(&self) -> bool {
+        !self.silent && self.volume > 0
+    }
+}
+
+// Global state for tone generation, one slot per timer
+static CHANNELS: IrqCell<[Option<Channel>; MAX_TONES]> = IrqCell::new([None; MAX_TONES]);
 
 /// Start generating a tone on the specified pin
 ///
+/// Up to three pins can play at once. Stops a melody playing on the pin.
+///
 /// # Arguments
 /// * `pin` - Arduino pin number (0-13)
 /// * `frequency` - Frequency in Hz (31-65535)
@@ -72,65 +212,24 @@ static TONE_MASK: Mutex<Cell<u8>> = Mutex::new(Cell::new(0));
 /// tone(11, 440);
 /// ```
 pub fn tone(pin: u8, frequency: u16) {
-    if frequency == 0 || pin > 13 {
-        return;
-    }
-
-    // Find the best prescaler and OCR value
-    let mut ocr: u32 = 0;
-    let mut prescaler_bits: u8 = 0;
-
-    for &(bits, prescaler) in &PRESCALERS {
-        // Calculate OCR value: F_CPU / frequency / 2 / prescaler - 1
-        let calc_ocr = F_CPU / (frequency as u32) / 2 / prescaler;
-
-        if calc_ocr > 0 && calc_ocr <= 256 {
-            ocr = calc_ocr - 1;
-            prescaler_bits = bits;
-            break;
-        }
-    }
-
-    if ocr == 0 {
-        return; // Frequency out of range
-    }
-
-    critical_section::with(|cs| {
-        // Store pin number
-        TONE_PIN.borrow(cs).set(Some(pin));
-
-        // Set toggle count to 0 (infinite duration)
-        TONE_TOGGLE_COUNT.borrow(cs).set(0);
-
-        // Get port and mask for the pin
-        let port = pin_to_output_port(pin);
-        let mask = pin_to_bit_mask(pin);
-
-        TONE_PORT.borrow(cs).set(port as usize);
-        TONE_MASK.borrow(cs).set(mask);
-
-        unsafe {
-            // Set pin as output
-            let ddr = pin_to_ddr_port(pin);
-            write_volatile(ddr, read_volatile(ddr) | mask);
-
-            // Configure Timer2 for CTC mode
-            // WGM22:0 = 010 (CTC mode, TOP = OCR2A)
-            write_volatile(TCCR2A, 1 << WGM21);
-
-            // Set prescaler and start timer
-            write_volatile(TCCR2B, prescaler_bits);
-
-            // Set compare value
-            write_volatile(OCR2A, ocr as u8);
-
-            // Reset counter
-            write_volatile(TCNT2, 0);
+    tone_with_volume(pin, frequency, MAX_VOLUME);
+}
 
-            // Enable Timer2 Compare Match A interrupt
-            write_volatile(TIMSK2, read_volatile(TIMSK2) | (1 << OCIE2A));
-        }
-    });
+/// Start generating a tone at a reduced volume
+///
+/// The volume shortens the high part of each period: 255 is a square wave,
+/// lower values sound quieter on a piezo buzzer and 0 is silent. Very short
+/// high times are limited by the timer resolution at high frequencies.
+///
+/// # Example
+/// ```no_run
+/// use arduino_uno::tone_with_volume;
+///
+/// tone_with_volume(9, 880, 40);  // Quiet status chirp on pin 9
+/// ```
+pub fn tone_with_volume(pin: u8, frequency: u16, volume: u8) {
+    crate::melody::cancel(pin);
+    start(pin, frequency, 0, false, Some(volume));
 
     // Enable global interrupts AFTER critical section
     unsafe {
@@ -162,19 +261,34 @@ pub fn tone_duration(pin: u8, frequency: u16, duration_ms: u32) {
         return;
     }
 
-    // Calculate number of toggles needed
-    // Each toggle happens at frequency, so we need frequency * duration_ms / 1000 * 2 toggles
-    let toggles = (frequency as u32 * duration_ms * 2) / 1000;
+    crate::melody::cancel(pin);
+    start(pin, frequency, toggles_for(frequency, duration_ms), false, Some(MAX_VOLUME));
 
-    critical_section::with(|cs| {
-        TONE_TOGGLE_COUNT.borrow(cs).set(toggles);
-    });
+    unsafe {
+        core::arch::asm!("sei");
+    }
+}
 
-    tone(pin, frequency);
+/// Change the volume of the tone or melody playing on a pin
+///
+/// Melody notes keep the volume until the melody ends or `tone()` is called.
+pub fn set_tone_volume(pin: u8, volume: u8) {
+    critical_section::with(|_| {
+        let Some((timer, channel)) = find(pin) else {
+            return;
+        };
+        if let Some((prescaler_bits, high_ticks, low_ticks)) = timer_settings(timer, channel.frequency, volume.max(1)) {
+            let mut channel = Channel { volume, high_ticks, low_ticks, ..channel };
+            channel.hardware = channel.audible() && timer.hardware_pin() == Some(pin);
+            configure(timer, channel, prescaler_bits);
+        }
+    });
 }
 
 /// Stop generating tone on the specified pin
 ///
+/// Also stops a melody playing on that pin.
+///
 /// # Arguments
 /// * `pin` - Arduino pin number (0-13)
 ///
@@ -186,64 +300,385 @@ pub fn tone_duration(pin: u8, frequency: u16, duration_ms: u32) {
 /// no_tone(11);
 /// ```
 pub fn no_tone(pin: u8) {
-    critical_section::with(|cs| {
-        let current_pin = TONE_PIN.borrow(cs).get();
+    crate::melody::cancel(pin);
+    stop(pin);
+}
+
+/// Number of pin toggles (half periods) lasting `duration_ms`, at least 1
+pub(crate) fn toggles_for(frequency: u16, duration_ms: u32) -> u32 {
+    // Each toggle happens at frequency, so we need frequency * duration_ms / 1000 * 2 toggles
+    (frequency as u32 * duration_ms * 2 / 1000).max(1)
+}
+
+/// Find the prescaler bits and high/low half periods in ticks for a frequency
+fn timer_settings(timer: ToneTimer, frequency: u16, volume: u8) -> Option<(u8, u32, u32)> {
+    if frequency == 0 {
+        return None;
+    }
+
+    let f_cpu = crate::clock::cpu_frequency();
+    for &(bits, prescaler) in timer.prescalers() {
+        // Full period in timer ticks, rounded
+        let period = (f_cpu / prescaler + frequency as u32 / 2) / frequency as u32;
+        let high = if volume == MAX_VOLUME { period / 2 } else { (period * volume as u32 / 510).max(1) };
+        let low = period - high;
+
+        if high.max(low) <= timer.max_ticks() {
+            let min_ticks = MIN_HALF_CYCLES.div_ceil(prescaler).max(2);
+            return (high.min(low) >= min_ticks).then_some((bits, high, low));
+        }
+    }
 
-        if current_pin == Some(pin) {
-            unsafe {
-                // Disable Timer2 Compare Match A interrupt
+    None // Frequency out of range
+}
+
+/// Timer and state of the channel playing on `pin`
+fn find(pin: u8) -> Option<(ToneTimer, Channel)> {
+    let channels = CHANNELS.get();
+    ToneTimer::ALL
+        .into_iter()
+        .find_map(|timer| channels[timer as usize].filter(|channel| channel.pin == pin).map(|channel| (timer, channel)))
+}
+
+/// Pick the timer for a new tone on `pin`
+fn allocate(pin: u8) -> Option<ToneTimer> {
+    if let Some((timer, _)) = find(pin) {
+        return Some(timer);
+    }
+
+    let channels = CHANNELS.get();
+    let available = |timer: ToneTimer| channels[timer as usize].is_none() && timer_is_free(timer);
+
+    // Prefer the timer that can toggle this pin in hardware
+    ToneTimer::ALL
+        .into_iter()
+        .find(|&timer| timer.hardware_pin() == Some(pin) && available(timer))
+        .or_else(|| ToneTimer::ALL.into_iter().find(|&timer| available(timer)))
+}
+
+/// Check that no servo, stepper, clock or PWM output is using a timer
+fn timer_is_free(timer: ToneTimer) -> bool {
+    unsafe {
+        match timer {
+            // PWM on D3/D11, including a `PcmPlayer`, connects the Timer2
+            // outputs; a stepper ticks on compare B and a crystal `SoftRtc`
+            // clocks the timer from TOSC1
+            ToneTimer::Timer2 => {
+                read_volatile(TCCR2A) & 0xF0 == 0
+                    && read_volatile(TIMSK2) & ((1 << OCIE2B) | (1 << TOIE2)) == 0
+                    && read_volatile(ASSR) & (1 << AS2) == 0
+            }
+            // PWM on D9/D10 connects the Timer1 outputs
+            ToneTimer::Timer1 => {
+                !crate::servo::timer1_in_use() && !crate::pcm::timer1_in_use() && read_volatile(TCCR1A) & 0xF0 == 0
+            }
+            // PWM on D5/D6 switches Timer0 to fast PWM, which buffers OCR0B
+            ToneTimer::Timer0 => read_volatile(TCCR0A) & 0b11 == 0,
+        }
+    }
+}
+
+/// Start a timer toggling `pin` at `frequency` for `toggles` half periods
+///
+/// A count of 0 runs until stopped. With `silent` the pin is held low and only
+/// the toggles are counted, which times melody rests. `volume` of `None`
+/// keeps the volume of the tone already playing on the pin. Does not enable
+/// global interrupts, so it can be called from the ISR. Returns false if the
+/// pin or frequency is out of range or all timers are busy.
+pub(crate) fn start(pin: u8, frequency: u16, toggles: u32, silent: bool, volume: Option<u8>) -> bool {
+    if pin > 13 {
+        return false;
+    }
+
+    critical_section::with(|_| {
+        let Some(timer) = allocate(pin) else {
+            return false;
+        };
+        let previous = CHANNELS.get()[timer as usize];
+        let volume = volume.or(previous.map(|channel| channel.volume)).unwrap_or(MAX_VOLUME);
+
+        let Some((prescaler_bits, high_ticks, low_ticks)) = timer_settings(timer, frequency, volume.max(1)) else {
+            return false;
+        };
+
+        // The timer is held from the first tone until it stops
+        if previous.is_none() {
+            match timer {
+                ToneTimer::Timer2 => power::acquire(PowerPeripheral::Timer2),
+                ToneTimer::Timer1 => power::acquire(PowerPeripheral::Timer1),
+                // Timer0 stays powered for millis()
+                ToneTimer::Timer0 => {}
+            }
+        }
+
+        let mut channel = Channel {
+            pin,
+            frequency,
+            volume,
+            hardware: false,
+            silent,
+            toggles,
+            high_ticks,
+            low_ticks,
+            level: false,
+            wait: 0,
+        };
+        channel.hardware = channel.audible() && timer.hardware_pin() == Some(pin);
+
+        unsafe {
+            // Set pin as output
+            let ddr = pin_to_ddr_port(pin);
+            write_volatile(ddr, read_volatile(ddr) | pin_to_bit_mask(pin));
+        }
+
+        configure(timer, channel, prescaler_bits);
+        true
+    })
+}
+
+/// Program a timer for a channel and store it
+///
+/// Must be called inside a critical section.
+fn configure(timer: ToneTimer, mut channel: Channel, prescaler_bits: u8) {
+    // Only interrupt when something has to happen on a compare match
+    let needs_interrupt = !channel.hardware || channel.toggles > 0 || channel.high_ticks != channel.low_ticks;
+    // The first half period (before the first toggle) is low
+    channel.level = false;
+    let first = channel.low_ticks;
+
+    unsafe {
+        let port = pin_to_output_port(channel.pin);
+        write_volatile(port, read_volatile(port) & !pin_to_bit_mask(channel.pin));
+
+        match timer {
+            ToneTimer::Timer2 => {
                 write_volatile(TIMSK2, read_volatile(TIMSK2) & !(1 << OCIE2A));
+                write_volatile(TCCR2B, 0);
+
+                // Configure Timer2 for CTC mode
+                // WGM22:0 = 010 (CTC mode, TOP = OCR2A)
+                if channel.hardware {
+                    // Force OC2A low, then toggle it on every compare match
+                    write_volatile(TCCR2A, (1 << WGM21) | (1 << COM2A1));
+                    write_volatile(TCCR2B, 1 << FOC2A);
+                    write_volatile(TCCR2A, (1 << WGM21) | (1 << COM2A0));
+                } else {
+                    write_volatile(TCCR2A, 1 << WGM21);
+                }
 
-                // Set pin low
-                let port_addr = TONE_PORT.borrow(cs).get();
-                let mask = TONE_MASK.borrow(cs).get();
-                if port_addr != 0 {
-                    let port = port_addr as *mut u8;
-                    write_volatile(port, read_volatile(port) & !mask);
+                // Set compare value and reset counter
+                write_volatile(OCR2A, (first - 1) as u8);
+                write_volatile(TCNT2, 0);
+
+                // Set prescaler and start timer
+                write_volatile(TCCR2B, prescaler_bits);
+
+                write_volatile(TIFR2, 1 << OCF2A);
+                if needs_interrupt {
+                    write_volatile(TIMSK2, read_volatile(TIMSK2) | (1 << OCIE2A));
                 }
             }
+            ToneTimer::Timer1 => {
+                write_volatile(TIMSK1, read_volatile(TIMSK1) & !(1 << OCIE1B));
+                write_volatile(TCCR1B, 0);
+
+                // CTC mode 12 (TOP = ICR1); OC1A and the interrupt fire at BOTTOM
+                if channel.hardware {
+                    // Force OC1A low, then toggle it on every compare match
+                    write_volatile(TCCR1A, 1 << COM1A1);
+                    write_volatile(TCCR1C, 1 << FOC1A);
+                    write_volatile(TCCR1A, 1 << COM1A0);
+                } else {
+                    write_volatile(TCCR1A, 0);
+                }
 
-            TONE_PIN.borrow(cs).set(None);
-            TONE_TOGGLE_COUNT.borrow(cs).set(0);
+                write_u16(ICR1H, ICR1L, (first - 1) as u16);
+                write_u16(OCR1AH, OCR1AL, 0);
+                write_u16(OCR1BH, OCR1BL, 0);
+                write_u16(TCNT1H, TCNT1L, 0);
+
+                write_volatile(TCCR1B, (1 << WGM13) | (1 << WGM12) | prescaler_bits);
+
+                write_volatile(TIFR1, 1 << OCF1B);
+                if needs_interrupt {
+                    write_volatile(TIMSK1, read_volatile(TIMSK1) | (1 << OCIE1B));
+                }
+            }
+            ToneTimer::Timer0 => {
+                write_volatile(TIMSK0, read_volatile(TIMSK0) & !(1 << OCIE0B));
+                channel.wait = first;
+                let step = next_step(&mut channel.wait);
+                write_volatile(OCR0B, read_volatile(TCNT0).wrapping_add(step));
+                write_volatile(TIFR0, 1 << OCF0B);
+                write_volatile(TIMSK0, read_volatile(TIMSK0) | (1 << OCIE0B));
+            }
+        }
+    }
+
+    CHANNELS.lock(|channels| channels[timer as usize] = Some(channel));
+}
+
+/// Stop the tone on `pin`, if any, and release its timer
+pub(crate) fn stop(pin: u8) {
+    critical_section::with(|_| {
+        if let Some((timer, _)) = find(pin) {
+            stop_timer(timer);
+        }
+    });
+}
+
+/// Stop the tone on Timer1 before a servo takes the timer over
+pub(crate) fn release_timer1() {
+    critical_section::with(|_| {
+        if let Some(channel) = CHANNELS.get()[ToneTimer::Timer1 as usize] {
+            crate::melody::cancel(channel.pin);
+            stop_timer(ToneTimer::Timer1);
         }
     });
 }
 
+/// Stop a timer channel, set its pin low and free it
+///
+/// Must be called inside a critical section.
+fn stop_timer(timer: ToneTimer) {
+    let Some(channel) = CHANNELS.lock(|channels| channels[timer as usize].take()) else {
+        return;
+    };
+
+    unsafe {
+        match timer {
+            ToneTimer::Timer2 => {
+                // Disable Timer2 Compare Match A interrupt and the OC2A output
+                write_volatile(TIMSK2, read_volatile(TIMSK2) & !(1 << OCIE2A));
+                write_volatile(TCCR2A, read_volatile(TCCR2A) & !((1 << COM2A1) | (1 << COM2A0)));
+            }
+            ToneTimer::Timer1 => {
+                write_volatile(TIMSK1, read_volatile(TIMSK1) & !(1 << OCIE1B));
+                write_volatile(TCCR1A, 0);
+            }
+            ToneTimer::Timer0 => {
+                write_volatile(TIMSK0, read_volatile(TIMSK0) & !(1 << OCIE0B));
+            }
+        }
+
+        // Set pin low
+        let port = pin_to_output_port(channel.pin);
+        write_volatile(port, read_volatile(port) & !pin_to_bit_mask(channel.pin));
+    }
+
+    match timer {
+        ToneTimer::Timer2 => power::release(PowerPeripheral::Timer2, || unsafe { write_volatile(TCCR2B, 0) }),
+        ToneTimer::Timer1 => power::release(PowerPeripheral::Timer1, || unsafe { write_volatile(TCCR1B, 0) }),
+        ToneTimer::Timer0 => {}
+    }
+}
+
+/// Take the next Timer0 compare step out of `wait`
+///
+/// Steps are at most 255 ticks and never leave a tiny remainder that the ISR
+/// could not schedule in time.
+fn next_step(wait: &mut u32) -> u8 {
+    let step = match *wait {
+        0..=255 => *wait,
+        256..=319 => *wait / 2,
+        _ => 255,
+    };
+    *wait -= step;
+    step as u8
+}
+
+/// Handle a compare match of a channel that completes a half period
+///
+/// Toggles the pin in software if needed and returns the length of the half
+/// period that just started, plus the pin if the duration has expired.
+fn half_period_elapsed(channel: &mut Channel) -> (u32, Option<u8>) {
+    channel.level = !channel.level;
+
+    if !channel.hardware && channel.audible() {
+        unsafe {
+            // Toggle pin by XORing the bit in PORT register
+            let port = pin_to_output_port(channel.pin);
+            write_volatile(port, read_volatile(port) ^ pin_to_bit_mask(channel.pin));
+        }
+    }
+
+    // Handle duration
+    let mut finished = None;
+    if channel.toggles > 0 {
+        channel.toggles -= 1;
+        if channel.toggles == 0 {
+            finished = Some(channel.pin);
+        }
+    }
+
+    let next = if channel.level { channel.high_ticks } else { channel.low_ticks };
+    (next, finished)
+}
+
+/// Duration expired: play the next melody note or stop
+fn finish(timer: ToneTimer, pin: u8) {
+    if !crate::melody::advance(pin) {
+        stop_timer(timer);
+    }
+}
+
+unsafe fn write_u16(high: *mut u8, low: *mut u8, value: u16) {
+    write_volatile(high, (value >> 8) as u8);
+    write_volatile(low, value as u8);
+}
+
 /// Timer2 Compare Match A ISR - toggles the output pin
 #[link_section = ".text"]
 #[export_name = "__vector_7"]
 pub unsafe extern "avr-interrupt" fn __vector_7() {
-    critical_section::with(|cs| {
-        let port_addr = TONE_PORT.borrow(cs).get();
-        let mask = TONE_MASK.borrow(cs).get();
-
-        if port_addr != 0 {
-            let port = port_addr as *mut u8;
-            // Toggle pin by XORing the bit in PORT register
-            let current = read_volatile(port);
-            write_volatile(port, current ^ mask);
-        }
+    let finished = CHANNELS.lock(|channels| {
+        let channel = channels[ToneTimer::Timer2 as usize].as_mut()?;
+        let (next, finished) = half_period_elapsed(channel);
+        write_volatile(OCR2A, (next - 1) as u8);
+        finished
+    });
 
-        // Handle duration
-        let toggle_count = TONE_TOGGLE_COUNT.borrow(cs).get();
-        if toggle_count > 0 {
-            let new_count = toggle_count - 1;
-            TONE_TOGGLE_COUNT.borrow(cs).set(new_count);
+    if let Some(pin) = finished {
+        finish(ToneTimer::Timer2, pin);
+    }
+}
 
-            if new_count == 0 {
-                // Duration expired, stop tone
-                write_volatile(TIMSK2, read_volatile(TIMSK2) & !(1 << OCIE2A));
+/// Timer1 Compare Match B ISR - toggles the output pin
+#[link_section = ".text"]
+#[export_name = "__vector_12"]
+pub unsafe extern "avr-interrupt" fn __vector_12() {
+    let finished = CHANNELS.lock(|channels| {
+        let channel = channels[ToneTimer::Timer1 as usize].as_mut()?;
+        let (next, finished) = half_period_elapsed(channel);
+        write_u16(ICR1H, ICR1L, (next - 1) as u16);
+        finished
+    });
 
-                // Set pin low
-                if port_addr != 0 {
-                    let port = port_addr as *mut u8;
-                    write_volatile(port, read_volatile(port) & !mask);
-                }
+    if let Some(pin) = finished {
+        finish(ToneTimer::Timer1, pin);
+    }
+}
 
-                TONE_PIN.borrow(cs).set(None);
-            }
+/// Timer0 Compare Match B ISR - schedules and toggles the output pin
+#[link_section = ".text"]
+#[export_name = "__vector_15"]
+pub unsafe extern "avr-interrupt" fn __vector_15() {
+    let finished = CHANNELS.lock(|channels| {
+        let channel = channels[ToneTimer::Timer0 as usize].as_mut()?;
+        let mut finished = None;
+        if channel.wait == 0 {
+            let (next, done) = half_period_elapsed(channel);
+            channel.wait = next;
+            finished = done;
         }
+        let step = next_step(&mut channel.wait);
+        write_volatile(OCR0B, read_volatile(OCR0B).wrapping_add(step));
+        finished
     });
+
+    if let Some(pin) = finished {
+        finish(ToneTimer::Timer0, pin);
+    }
 }
 
 // Helper functions to get port addresses and bit masks for pins
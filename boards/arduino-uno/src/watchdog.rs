@@ -4,19 +4,26 @@
 //! - Reset the system if it becomes unresponsive
 //! - Generate interrupts for periodic tasks
 //! - Implement timeout mechanisms
+//! - Record a crash breadcrumb before a watchdog reset
+//!
+//! The watchdog interrupt (`__vector_6`) is managed by this module; register
+//! a callback with [`Watchdog::attach`] instead of defining the vector yourself.
 //!
 //! # Safety
 //! The watchdog timer persists across resets. Always disable or reset
 //! the watchdog in the early stages of your program to prevent unexpected
 //! resets during development.
 
-use core::ptr::{read_volatile, write_volatile};
+use core::mem::MaybeUninit;
+use core::ptr::{addr_of, addr_of_mut, read_volatile, write_volatile};
+use ossidata_core::irq::{IrqCell, IrqFlag};
+use ossidata_core::storage::crc16;
 
 // Watchdog Timer Control Register
 const WDTCSR: *mut u8 = 0x60 as *mut u8;
 
 // WDTCSR bits
-const _WDIF: u8 = 7;   // Watchdog Interrupt Flag
+const WDIF: u8 = 7;    // Watchdog Interrupt Flag
 const WDIE: u8 = 6;   // Watchdog Interrupt Enable
 const WDP3: u8 = 5;   // Watchdog Timer Prescaler bit 3
 const WDCE: u8 = 4;   // Watchdog Change Enable
@@ -25,10 +32,30 @@ const _WDP2: u8 = 2;   // Watchdog Timer Prescaler bit 2
 const _WDP1: u8 = 1;   // Watchdog Timer Prescaler bit 1
 const _WDP0: u8 = 0;   // Watchdog Timer Prescaler bit 0
 
-// MCU Control Register (for watchdog reset)
+// MCU Status Register (reset flags)
 const MCUSR: *mut u8 = 0x54 as *mut u8;
+const PORF: u8 = 0;   // Power-on Reset Flag
+const EXTRF: u8 = 1;  // External Reset Flag
+const BORF: u8 = 2;   // Brown-out Reset Flag
 const WDRF: u8 = 3;   // Watchdog Reset Flag
 
+// Breadcrumb layout: magic, task ID, PC (LE), millis (LE), crc16 (LE)
+const BREADCRUMB_MAGIC: u8 = 0xB7;
+const BREADCRUMB_LEN: usize = 10;
+
+// Survives a watchdog reset: the startup code neither clears nor copies `.noinit`
+#[link_section = ".noinit"]
+static mut BREADCRUMB: MaybeUninit<[u8; BREADCRUMB_LEN]> = MaybeUninit::uninit();
+
+// Return address of the interrupted code, stored by the `__vector_6` trampoline
+static mut INTERRUPTED_PC: u16 = 0;
+
+static CALLBACK: IrqCell<Option<fn()>> = IrqCell::new(None);
+static TASK_ID: IrqCell<u8> = IrqCell::new(0);
+static RESET_CAUSE: IrqCell<Option<ResetCause>> = IrqCell::new(None);
+// Set while a sleep wake-up timeout is armed; that interrupt skips the callback
+static WAKE_PENDING: IrqFlag = IrqFlag::new();
+
 /// Watchdog timeout periods
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -71,6 +98,112 @@ impl WatchdogTimeout {
             WatchdogTimeout::S8 => 8000,
         }
     }
+
+    /// Prescaler bits for WDTCSR (WDP3 is not adjacent to WDP2..0)
+    const fn prescaler_bits(&self) -> u8 {
+        let bits = *self as u8;
+        ((bits & 0b1000) << (WDP3 - 3)) | (bits & 0b0111)
+    }
+}
+
+/// Crash breadcrumb recorded by the watchdog interrupt before a reset
+///
+/// See [`Watchdog::enable_interrupt_and_reset`] and [`Watchdog::reset_cause`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breadcrumb {
+    /// Task ID set with [`Watchdog::set_task`] when the timeout hit
+    pub task_id: u8,
+    /// Byte address of the interrupted instruction (as shown by `avr-objdump`)
+    pub pc: u16,
+    /// `millis()` timestamp when the timeout hit
+    pub millis: u32,
+}
+
+impl Breadcrumb {
+    fn to_bytes(self) -> [u8; BREADCRUMB_LEN] {
+        let mut bytes = [0u8; BREADCRUMB_LEN];
+        bytes[0] = BREADCRUMB_MAGIC;
+        bytes[1] = self.task_id;
+        bytes[2..4].copy_from_slice(&self.pc.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.millis.to_le_bytes());
+        let crc = crc16(0xFFFF, &bytes[..8]);
+        bytes[8..].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; BREADCRUMB_LEN]) -> Option<Self> {
+        let crc = u16::from_le_bytes([bytes[8], bytes[9]]);
+        if bytes[0] != BREADCRUMB_MAGIC || crc16(0xFFFF, &bytes[..8]) != crc {
+            return None;
+        }
+
+        Some(Self {
+            task_id: bytes[1],
+            pc: u16::from_le_bytes([bytes[2], bytes[3]]),
+            millis: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        })
+    }
+
+    /// Read and invalidate the breadcrumb left in `.noinit` RAM
+    fn take() -> Option<Self> {
+        unsafe {
+            let slot = addr_of_mut!(BREADCRUMB) as *mut [u8; BREADCRUMB_LEN];
+            let breadcrumb = Self::from_bytes(&read_volatile(slot));
+            write_volatile(slot, [0; BREADCRUMB_LEN]);
+            breadcrumb
+        }
+    }
+
+    fn store(self) {
+        unsafe {
+            let slot = addr_of_mut!(BREADCRUMB) as *mut [u8; BREADCRUMB_LEN];
+            write_volatile(slot, self.to_bytes());
+        }
+    }
+}
+
+/// Decoded reset flags from MCUSR, plus any watchdog breadcrumb
+///
+/// # Note
+/// The Optiboot bootloader clears MCUSR before starting the sketch, so the
+/// flags may all read as zero on a stock Uno. The breadcrumb lives in RAM and
+/// is still reported in that case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetCause {
+    flags: u8,
+    breadcrumb: Option<Breadcrumb>,
+}
+
+impl ResetCause {
+    /// Raw MCUSR reset flags
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Reset caused by power-on (PORF)
+    pub fn power_on(&self) -> bool {
+        self.flags & (1 << PORF) != 0
+    }
+
+    /// Reset caused by the RESET pin (EXTRF)
+    pub fn external(&self) -> bool {
+        self.flags & (1 << EXTRF) != 0
+    }
+
+    /// Reset caused by the brown-out detector (BORF)
+    pub fn brown_out(&self) -> bool {
+        self.flags & (1 << BORF) != 0
+    }
+
+    /// Reset caused by a watchdog timeout (WDRF)
+    pub fn watchdog(&self) -> bool {
+        self.flags & (1 << WDRF) != 0
+    }
+
+    /// Breadcrumb recorded by the watchdog interrupt before the reset
+    pub fn breadcrumb(&self) -> Option<Breadcrumb> {
+        self.breadcrumb
+    }
 }
 
 /// Watchdog Timer
@@ -100,51 +233,61 @@ impl Watchdog {
     /// }
     /// ```
     pub fn enable(timeout: WatchdogTimeout) {
-        unsafe {
-            // Disable interrupts during configuration
-            core::arch::asm!("cli");
-
-            // Clear WDRF in MCUSR to prevent continuous resets
-            let mcusr = read_volatile(MCUSR);
-            write_volatile(MCUSR, mcusr & !(1 << WDRF));
-
-            // Set WDCE and WDE to enable configuration
-            let wdtcsr = read_volatile(WDTCSR);
-            write_volatile(WDTCSR, wdtcsr | (1 << WDCE) | (1 << WDE));
-
-            // Configure timeout and enable reset mode
-            let timeout_bits = timeout as u8;
-            let wdp = ((timeout_bits & 0b1000) << (WDP3 - 3)) | (timeout_bits & 0b0111);
-            write_volatile(WDTCSR, (1 << WDE) | wdp);
-
-            // Re-enable interrupts
-            core::arch::asm!("sei");
-        }
+        Self::configure((1 << WDE) | timeout.prescaler_bits());
     }
 
     /// Enable watchdog in interrupt mode
     ///
     /// The watchdog will generate an interrupt instead of resetting the system.
-    /// This can be used for periodic tasks or to implement a custom reset handler.
-    ///
-    /// # Note
-    /// You must implement the `__vector_6` interrupt handler to handle watchdog interrupts.
+    /// This can be used for periodic tasks; the callback registered with
+    /// [`Watchdog::attach`] runs on every timeout.
     pub fn enable_interrupt(timeout: WatchdogTimeout) {
-        unsafe {
-            core::arch::asm!("cli");
+        Self::configure((1 << WDIE) | timeout.prescaler_bits());
+    }
 
-            let mcusr = read_volatile(MCUSR);
-            write_volatile(MCUSR, mcusr & !(1 << WDRF));
+    /// Enable watchdog in interrupt-then-reset mode (WDIE + WDE)
+    ///
+    /// On the first timeout the interrupt records a [`Breadcrumb`] in `.noinit`
+    /// RAM and runs the attached callback. The hardware clears WDIE when the
+    /// interrupt runs, so the system resets on the next timeout unless the
+    /// watchdog is kicked and this mode re-enabled.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use arduino_uno::{Watchdog, WatchdogTimeout};
+    ///
+    /// if let Some(crumb) = Watchdog::reset_cause().breadcrumb() {
+    ///     // crumb.task_id and crumb.pc show where the program hung
+    /// }
+    ///
+    /// Watchdog::enable_interrupt_and_reset(WatchdogTimeout::S1);
+    /// loop {
+    ///     Watchdog::set_task(1);
+    ///     // Do work...
+    ///     Watchdog::reset();
+    /// }
+    /// ```
+    pub fn enable_interrupt_and_reset(timeout: WatchdogTimeout) {
+        Self::configure((1 << WDIE) | (1 << WDE) | timeout.prescaler_bits());
+    }
 
-            let wdtcsr = read_volatile(WDTCSR);
-            write_volatile(WDTCSR, wdtcsr | (1 << WDCE) | (1 << WDE));
+    /// Register a callback for the watchdog interrupt
+    ///
+    /// The callback runs in interrupt context. In interrupt-then-reset mode it
+    /// runs after the breadcrumb is recorded, so it can also flush state
+    /// before the reset.
+    pub fn attach(callback: fn()) {
+        CALLBACK.set(Some(callback));
+    }
 
-            let timeout_bits = timeout as u8;
-            let wdp = ((timeout_bits & 0b1000) << (WDP3 - 3)) | (timeout_bits & 0b0111);
-            write_volatile(WDTCSR, (1 << WDIE) | wdp);
+    /// Remove the watchdog interrupt callback
+    pub fn detach() {
+        CALLBACK.set(None);
+    }
 
-            core::arch::asm!("sei");
-        }
+    /// Set the task ID recorded in the breadcrumb on a watchdog timeout
+    pub fn set_task(task_id: u8) {
+        TASK_ID.set(task_id);
     }
 
     /// Reset (kick) the watchdog timer
@@ -176,22 +319,7 @@ impl Watchdog {
     /// This modifies global hardware state. Ensure no other code
     /// depends on the watchdog being enabled.
     pub fn disable() {
-        unsafe {
-            core::arch::asm!("cli");
-
-            // Clear WDRF
-            let mcusr = read_volatile(MCUSR);
-            write_volatile(MCUSR, mcusr & !(1 << WDRF));
-
-            // Set WDCE and WDE to enable configuration
-            let wdtcsr = read_volatile(WDTCSR);
-            write_volatile(WDTCSR, wdtcsr | (1 << WDCE) | (1 << WDE));
-
-            // Disable watchdog by clearing WDE and WDIE
-            write_volatile(WDTCSR, 0x00);
-
-            core::arch::asm!("sei");
-        }
+        Self::configure(0x00);
     }
 
     /// Check if the last reset was caused by the watchdog
@@ -219,9 +347,151 @@ impl Watchdog {
     /// This should be called early in your program to clear the WDRF bit
     /// and prevent the watchdog from immediately timing out again.
     pub fn clear_reset_flag() {
+        Self::capture_reset_cause();
         unsafe {
             let mcusr = read_volatile(MCUSR);
             write_volatile(MCUSR, mcusr & !(1 << WDRF));
         }
     }
+
+    /// Decode the cause of the last reset
+    ///
+    /// The MCUSR flags and breadcrumb are captured on the first call (or the
+    /// first watchdog reconfiguration, which clears WDRF) and the breadcrumb
+    /// is invalidated, so later calls return the same result and a following
+    /// non-watchdog reset does not report a stale breadcrumb.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use arduino_uno::Watchdog;
+    ///
+    /// let cause = Watchdog::reset_cause();
+    /// if cause.watchdog() {
+    ///     if let Some(crumb) = cause.breadcrumb() {
+    ///         // Log crumb.task_id, crumb.pc and crumb.millis
+    ///     }
+    /// }
+    /// ```
+    pub fn reset_cause() -> ResetCause {
+        Self::capture_reset_cause()
+    }
+
+    fn capture_reset_cause() -> ResetCause {
+        RESET_CAUSE.lock(|cause| {
+            *cause.get_or_insert_with(|| ResetCause {
+                flags: unsafe { read_volatile(MCUSR) } & 0x0F,
+                breadcrumb: Breadcrumb::take(),
+            })
+        })
+    }
+
+    /// Run the timed WDTCSR change sequence with the given control value
+    fn configure(control: u8) {
+        install_vector();
+        Self::capture_reset_cause();
+        critical_section::with(|_| unsafe {
+            // Clear WDRF in MCUSR, otherwise WDE cannot be cleared
+            let mcusr = read_volatile(MCUSR);
+            write_volatile(MCUSR, mcusr & !(1 << WDRF));
+
+            // Set WDCE and WDE, then write the new value within 4 cycles
+            let wdtcsr = read_volatile(WDTCSR);
+            write_volatile(WDTCSR, wdtcsr | (1 << WDCE) | (1 << WDE));
+            write_volatile(WDTCSR, control);
+        });
+    }
+}
+
+/// Arm a single watchdog interrupt to wake from sleep
+///
+/// The interrupt clears the pending flag instead of running the callback, so
+/// [`wake_pending`] tells whether the watchdog or another source woke the CPU.
+/// The watchdog counter is restarted first, so the timeout runs in full.
+pub(crate) fn arm_wake(timeout: WatchdogTimeout) {
+    WAKE_PENDING.set();
+    critical_section::with(|_| {
+        // Keeps the new timeout from firing early on a partly counted
+        // prescaler; the WDTCSR sequence in `configure` follows right away
+        unsafe { core::arch::asm!("wdr") };
+        Watchdog::configure((1 << WDIE) | timeout.prescaler_bits());
+    });
+}
+
+/// Whether the timeout armed by [`arm_wake`] has not fired yet
+pub(crate) fn wake_pending() -> bool {
+    WAKE_PENDING.is_set()
+}
+
+/// Current WDTCSR configuration, for [`restore_control`]
+pub(crate) fn control() -> u8 {
+    unsafe { read_volatile(WDTCSR) & !((1 << WDIF) | (1 << WDCE)) }
+}
+
+/// Restore a configuration saved with [`control`]
+pub(crate) fn restore_control(control: u8) {
+    WAKE_PENDING.clear();
+    Watchdog::configure(control);
+}
+
+// Watchdog interrupt entry: saves the interrupted PC, then jumps to the handler.
+//
+// The return address has to be read before a handler prologue moves the stack
+// pointer by an unknown amount. After four pushes it sits at SP+5 (high byte)
+// and SP+6 (low byte). None of these instructions touch SREG.
+//
+// The vector is emitted into its own section from inside a function, because
+// module-level assembly is not assembled for the target CPU on AVR. The
+// function itself executes nothing; `configure` calls it so it is always linked.
+// It is never inlined, so the global label is only emitted once.
+#[inline(never)]
+#[allow(named_asm_labels)]
+fn install_vector() {
+    unsafe {
+        core::arch::asm!(
+            ".pushsection .text.__vector_6,\"ax\",@progbits",
+            ".global __vector_6",
+            "__vector_6:",
+            "push r24",
+            "push r25",
+            "push r30",
+            "push r31",
+            "in r30, 0x3d",
+            "in r31, 0x3e",
+            "ldd r25, Z+5",
+            "ldd r24, Z+6",
+            "sts {pc}+1, r25",
+            "sts {pc}, r24",
+            "pop r31",
+            "pop r30",
+            "pop r25",
+            "pop r24",
+            "jmp {handler}",
+            ".popsection",
+            pc = sym INTERRUPTED_PC,
+            handler = sym watchdog_interrupt,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+}
+
+/// Watchdog timeout interrupt handler, entered from the `__vector_6` trampoline
+unsafe extern "avr-interrupt" fn watchdog_interrupt() {
+    // Sleep wake-up timeouts are consumed by the sleep loop
+    if WAKE_PENDING.take() {
+        return;
+    }
+
+    // WDE still set means the next timeout resets the system
+    if read_volatile(WDTCSR) & (1 << WDE) != 0 {
+        Breadcrumb {
+            task_id: TASK_ID.get(),
+            pc: read_volatile(addr_of!(INTERRUPTED_PC)) << 1,
+            millis: crate::millis(),
+        }
+        .store();
+    }
+
+    if let Some(callback) = CALLBACK.get() {
+        callback();
+    }
 }
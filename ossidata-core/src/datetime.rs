@@ -0,0 +1,814 @@
+//! Calendar date and time for real-time clocks
+//!
+//! [`DateTime`] covers 2000-01-01 00:00:00 to 2099-12-31 23:59:59, the range
+//! of the two-digit year registers in common RTC chips. Within that range
+//! every fourth year is a leap year, and Unix timestamps fit in a `u32`.
+//!
+//! [`TimeZone`] converts between UTC and local time with a fixed offset and
+//! optional EU or US daylight saving rules. Formatting uses `ufmt` and
+//! produces ISO-8601 (`2025-01-05T12:00:00`, or with an offset for
+//! [`ZonedDateTime`]); [`DateTime::parse_iso8601`] reads the same format.
+
+use core::ops::{Add, Sub};
+use ufmt::{uDisplay, uWrite, Formatter};
+use crate::time::Duration;
+
+const SECONDS_PER_DAY: u32 = 86_400;
+
+/// Unix timestamp of 2000-01-01 00:00:00
+const UNIX_EPOCH_2000: u32 = 946_684_800;
+
+/// Days from 2000-01-01 to 2100-01-01
+const DAYS_IN_RANGE: u32 = 36_525;
+
+/// Days in a four-year cycle starting with a leap year
+const DAYS_PER_CYCLE: u16 = 4 * 365 + 1;
+
+// Days before the first of each month in a non-leap year
+const DAYS_BEFORE_MONTH: [u16; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+const fn is_leap(year_offset: u8) -> bool {
+    // 2000 is a leap year and 2100 is outside the range
+    year_offset % 4 == 0
+}
+
+const fn days_in_month(year_offset: u8, month: u8) -> u8 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap(year_offset) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Date and time representation
+///
+/// Represents a specific point in time with no timezone information.
+/// Year range: 2000-2099. Ordering is chronological.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    // Field order gives the derived ordering
+    year_offset: u8,  // 0-99 (representing 2000-2099)
+    month: u8,        // 1-12
+    day: u8,          // 1-31
+    hour: u8,         // 0-23
+    minute: u8,       // 0-59
+    second: u8,       // 0-59
+}
+
+impl DateTime {
+    /// Create a new DateTime
+    ///
+    /// Values are not checked; see [`DateTime::is_valid`].
+    ///
+    /// # Arguments
+    /// * `year` - Full year (2000-2099)
+    /// * `month` - Month (1-12)
+    /// * `day` - Day of month (1-31)
+    /// * `hour` - Hour (0-23)
+    /// * `minute` - Minute (0-59)
+    /// * `second` - Second (0-59)
+    pub const fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        DateTime {
+            year_offset: (year.wrapping_sub(2000) & 0xFF) as u8,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Get the full year (2000-2099)
+    pub const fn year(&self) -> u16 {
+        2000 + self.year_offset as u16
+    }
+
+    /// Get the month (1-12)
+    pub const fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Get the day of month (1-31)
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Get the hour (0-23)
+    pub const fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Get the minute (0-59)
+    pub const fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Get the second (0-59)
+    pub const fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Get day of week (0=Sunday, 6=Saturday)
+    pub const fn day_of_week(&self) -> u8 {
+        // 2000-01-01 was a Saturday
+        ((self.days_since_2000() + 6) % 7) as u8
+    }
+
+    /// Get the day of the year (1-366)
+    pub const fn day_of_year(&self) -> u16 {
+        let mut days = DAYS_BEFORE_MONTH[(self.month_index()) as usize] + self.day as u16;
+        if self.month > 2 && is_leap(self.year_offset) {
+            days += 1;
+        }
+        days
+    }
+
+    /// Check if the date is valid
+    pub const fn is_valid(&self) -> bool {
+        self.year_offset <= 99
+            && self.month >= 1
+            && self.day >= 1
+            && self.day <= days_in_month(self.year_offset, self.month)
+            && self.hour <= 23
+            && self.minute <= 59
+            && self.second <= 59
+    }
+
+    /// Month as a 0-11 table index (clamped so invalid dates cannot panic)
+    const fn month_index(&self) -> u8 {
+        match self.month {
+            0 => 0,
+            m if m > 12 => 11,
+            m => m - 1,
+        }
+    }
+
+    /// Days since 2000-01-01, computed in u32 so invalid dates cannot overflow
+    const fn days_since_2000(&self) -> u32 {
+        let years = self.year_offset as u32;
+        // One extra day for each leap year before this one
+        let days = 365 * years + years.div_ceil(4) + self.day_of_year() as u32;
+        // Day 0 of an invalid date counts as the first day
+        days.saturating_sub(1)
+    }
+
+    /// Seconds since 2000-01-01 00:00:00
+    ///
+    /// Only meaningful for valid dates.
+    pub const fn seconds_since_2000(&self) -> u32 {
+        // Wrapping, so invalid dates give a wrong value rather than a panic
+        self.days_since_2000()
+            .wrapping_mul(SECONDS_PER_DAY)
+            .wrapping_add(self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32)
+    }
+
+    /// Create a DateTime from seconds since 2000-01-01 00:00:00
+    ///
+    /// Returns `None` past 2099-12-31 23:59:59.
+    pub const fn from_seconds_since_2000(seconds: u32) -> Option<Self> {
+        let days = seconds / SECONDS_PER_DAY;
+        if days >= DAYS_IN_RANGE {
+            return None;
+        }
+        let time = seconds % SECONDS_PER_DAY;
+
+        // Each four-year cycle starts with a leap year
+        let days = days as u16;
+        let cycle = days / DAYS_PER_CYCLE;
+        let mut day_of_year = days % DAYS_PER_CYCLE;
+        let mut year_offset = (cycle * 4) as u8;
+        if day_of_year >= 366 {
+            day_of_year -= 366;
+            year_offset += 1 + (day_of_year / 365) as u8;
+            day_of_year %= 365;
+        }
+
+        let mut month = 1;
+        loop {
+            let length = days_in_month(year_offset, month) as u16;
+            if day_of_year < length {
+                break;
+            }
+            day_of_year -= length;
+            month += 1;
+        }
+
+        Some(DateTime {
+            year_offset,
+            month,
+            day: day_of_year as u8 + 1,
+            hour: (time / 3600) as u8,
+            minute: (time / 60 % 60) as u8,
+            second: (time % 60) as u8,
+        })
+    }
+
+    /// Seconds since the Unix epoch (1970-01-01 00:00:00 UTC)
+    ///
+    /// Only meaningful for valid dates.
+    pub const fn unix_timestamp(&self) -> u32 {
+        UNIX_EPOCH_2000 + self.seconds_since_2000()
+    }
+
+    /// Create a DateTime from a Unix timestamp
+    ///
+    /// Returns `None` outside 2000-2099.
+    pub const fn from_unix_timestamp(timestamp: u32) -> Option<Self> {
+        if timestamp < UNIX_EPOCH_2000 {
+            return None;
+        }
+        Self::from_seconds_since_2000(timestamp - UNIX_EPOCH_2000)
+    }
+
+    /// Add a signed number of seconds
+    ///
+    /// Returns `None` if the result is outside 2000-2099.
+    pub fn checked_add_seconds(&self, seconds: i64) -> Option<Self> {
+        let total = self.seconds_since_2000() as i64 + seconds;
+        if total < 0 || total > u32::MAX as i64 {
+            return None;
+        }
+        Self::from_seconds_since_2000(total as u32)
+    }
+
+    /// Add a duration (sub-second parts are dropped)
+    ///
+    /// Returns `None` if the result is past 2099.
+    pub fn checked_add(&self, duration: Duration) -> Option<Self> {
+        let seconds = i64::try_from(duration.to_secs()).ok()?;
+        self.checked_add_seconds(seconds)
+    }
+
+    /// Subtract a duration (sub-second parts are dropped)
+    ///
+    /// Returns `None` if the result is before 2000.
+    pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
+        let seconds = i64::try_from(duration.to_secs()).ok()?;
+        self.checked_add_seconds(-seconds)
+    }
+
+    /// Signed number of seconds from `earlier` to `self`
+    ///
+    /// Negative if `earlier` is actually later.
+    pub const fn seconds_since(&self, earlier: &DateTime) -> i64 {
+        self.seconds_since_2000() as i64 - earlier.seconds_since_2000() as i64
+    }
+
+    /// Time elapsed from `earlier` to `self`, or `None` if `earlier` is later
+    pub fn duration_since(&self, earlier: &DateTime) -> Option<Duration> {
+        let seconds = self.seconds_since(earlier);
+        if seconds < 0 {
+            return None;
+        }
+        Some(Duration::secs(seconds as u64))
+    }
+
+    /// Parse an ISO-8601 date and time
+    ///
+    /// Accepts `YYYY-MM-DD`, `YYYY-MM-DDTHH:MM` and `YYYY-MM-DDTHH:MM:SS`
+    /// (with `T` or a space), optionally followed by fractional seconds
+    /// (ignored) and a `Z`, `±HH`, `±HHMM` or `±HH:MM` offset. A time with an
+    /// offset is converted to UTC; one without is returned as written.
+    pub fn parse_iso8601(text: &str) -> Result<Self, DateTimeParseError> {
+        let zoned = ZonedDateTime::parse_iso8601(text)?;
+        zoned.to_utc().ok_or(DateTimeParseError::OutOfRange)
+    }
+}
+
+impl Add<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// # Panics
+    /// If the result is past 2099; see [`DateTime::checked_add`].
+    fn add(self, duration: Duration) -> DateTime {
+        self.checked_add(duration).expect("DateTime out of range")
+    }
+}
+
+impl Sub<Duration> for DateTime {
+    type Output = DateTime;
+
+    /// # Panics
+    /// If the result is before 2000; see [`DateTime::checked_sub`].
+    fn sub(self, duration: Duration) -> DateTime {
+        self.checked_sub(duration).expect("DateTime out of range")
+    }
+}
+
+/// Write `value` as exactly `width` zero-padded digits
+fn write_padded<W: uWrite + ?Sized>(
+    f: &mut Formatter<'_, W>,
+    mut value: u16,
+    width: usize,
+) -> Result<(), W::Error> {
+    let mut digits = [b'0'; 4];
+    for digit in digits[..width].iter_mut().rev() {
+        *digit = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+    // Only ASCII digits were written
+    f.write_str(core::str::from_utf8(&digits[..width]).unwrap_or(""))
+}
+
+impl uDisplay for DateTime {
+    /// ISO-8601 without offset: `2025-01-05T12:00:00`
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        write_padded(f, self.year(), 4)?;
+        f.write_char('-')?;
+        write_padded(f, self.month as u16, 2)?;
+        f.write_char('-')?;
+        write_padded(f, self.day as u16, 2)?;
+        f.write_char('T')?;
+        write_padded(f, self.hour as u16, 2)?;
+        f.write_char(':')?;
+        write_padded(f, self.minute as u16, 2)?;
+        f.write_char(':')?;
+        write_padded(f, self.second as u16, 2)
+    }
+}
+
+/// ISO-8601 parse error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTimeParseError {
+    /// Text does not match the expected format
+    Format,
+    /// Fields are out of range or the date is outside 2000-2099
+    OutOfRange,
+}
+
+/// Daylight saving time rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DstRule {
+    /// No daylight saving time
+    None,
+    /// EU: last Sunday of March 01:00 UTC to last Sunday of October 01:00 UTC
+    Eu,
+    /// US: second Sunday of March 02:00 local to first Sunday of November 02:00 local
+    Us,
+}
+
+/// Fixed-offset time zone with an optional daylight saving rule
+///
+/// Daylight saving time adds one hour to the standard offset.
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::datetime::{DateTime, DstRule, TimeZone};
+///
+/// let berlin = TimeZone::fixed(60).with_dst(DstRule::Eu);
+/// let utc = DateTime::new(2025, 7, 1, 10, 0, 0);
+/// let local = berlin.to_local(&utc).unwrap();  // 2025-07-01T12:00:00+02:00
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeZone {
+    offset_minutes: i16,
+    dst: DstRule,
+}
+
+impl TimeZone {
+    /// Coordinated Universal Time
+    pub const UTC: TimeZone = TimeZone::fixed(0);
+
+    /// Time zone with a standard offset from UTC in minutes (east positive)
+    pub const fn fixed(offset_minutes: i16) -> Self {
+        TimeZone { offset_minutes, dst: DstRule::None }
+    }
+
+    /// Apply a daylight saving rule
+    pub const fn with_dst(self, dst: DstRule) -> Self {
+        TimeZone { dst, ..self }
+    }
+
+    /// Standard (winter) offset from UTC in minutes
+    pub const fn standard_offset(&self) -> i16 {
+        self.offset_minutes
+    }
+
+    /// Daylight saving rule
+    pub const fn dst_rule(&self) -> DstRule {
+        self.dst
+    }
+
+    /// Check whether daylight saving time is in effect at a UTC time
+    pub fn is_dst(&self, utc: &DateTime) -> bool {
+        let year = utc.year();
+        let (start, end) = match self.dst {
+            DstRule::None => return false,
+            DstRule::Eu => (
+                DateTime::new(year, 3, last_sunday(year, 3), 1, 0, 0).seconds_since_2000() as i64,
+                DateTime::new(year, 10, last_sunday(year, 10), 1, 0, 0).seconds_since_2000() as i64,
+            ),
+            DstRule::Us => {
+                let standard = self.offset_minutes as i64 * 60;
+                // 02:00 standard time, and 02:00 daylight time (01:00 standard)
+                let start = DateTime::new(year, 3, nth_sunday(year, 3, 2), 2, 0, 0);
+                let end = DateTime::new(year, 11, nth_sunday(year, 11, 1), 1, 0, 0);
+                (
+                    start.seconds_since_2000() as i64 - standard,
+                    end.seconds_since_2000() as i64 - standard,
+                )
+            }
+        };
+
+        let now = utc.seconds_since_2000() as i64;
+        now >= start && now < end
+    }
+
+    /// Offset from UTC in minutes at a UTC time, including daylight saving
+    pub fn offset_at(&self, utc: &DateTime) -> i16 {
+        if self.is_dst(utc) {
+            self.offset_minutes + 60
+        } else {
+            self.offset_minutes
+        }
+    }
+
+    /// Convert a UTC time to local time
+    ///
+    /// Returns `None` if the local time is outside 2000-2099.
+    pub fn to_local(&self, utc: &DateTime) -> Option<ZonedDateTime> {
+        let offset_minutes = self.offset_at(utc);
+        let local = utc.checked_add_seconds(offset_minutes as i64 * 60)?;
+        Some(ZonedDateTime { local, offset_minutes })
+    }
+
+    /// Convert a local time to UTC
+    ///
+    /// A local time repeated when clocks go back resolves to its standard
+    /// time (second) occurrence; a local time skipped when clocks go forward
+    /// is read as standard time. Returns `None` outside 2000-2099.
+    pub fn to_utc(&self, local: &DateTime) -> Option<DateTime> {
+        let standard = local.checked_add_seconds(-(self.offset_minutes as i64) * 60)?;
+        if self.is_dst(&standard) {
+            let daylight = standard.checked_add_seconds(-3600)?;
+            if self.is_dst(&daylight) {
+                return Some(daylight);
+            }
+        }
+        Some(standard)
+    }
+}
+
+/// Day of month of the `n`th Sunday (1-based)
+fn nth_sunday(year: u16, month: u8, n: u8) -> u8 {
+    let first = DateTime::new(year, month, 1, 0, 0, 0).day_of_week();
+    1 + (7 - first) % 7 + 7 * (n - 1)
+}
+
+/// Day of month of the last Sunday in a 31-day month
+fn last_sunday(year: u16, month: u8) -> u8 {
+    31 - DateTime::new(year, month, 31, 0, 0, 0).day_of_week()
+}
+
+/// Local date and time with its offset from UTC
+///
+/// Formats as ISO-8601 with offset, e.g. `2025-07-01T12:00:00+02:00`, or
+/// with a `Z` suffix for a zero offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZonedDateTime {
+    local: DateTime,
+    offset_minutes: i16,
+}
+
+impl ZonedDateTime {
+    /// Combine a local time with its offset from UTC in minutes
+    pub const fn new(local: DateTime, offset_minutes: i16) -> Self {
+        ZonedDateTime { local, offset_minutes }
+    }
+
+    /// Local date and time
+    pub const fn local(&self) -> DateTime {
+        self.local
+    }
+
+    /// Offset from UTC in minutes (east positive)
+    pub const fn offset_minutes(&self) -> i16 {
+        self.offset_minutes
+    }
+
+    /// Convert to UTC, or `None` if the result is outside 2000-2099
+    pub fn to_utc(&self) -> Option<DateTime> {
+        self.local.checked_add_seconds(-(self.offset_minutes as i64) * 60)
+    }
+
+    /// Parse an ISO-8601 date and time, keeping its offset
+    ///
+    /// See [`DateTime::parse_iso8601`] for the accepted forms. A missing
+    /// offset is read as zero.
+    pub fn parse_iso8601(text: &str) -> Result<Self, DateTimeParseError> {
+        let mut parser = Parser { bytes: text.as_bytes(), position: 0 };
+
+        let year = parser.number(4)?;
+        parser.expect(b'-')?;
+        let month = parser.number(2)? as u8;
+        parser.expect(b'-')?;
+        let day = parser.number(2)? as u8;
+
+        let (mut hour, mut minute, mut second) = (0, 0, 0);
+        if parser.accept(b'T') || parser.accept(b' ') {
+            hour = parser.number(2)? as u8;
+            parser.expect(b':')?;
+            minute = parser.number(2)? as u8;
+            if parser.accept(b':') {
+                second = parser.number(2)? as u8;
+                if parser.accept(b'.') || parser.accept(b',') {
+                    parser.skip_digits()?;
+                }
+            }
+        }
+
+        let offset_minutes = match parser.next() {
+            None => 0,
+            Some(b'Z') => 0,
+            Some(sign @ (b'+' | b'-')) => {
+                let hours = parser.number(2)? as i16;
+                let minutes = if parser.at_end() {
+                    0
+                } else {
+                    parser.accept(b':');
+                    parser.number(2)? as i16
+                };
+                if hours > 23 || minutes > 59 {
+                    return Err(DateTimeParseError::OutOfRange);
+                }
+                let offset = hours * 60 + minutes;
+                if sign == b'-' { -offset } else { offset }
+            }
+            Some(_) => return Err(DateTimeParseError::Format),
+        };
+        if !parser.at_end() {
+            return Err(DateTimeParseError::Format);
+        }
+
+        if !(2000..=2099).contains(&year) {
+            return Err(DateTimeParseError::OutOfRange);
+        }
+        let local = DateTime::new(year, month, day, hour, minute, second);
+        if !local.is_valid() {
+            return Err(DateTimeParseError::OutOfRange);
+        }
+        Ok(ZonedDateTime { local, offset_minutes })
+    }
+}
+
+impl uDisplay for ZonedDateTime {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        self.local.fmt(f)?;
+        if self.offset_minutes == 0 {
+            return f.write_char('Z');
+        }
+        f.write_char(if self.offset_minutes < 0 { '-' } else { '+' })?;
+        let offset = self.offset_minutes.unsigned_abs();
+        write_padded(f, offset / 60, 2)?;
+        f.write_char(':')?;
+        write_padded(f, offset % 60, 2)
+    }
+}
+
+/// Byte cursor for ISO-8601 parsing
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn at_end(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.bytes.get(self.position).copied();
+        self.position += 1;
+        byte
+    }
+
+    fn accept(&mut self, expected: u8) -> bool {
+        if self.bytes.get(self.position) == Some(&expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), DateTimeParseError> {
+        if self.accept(expected) { Ok(()) } else { Err(DateTimeParseError::Format) }
+    }
+
+    /// Exactly `digits` decimal digits
+    fn number(&mut self, digits: usize) -> Result<u16, DateTimeParseError> {
+        let mut value = 0u16;
+        for _ in 0..digits {
+            match self.next() {
+                Some(byte @ b'0'..=b'9') => value = value * 10 + (byte - b'0') as u16,
+                _ => return Err(DateTimeParseError::Format),
+            }
+        }
+        Ok(value)
+    }
+
+    /// One or more decimal digits
+    fn skip_digits(&mut self) -> Result<(), DateTimeParseError> {
+        let start = self.position;
+        while matches!(self.bytes.get(self.position), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+        if self.position == start { Err(DateTimeParseError::Format) } else { Ok(()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ufmt writer into a fixed buffer
+    struct Buffer {
+        data: [u8; 40],
+        len: usize,
+    }
+
+    impl uWrite for Buffer {
+        type Error = ();
+
+        fn write_str(&mut self, s: &str) -> Result<(), ()> {
+            self.data[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+            self.len += s.len();
+            Ok(())
+        }
+    }
+
+    fn format<T: uDisplay>(value: &T) -> Buffer {
+        let mut buffer = Buffer { data: [0; 40], len: 0 };
+        ufmt::uwrite!(buffer, "{}", value).unwrap();
+        buffer
+    }
+
+    fn formatted<T: uDisplay>(value: &T, expected: &str) {
+        let buffer = format(value);
+        assert_eq!(&buffer.data[..buffer.len], expected.as_bytes());
+    }
+
+    #[test]
+    fn unix_timestamp_roundtrip() {
+        let dt = DateTime::new(2025, 1, 5, 12, 34, 56);
+        assert_eq!(dt.unix_timestamp(), 1_736_080_496);
+        assert_eq!(DateTime::from_unix_timestamp(1_736_080_496), Some(dt));
+
+        assert_eq!(DateTime::new(2000, 1, 1, 0, 0, 0).unix_timestamp(), UNIX_EPOCH_2000);
+        assert_eq!(DateTime::new(2024, 2, 29, 23, 59, 59).unix_timestamp(), 1_709_251_199);
+        assert_eq!(DateTime::new(2099, 12, 31, 23, 59, 59).unix_timestamp(), 4_102_444_799);
+        assert_eq!(DateTime::from_unix_timestamp(4_102_444_800), None);
+        assert_eq!(DateTime::from_unix_timestamp(UNIX_EPOCH_2000 - 1), None);
+    }
+
+    #[test]
+    fn every_day_roundtrips() {
+        let mut expected = DateTime::new(2000, 1, 1, 0, 0, 0);
+        for day in 0..DAYS_IN_RANGE {
+            let dt = DateTime::from_seconds_since_2000(day * SECONDS_PER_DAY).unwrap();
+            assert_eq!(dt, expected);
+            assert!(dt.is_valid());
+            assert_eq!(dt.seconds_since_2000(), day * SECONDS_PER_DAY);
+
+            // Step to the next calendar day by hand
+            expected = if expected.day < days_in_month(expected.year_offset, expected.month) {
+                DateTime { day: expected.day + 1, ..expected }
+            } else if expected.month < 12 {
+                DateTime { day: 1, month: expected.month + 1, ..expected }
+            } else {
+                DateTime { day: 1, month: 1, year_offset: expected.year_offset + 1, ..expected }
+            };
+        }
+    }
+
+    #[test]
+    fn weekday_and_day_of_year() {
+        assert_eq!(DateTime::new(2000, 1, 1, 0, 0, 0).day_of_week(), 6);
+        assert_eq!(DateTime::new(2025, 1, 5, 0, 0, 0).day_of_week(), 0);
+        assert_eq!(DateTime::new(2024, 12, 31, 0, 0, 0).day_of_year(), 366);
+        assert_eq!(DateTime::new(2025, 3, 1, 0, 0, 0).day_of_year(), 60);
+    }
+
+    #[test]
+    fn invalid_dates_do_not_panic() {
+        for dt in [
+            DateTime::new(1999, 12, 31, 0, 0, 0),
+            DateTime::new(2000, 1, 0, 0, 0, 0),
+            DateTime::new(2255, 13, 255, 255, 255, 255),
+        ] {
+            assert!(!dt.is_valid());
+            assert!(dt.day_of_week() < 7);
+            let _ = dt.seconds_since_2000();
+        }
+    }
+
+    #[test]
+    fn arithmetic_and_ordering() {
+        let dt = DateTime::new(2024, 2, 28, 23, 0, 0);
+        let later = dt + Duration::secs(2 * 3600);
+        assert_eq!(later, DateTime::new(2024, 2, 29, 1, 0, 0));
+        assert_eq!(later - Duration::secs(2 * 3600), dt);
+        assert!(later > dt);
+
+        assert_eq!(later.seconds_since(&dt), 7200);
+        assert_eq!(dt.seconds_since(&later), -7200);
+        assert_eq!(later.duration_since(&dt), Some(Duration::secs(7200)));
+        assert_eq!(dt.duration_since(&later), None);
+
+        let first = DateTime::new(2000, 1, 1, 0, 0, 0);
+        assert_eq!(first.checked_sub(Duration::secs(1)), None);
+        assert_eq!(DateTime::new(2099, 12, 31, 23, 59, 59).checked_add(Duration::secs(1)), None);
+        assert!(DateTime::new(2000, 12, 31, 0, 0, 0) < DateTime::new(2001, 1, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn validity() {
+        assert!(DateTime::new(2024, 2, 29, 0, 0, 0).is_valid());
+        assert!(!DateTime::new(2025, 2, 29, 0, 0, 0).is_valid());
+        assert!(!DateTime::new(2025, 4, 31, 0, 0, 0).is_valid());
+        assert!(!DateTime::new(2025, 13, 1, 0, 0, 0).is_valid());
+        assert!(!DateTime::new(1999, 12, 31, 0, 0, 0).is_valid());
+        assert!(!DateTime::new(2025, 1, 1, 24, 0, 0).is_valid());
+    }
+
+    #[test]
+    fn eu_dst_transitions() {
+        let berlin = TimeZone::fixed(60).with_dst(DstRule::Eu);
+
+        // 2025: last Sundays are March 30 and October 26
+        assert!(!berlin.is_dst(&DateTime::new(2025, 3, 30, 0, 59, 59)));
+        assert!(berlin.is_dst(&DateTime::new(2025, 3, 30, 1, 0, 0)));
+        assert!(berlin.is_dst(&DateTime::new(2025, 10, 26, 0, 59, 59)));
+        assert!(!berlin.is_dst(&DateTime::new(2025, 10, 26, 1, 0, 0)));
+
+        let summer = berlin.to_local(&DateTime::new(2025, 7, 1, 10, 0, 0)).unwrap();
+        assert_eq!(summer.local(), DateTime::new(2025, 7, 1, 12, 0, 0));
+        assert_eq!(summer.offset_minutes(), 120);
+        assert_eq!(summer.to_utc(), Some(DateTime::new(2025, 7, 1, 10, 0, 0)));
+
+        let winter = berlin.to_local(&DateTime::new(2025, 1, 1, 10, 0, 0)).unwrap();
+        assert_eq!(winter.local(), DateTime::new(2025, 1, 1, 11, 0, 0));
+    }
+
+    #[test]
+    fn us_dst_transitions() {
+        let new_york = TimeZone::fixed(-300).with_dst(DstRule::Us);
+
+        // 2025: March 9 02:00 EST (07:00 UTC) to November 2 02:00 EDT (06:00 UTC)
+        assert!(!new_york.is_dst(&DateTime::new(2025, 3, 9, 6, 59, 59)));
+        assert!(new_york.is_dst(&DateTime::new(2025, 3, 9, 7, 0, 0)));
+        assert!(new_york.is_dst(&DateTime::new(2025, 11, 2, 5, 59, 59)));
+        assert!(!new_york.is_dst(&DateTime::new(2025, 11, 2, 6, 0, 0)));
+    }
+
+    #[test]
+    fn local_to_utc() {
+        let berlin = TimeZone::fixed(60).with_dst(DstRule::Eu);
+        assert_eq!(
+            berlin.to_utc(&DateTime::new(2025, 7, 1, 12, 0, 0)),
+            Some(DateTime::new(2025, 7, 1, 10, 0, 0))
+        );
+        assert_eq!(
+            berlin.to_utc(&DateTime::new(2025, 1, 1, 12, 0, 0)),
+            Some(DateTime::new(2025, 1, 1, 11, 0, 0))
+        );
+        // Repeated hour resolves to standard time
+        assert_eq!(
+            berlin.to_utc(&DateTime::new(2025, 10, 26, 2, 30, 0)),
+            Some(DateTime::new(2025, 10, 26, 1, 30, 0))
+        );
+        assert_eq!(TimeZone::UTC.to_utc(&DateTime::new(2025, 1, 1, 0, 0, 0)), Some(DateTime::new(2025, 1, 1, 0, 0, 0)));
+    }
+
+    #[test]
+    fn iso8601_formatting() {
+        formatted(&DateTime::new(2025, 1, 5, 9, 3, 7), "2025-01-05T09:03:07");
+        formatted(&ZonedDateTime::new(DateTime::new(2025, 7, 1, 12, 0, 0), 120), "2025-07-01T12:00:00+02:00");
+        formatted(&ZonedDateTime::new(DateTime::new(2025, 7, 1, 12, 0, 0), -330), "2025-07-01T12:00:00-05:30");
+        formatted(&ZonedDateTime::new(DateTime::new(2025, 7, 1, 12, 0, 0), 0), "2025-07-01T12:00:00Z");
+    }
+
+    #[test]
+    fn iso8601_parsing() {
+        let dt = DateTime::new(2025, 1, 5, 12, 34, 56);
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T12:34:56"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05 12:34:56Z"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T12:34:56.789Z"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T14:34:56+02:00"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T07:34:56-0500"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T13:34:56+01"), Ok(dt));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05"), Ok(DateTime::new(2025, 1, 5, 0, 0, 0)));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T12:34"), Ok(DateTime::new(2025, 1, 5, 12, 34, 0)));
+
+        let zoned = ZonedDateTime::parse_iso8601("2025-07-01T12:00:00+02:00").unwrap();
+        assert_eq!(zoned.offset_minutes(), 120);
+        assert_eq!(zoned.local(), DateTime::new(2025, 7, 1, 12, 0, 0));
+
+        assert_eq!(DateTime::parse_iso8601("2025-1-05"), Err(DateTimeParseError::Format));
+        assert_eq!(DateTime::parse_iso8601("2025-01-05T12:34:56 "), Err(DateTimeParseError::Format));
+        assert_eq!(DateTime::parse_iso8601("2025-02-30"), Err(DateTimeParseError::OutOfRange));
+        assert_eq!(DateTime::parse_iso8601("1999-12-31"), Err(DateTimeParseError::OutOfRange));
+    }
+}
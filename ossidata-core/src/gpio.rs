@@ -44,6 +44,9 @@ impl embedded_hal::digital::Error for GpioError {
 
 impl<const N: u8, MODE> Pin<N, MODE> {
     /// Create a new pin (unsafe because it doesn't check hardware state)
+    ///
+    /// # Safety
+    /// Caller must ensure exclusive access to this pin
     pub const unsafe fn new() -> Self {
         Self {
             _mode: PhantomData,
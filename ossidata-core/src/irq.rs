@@ -0,0 +1,484 @@
+//! Interrupt-safe shared state
+//!
+//! Primitives for sharing data between interrupt handlers and the main program
+//! without `static mut`:
+//!
+//! - [`IrqFlag`]: a boolean event flag
+//! - [`IrqCounter`]: a wrapping event counter (`u8`, `u16` or `u32`)
+//! - [`ByteQueue`]: a lock-free single-producer/single-consumer byte queue
+//! - [`IrqCell`]: a value of any type behind a critical section
+//!
+//! All of them can be placed in a `static` and used from both sides. Wherever
+//! interrupts have to be masked (multi-byte counters, `IrqCell`), the previous
+//! interrupt state is saved and restored rather than unconditionally
+//! re-enabling interrupts, so they are safe to use inside ISRs and in code that
+//! already runs with interrupts disabled. On AVR this means SREG is saved and
+//! restored around the access.
+
+use core::cell::RefCell;
+use critical_section::Mutex;
+use portable_atomic::{AtomicBool, AtomicU16, AtomicU32, AtomicU8, Ordering};
+
+/// A boolean flag shared between an interrupt handler and the main program
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::irq::IrqFlag;
+///
+/// static DATA_READY: IrqFlag = IrqFlag::new();
+///
+/// // In the ISR
+/// DATA_READY.set();
+///
+/// // In the main loop
+/// if DATA_READY.take() {
+///     // Handle the event exactly once
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct IrqFlag {
+    value: AtomicBool,
+}
+
+impl IrqFlag {
+    /// Create a cleared flag
+    pub const fn new() -> Self {
+        Self { value: AtomicBool::new(false) }
+    }
+
+    /// Set the flag
+    #[inline]
+    pub fn set(&self) {
+        self.value.store(true, Ordering::Release);
+    }
+
+    /// Clear the flag
+    #[inline]
+    pub fn clear(&self) {
+        self.value.store(false, Ordering::Release);
+    }
+
+    /// Check whether the flag is set without clearing it
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        self.value.load(Ordering::Acquire)
+    }
+
+    /// Clear the flag, returning whether it was set
+    #[inline]
+    pub fn take(&self) -> bool {
+        self.value.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// Integer types usable with [`IrqCounter`]
+///
+/// Implemented for `u8`, `u16` and `u32`.
+pub trait CounterValue: Copy + sealed::Sealed {
+    /// Atomic storage for this type
+    #[doc(hidden)]
+    type Atomic;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A wrapping counter shared between an interrupt handler and the main program
+///
+/// Reads of multi-byte counters are never torn, even on 8-bit targets.
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::irq::IrqCounter;
+///
+/// static PULSES: IrqCounter<u32> = IrqCounter::<u32>::new();
+///
+/// // In the ISR
+/// PULSES.increment();
+///
+/// // In the main loop
+/// let pulses_this_second = PULSES.take();
+/// ```
+pub struct IrqCounter<T: CounterValue> {
+    value: T::Atomic,
+}
+
+macro_rules! impl_counter {
+    ($($ty:ty => $atomic:ty),*) => {$(
+        impl sealed::Sealed for $ty {}
+
+        impl CounterValue for $ty {
+            type Atomic = $atomic;
+        }
+
+        impl IrqCounter<$ty> {
+            /// Create a counter starting at zero
+            pub const fn new() -> Self {
+                Self { value: <$atomic>::new(0) }
+            }
+
+            /// Add one, returning the previous value
+            #[inline]
+            pub fn increment(&self) -> $ty {
+                self.add(1)
+            }
+
+            /// Add `amount` (wrapping), returning the previous value
+            #[inline]
+            pub fn add(&self, amount: $ty) -> $ty {
+                self.value.fetch_add(amount, Ordering::AcqRel)
+            }
+
+            /// Get the current value
+            #[inline]
+            pub fn get(&self) -> $ty {
+                self.value.load(Ordering::Acquire)
+            }
+
+            /// Overwrite the current value
+            #[inline]
+            pub fn set(&self, value: $ty) {
+                self.value.store(value, Ordering::Release);
+            }
+
+            /// Reset to zero, returning the previous value
+            #[inline]
+            pub fn take(&self) -> $ty {
+                self.value.swap(0, Ordering::AcqRel)
+            }
+        }
+
+        impl Default for IrqCounter<$ty> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    )*};
+}
+
+impl_counter!(u8 => AtomicU8, u16 => AtomicU16, u32 => AtomicU32);
+
+/// A lock-free single-producer/single-consumer byte queue
+///
+/// Typically an ISR pushes received bytes and the main program pops them (or
+/// the other way around for transmit buffers). Neither side ever masks
+/// interrupts, so pushing from an ISR never delays other interrupts.
+///
+/// `N` is the size of the backing array and must be between 2 and 256; one
+/// slot is kept free to tell a full queue from an empty one, so the queue
+/// holds at most `N - 1` bytes.
+///
+/// Only one context may push and only one context may pop. Violating this
+/// cannot cause memory unsafety, but bytes may be lost or duplicated.
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::irq::ByteQueue;
+///
+/// static RX: ByteQueue<64> = ByteQueue::new();
+///
+/// // In the receive ISR
+/// if RX.push(byte).is_err() {
+///     // Queue full, byte dropped
+/// }
+///
+/// // In the main loop
+/// while let Some(byte) = RX.pop() {
+///     // Process byte
+/// }
+/// ```
+pub struct ByteQueue<const N: usize> {
+    buffer: [AtomicU8; N],
+    // Next slot to write, only advanced by the producer
+    head: AtomicU8,
+    // Next slot to read, only advanced by the consumer
+    tail: AtomicU8,
+}
+
+impl<const N: usize> ByteQueue<N> {
+    const VALID_SIZE: () = assert!(N >= 2 && N <= 256, "ByteQueue size must be between 2 and 256");
+
+    /// Create an empty queue
+    pub const fn new() -> Self {
+        let () = Self::VALID_SIZE;
+        Self {
+            buffer: [const { AtomicU8::new(0) }; N],
+            head: AtomicU8::new(0),
+            tail: AtomicU8::new(0),
+        }
+    }
+
+    #[inline]
+    fn next(index: u8) -> u8 {
+        // For N = 256 the u8 index wraps on its own
+        let next = index.wrapping_add(1);
+        if next as usize == N { 0 } else { next }
+    }
+
+    /// Append a byte (producer side)
+    ///
+    /// Returns the byte back as `Err` if the queue is full.
+    pub fn push(&self, byte: u8) -> Result<(), u8> {
+        let head = self.head.load(Ordering::Relaxed);
+        let next = Self::next(head);
+
+        if next == self.tail.load(Ordering::Acquire) {
+            return Err(byte);
+        }
+
+        self.buffer[head as usize].store(byte, Ordering::Relaxed);
+        self.head.store(next, Ordering::Release);
+        Ok(())
+    }
+
+    /// Remove the oldest byte (consumer side)
+    pub fn pop(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let byte = self.buffer[tail as usize].load(Ordering::Relaxed);
+        self.tail.store(Self::next(tail), Ordering::Release);
+        Some(byte)
+    }
+
+    /// Look at the oldest byte without removing it (consumer side)
+    pub fn peek(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        Some(self.buffer[tail as usize].load(Ordering::Relaxed))
+    }
+
+    /// Discard all queued bytes (consumer side)
+    pub fn clear(&self) {
+        self.tail.store(self.head.load(Ordering::Acquire), Ordering::Release);
+    }
+
+    /// Number of bytes currently queued
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire) as usize;
+        let tail = self.tail.load(Ordering::Acquire) as usize;
+        (N + head - tail) % N
+    }
+
+    /// Check whether the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) == self.tail.load(Ordering::Acquire)
+    }
+
+    /// Check whether the queue is full
+    pub fn is_full(&self) -> bool {
+        Self::next(self.head.load(Ordering::Acquire)) == self.tail.load(Ordering::Acquire)
+    }
+
+    /// Maximum number of bytes the queue can hold (`N - 1`)
+    pub const fn capacity(&self) -> usize {
+        N - 1
+    }
+}
+
+impl<const N: usize> Default for ByteQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A value shared between an interrupt handler and the main program
+///
+/// Every access runs inside a critical section. Use it for state that does
+/// not fit in an atomic, such as structs or 64-bit counters.
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::irq::IrqCell;
+///
+/// #[derive(Clone, Copy)]
+/// struct Reading { channel: u8, value: u16 }
+///
+/// static LAST: IrqCell<Reading> = IrqCell::new(Reading { channel: 0, value: 0 });
+///
+/// // In the ISR
+/// LAST.set(Reading { channel: 1, value: 512 });
+///
+/// // In the main loop
+/// let reading = LAST.get();
+/// ```
+pub struct IrqCell<T> {
+    value: Mutex<RefCell<T>>,
+}
+
+impl<T> IrqCell<T> {
+    /// Create a new cell
+    pub const fn new(value: T) -> Self {
+        Self { value: Mutex::new(RefCell::new(value)) }
+    }
+
+    /// Run `f` with exclusive access to the value
+    ///
+    /// Interrupts are masked while `f` runs, so keep it short.
+    ///
+    /// # Panics
+    /// Panics if called again from inside `f` on the same cell.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.value.borrow_ref_mut(cs)))
+    }
+
+    /// Replace the value, returning the previous one
+    pub fn replace(&self, value: T) -> T {
+        self.lock(|current| core::mem::replace(current, value))
+    }
+
+    /// Consume the cell and return the value
+    pub fn into_inner(self) -> T {
+        self.value.into_inner().into_inner()
+    }
+}
+
+impl<T: Copy> IrqCell<T> {
+    /// Get a copy of the value
+    pub fn get(&self) -> T {
+        self.lock(|value| *value)
+    }
+
+    /// Overwrite the value
+    pub fn set(&self, value: T) {
+        self.lock(|current| *current = value);
+    }
+
+    /// Apply `f` to the value, returning the new value
+    pub fn update(&self, f: impl FnOnce(T) -> T) -> T {
+        self.lock(|current| {
+            *current = f(*current);
+            *current
+        })
+    }
+}
+
+impl<T: Default> Default for IrqCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_push_pop_in_order() {
+        let queue: ByteQueue<8> = ByteQueue::new();
+        assert!(queue.is_empty());
+
+        for byte in 1..=5 {
+            queue.push(byte).unwrap();
+        }
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.peek(), Some(1));
+
+        for byte in 1..=5 {
+            assert_eq!(queue.pop(), Some(byte));
+        }
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn queue_full_returns_byte() {
+        let queue: ByteQueue<4> = ByteQueue::new();
+        assert_eq!(queue.capacity(), 3);
+
+        for byte in 0..3 {
+            queue.push(byte).unwrap();
+        }
+        assert!(queue.is_full());
+        assert_eq!(queue.push(42), Err(42));
+
+        assert_eq!(queue.pop(), Some(0));
+        queue.push(42).unwrap();
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn queue_wraps_around() {
+        let queue: ByteQueue<4> = ByteQueue::new();
+
+        for round in 0..20u8 {
+            queue.push(round).unwrap();
+            queue.push(round.wrapping_mul(3)).unwrap();
+            assert_eq!(queue.len(), 2);
+            assert_eq!(queue.pop(), Some(round));
+            assert_eq!(queue.pop(), Some(round.wrapping_mul(3)));
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn queue_full_size_wraps_at_256() {
+        let queue: ByteQueue<256> = ByteQueue::new();
+        assert_eq!(queue.capacity(), 255);
+
+        for byte in 0..255u8 {
+            queue.push(byte).unwrap();
+        }
+        assert!(queue.is_full());
+        assert_eq!(queue.push(0), Err(0));
+
+        for expected in 0..200u8 {
+            assert_eq!(queue.pop(), Some(expected));
+        }
+        for byte in 0..100u8 {
+            queue.push(byte).unwrap();
+        }
+        assert_eq!(queue.len(), 155);
+    }
+
+    #[test]
+    fn queue_clear_discards_pending() {
+        let queue: ByteQueue<8> = ByteQueue::new();
+        queue.push(1).unwrap();
+        queue.push(2).unwrap();
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn counter_wraps_and_takes() {
+        let counter = IrqCounter::<u8>::new();
+        counter.set(254);
+        assert_eq!(counter.increment(), 254);
+        assert_eq!(counter.increment(), 255);
+        assert_eq!(counter.get(), 0);
+
+        let counter = IrqCounter::<u32>::new();
+        counter.add(1000);
+        assert_eq!(counter.take(), 1000);
+        assert_eq!(counter.get(), 0);
+    }
+
+    #[test]
+    fn flag_take_clears() {
+        let flag = IrqFlag::new();
+        assert!(!flag.take());
+        flag.set();
+        assert!(flag.is_set());
+        assert!(flag.take());
+        assert!(!flag.is_set());
+    }
+
+    #[test]
+    fn cell_lock_and_update() {
+        let cell = IrqCell::new(10u64);
+        assert_eq!(cell.update(|v| v * 2), 20);
+        assert_eq!(cell.replace(5), 20);
+        cell.lock(|v| *v += 1);
+        assert_eq!(cell.get(), 6);
+    }
+}
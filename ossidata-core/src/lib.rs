@@ -6,11 +6,22 @@
 #![no_std]
 #![warn(missing_docs)]
 
+pub mod datetime;
 pub mod gpio;
+pub mod irq;
+pub mod melody;
+pub mod pid;
+pub mod quadrature;
+pub mod stepper;
+pub mod storage;
 pub mod prelude;
+pub mod time;
 
 /// Re-export embedded-hal traits
 pub use embedded_hal;
 
+/// Re-export fugit time types
+pub use fugit;
+
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
\ No newline at end of file
@@ -0,0 +1,381 @@
+//! Melody notes and RTTTL ringtone parsing
+//!
+//! A melody is a sequence of [`Note`]s, each a frequency and a length in
+//! 1/64 of a whole note, so the same melody can be played at any tempo.
+//! [`RtttlParser`] reads melodies in the RTTTL (Nokia ringtone) format:
+//!
+//! ```text
+//! Beep:d=8,o=5,b=160:c,e,g,4c6,p,g,2c6
+//! ```
+//!
+//! The parser reads its input one byte at a time through a callback, so the
+//! text can stay in flash and be parsed one note at a time while playing:
+//!
+//! ```ignore
+//! use ossidata_core::melody::RtttlParser;
+//!
+//! let text = b"Beep:d=8,o=5,b=160:c,e,g,4c6";
+//! let read = |i: usize| text[i];
+//! let mut parser = RtttlParser::new(text.len(), read)?;
+//! while let Some(note) = parser.next_note(read) {
+//!     play(note?.frequency, note?.duration_ms(parser.tempo()));
+//! }
+//! ```
+
+/// Frequencies of the notes C8 to B8 in Hz; lower octaves halve them
+const OCTAVE_8: [u16; 12] = [4186, 4435, 4699, 4978, 5274, 5588, 5920, 6272, 6645, 7040, 7459, 7902];
+
+/// Frequency in Hz of a MIDI note number (60 = C4, 69 = A4 = 440 Hz)
+pub const fn note_frequency(midi: u8) -> u16 {
+    let base = OCTAVE_8[(midi % 12) as usize];
+    let octave = midi / 12;
+    if octave >= 9 {
+        // Octave 8 and above (MIDI 108-127)
+        base << (octave - 9)
+    } else {
+        let shift = 9 - octave;
+        (base + (1 << (shift - 1))) >> shift
+    }
+}
+
+/// A note or rest in a melody
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note {
+    /// Frequency in Hz, 0 for a rest
+    pub frequency: u16,
+    /// Length in 1/64 of a whole note (see [`Note::QUARTER`] etc.)
+    pub length: u16,
+}
+
+impl Note {
+    /// Length of a whole note
+    pub const WHOLE: u16 = 64;
+    /// Length of a half note
+    pub const HALF: u16 = 32;
+    /// Length of a quarter note (one beat)
+    pub const QUARTER: u16 = 16;
+    /// Length of an eighth note
+    pub const EIGHTH: u16 = 8;
+    /// Length of a sixteenth note
+    pub const SIXTEENTH: u16 = 4;
+    /// Length of a thirty-second note
+    pub const THIRTY_SECOND: u16 = 2;
+
+    /// Create a note
+    pub const fn new(frequency: u16, length: u16) -> Self {
+        Self { frequency, length }
+    }
+
+    /// Create a rest
+    pub const fn rest(length: u16) -> Self {
+        Self { frequency: 0, length }
+    }
+
+    /// Lengthen a note length by half, e.g. `Note::dotted(Note::QUARTER)`
+    pub const fn dotted(length: u16) -> u16 {
+        length + length / 2
+    }
+
+    /// Check if this is a rest
+    pub const fn is_rest(&self) -> bool {
+        self.frequency == 0
+    }
+
+    /// Duration in milliseconds at a tempo in quarter notes per minute
+    pub const fn duration_ms(&self, bpm: u16) -> u32 {
+        if bpm == 0 {
+            return 0;
+        }
+        // One quarter note lasts 60000 / bpm ms and is 16 length units
+        self.length as u32 * 3750 / bpm as u32
+    }
+}
+
+/// Error in an RTTTL melody
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtttlError {
+    /// The name or settings section is not terminated by `:`
+    MissingSection,
+    /// A `d=`, `o=` or `b=` setting is unknown or out of range
+    InvalidSetting,
+    /// A note cannot be parsed
+    InvalidNote,
+}
+
+/// Default note length (quarter note) when the settings omit `d=`
+const DEFAULT_DURATION: u8 = 4;
+/// Default octave when the settings omit `o=`
+const DEFAULT_OCTAVE: u8 = 6;
+/// Default tempo when the settings omit `b=`
+const DEFAULT_BPM: u16 = 63;
+
+/// Incremental parser for RTTTL melodies
+///
+/// Holds only the settings and the current position, not the text itself.
+/// Pass the same `read` callback to [`Self::new`] and every
+/// [`Self::next_note`].
+#[derive(Debug, Clone, Copy)]
+pub struct RtttlParser {
+    duration: u8,
+    octave: u8,
+    bpm: u16,
+    start: usize,
+    pos: usize,
+    len: usize,
+}
+
+impl RtttlParser {
+    /// Parse the name and settings of a `len` byte melody
+    ///
+    /// `read(i)` returns the byte at index `i`, for `i < len`.
+    pub fn new(len: usize, read: impl Fn(usize) -> u8) -> Result<Self, RtttlError> {
+        let mut parser = Self {
+            duration: DEFAULT_DURATION,
+            octave: DEFAULT_OCTAVE,
+            bpm: DEFAULT_BPM,
+            start: 0,
+            pos: 0,
+            len,
+        };
+
+        // Skip the name
+        while parser.peek(&read).ok_or(RtttlError::MissingSection)? != b':' {
+            parser.pos += 1;
+        }
+        parser.pos += 1;
+
+        // Settings: comma-separated key=value pairs up to the next ':'
+        loop {
+            parser.skip_whitespace(&read);
+            match parser.bump(&read).ok_or(RtttlError::MissingSection)? {
+                b':' => break,
+                b',' => continue,
+                key => {
+                    parser.skip_whitespace(&read);
+                    if parser.bump(&read) != Some(b'=') {
+                        return Err(RtttlError::InvalidSetting);
+                    }
+                    parser.skip_whitespace(&read);
+                    let value = parser.number(&read).ok_or(RtttlError::InvalidSetting)?;
+                    match key.to_ascii_lowercase() {
+                        b'd' if valid_duration(value) => parser.duration = value as u8,
+                        b'o' if value <= 8 => parser.octave = value as u8,
+                        b'b' if (1..=900).contains(&value) => parser.bpm = value,
+                        _ => return Err(RtttlError::InvalidSetting),
+                    }
+                }
+            }
+        }
+
+        parser.start = parser.pos;
+        Ok(parser)
+    }
+
+    /// Tempo in quarter notes per minute
+    pub fn tempo(&self) -> u16 {
+        self.bpm
+    }
+
+    /// Go back to the first note
+    pub fn rewind(&mut self) {
+        self.pos = self.start;
+    }
+
+    /// Parse the next note, or return `None` at the end of the melody
+    ///
+    /// After an error the parser is at the end of the melody.
+    pub fn next_note(&mut self, read: impl Fn(usize) -> u8) -> Option<Result<Note, RtttlError>> {
+        self.skip_whitespace(&read);
+        self.peek(&read)?;
+        let note = self.parse_note(&read);
+        if note.is_err() {
+            self.pos = self.len;
+        }
+        Some(note)
+    }
+
+    fn parse_note(&mut self, read: &impl Fn(usize) -> u8) -> Result<Note, RtttlError> {
+        let duration = match self.number(read) {
+            Some(value) if valid_duration(value) => value as u8,
+            Some(_) => return Err(RtttlError::InvalidNote),
+            None => self.duration,
+        };
+
+        let semitone = match self.bump(read).map(|c| c.to_ascii_lowercase()) {
+            Some(b'c') => Some(0),
+            Some(b'd') => Some(2),
+            Some(b'e') => Some(4),
+            Some(b'f') => Some(5),
+            Some(b'g') => Some(7),
+            Some(b'a') => Some(9),
+            Some(b'b') | Some(b'h') => Some(11),
+            Some(b'p') => None,
+            _ => return Err(RtttlError::InvalidNote),
+        };
+        let sharp = self.eat(read, b'#');
+        let mut dotted = self.eat(read, b'.');
+        let octave = match self.peek(read) {
+            Some(digit @ b'0'..=b'8') => {
+                self.pos += 1;
+                digit - b'0'
+            }
+            _ => self.octave,
+        };
+        dotted |= self.eat(read, b'.');
+
+        self.skip_whitespace(read);
+        if !self.eat(read, b',') && self.peek(read).is_some() {
+            return Err(RtttlError::InvalidNote);
+        }
+
+        let mut length = Note::WHOLE / duration as u16;
+        if dotted {
+            length = Note::dotted(length);
+        }
+        let frequency = match semitone {
+            Some(semitone) => note_frequency((octave + 1) * 12 + semitone + sharp as u8),
+            None => 0,
+        };
+        Ok(Note::new(frequency, length))
+    }
+
+    fn peek(&self, read: &impl Fn(usize) -> u8) -> Option<u8> {
+        (self.pos < self.len).then(|| read(self.pos))
+    }
+
+    fn bump(&mut self, read: &impl Fn(usize) -> u8) -> Option<u8> {
+        let byte = self.peek(read)?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn eat(&mut self, read: &impl Fn(usize) -> u8, expected: u8) -> bool {
+        let found = self.peek(read) == Some(expected);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self, read: &impl Fn(usize) -> u8) {
+        while self.peek(read).is_some_and(|c| c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn number(&mut self, read: &impl Fn(usize) -> u8) -> Option<u16> {
+        let mut value: Option<u16> = None;
+        while let Some(digit @ b'0'..=b'9') = self.peek(read) {
+            self.pos += 1;
+            let digit = (digit - b'0') as u16;
+            value = Some(value.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+        }
+        value
+    }
+}
+
+fn valid_duration(value: u16) -> bool {
+    matches!(value, 1 | 2 | 4 | 8 | 16 | 32)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+    use super::*;
+
+    fn parse(text: &str) -> Result<(u16, Vec<Note>), RtttlError> {
+        let bytes = text.as_bytes();
+        let read = |i: usize| bytes[i];
+        let mut parser = RtttlParser::new(bytes.len(), read)?;
+        let mut notes = Vec::new();
+        while let Some(note) = parser.next_note(read) {
+            notes.push(note?);
+        }
+        Ok((parser.tempo(), notes))
+    }
+
+    #[test]
+    fn note_frequencies() {
+        assert_eq!(note_frequency(69), 440);
+        assert_eq!(note_frequency(60), 262);
+        assert_eq!(note_frequency(61), 277);
+        assert_eq!(note_frequency(81), 880);
+        assert_eq!(note_frequency(108), 4186);
+        assert_eq!(note_frequency(120), 8372);
+        assert_eq!(note_frequency(24), 33);
+    }
+
+    #[test]
+    fn note_durations() {
+        assert_eq!(Note::new(440, Note::QUARTER).duration_ms(120), 500);
+        assert_eq!(Note::new(440, Note::WHOLE).duration_ms(60), 4000);
+        assert_eq!(Note::rest(Note::dotted(Note::EIGHTH)).duration_ms(120), 375);
+        assert!(Note::rest(Note::HALF).is_rest());
+    }
+
+    #[test]
+    fn parses_settings_and_notes() {
+        let (tempo, notes) = parse("Beep:d=8,o=5,b=160:c,e,4g#,p,2c6.,16a.4").unwrap();
+        assert_eq!(tempo, 160);
+        assert_eq!(
+            notes,
+            [
+                Note::new(523, Note::EIGHTH),
+                Note::new(659, Note::EIGHTH),
+                Note::new(831, Note::QUARTER),
+                Note::rest(Note::EIGHTH),
+                Note::new(1047, Note::dotted(Note::HALF)),
+                Note::new(440, Note::dotted(Note::SIXTEENTH)),
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults_and_whitespace() {
+        let (tempo, notes) = parse("NoSettings::c, 8D#, b\n").unwrap();
+        assert_eq!(tempo, 63);
+        assert_eq!(
+            notes,
+            [
+                Note::new(1047, Note::QUARTER),
+                Note::new(1245, Note::EIGHTH),
+                Note::new(1976, Note::QUARTER),
+            ]
+        );
+
+        let (_, notes) = parse("Empty: d = 16 , o=4 :").unwrap();
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn rewind_restarts_melody() {
+        let text = b"x:d=4:c,d";
+        let read = |i: usize| text[i];
+        let mut parser = RtttlParser::new(text.len(), read).unwrap();
+        assert!(parser.next_note(read).is_some());
+        assert!(parser.next_note(read).is_some());
+        assert!(parser.next_note(read).is_none());
+        parser.rewind();
+        assert_eq!(parser.next_note(read), Some(Ok(Note::new(1047, Note::QUARTER))));
+    }
+
+    #[test]
+    fn rejects_malformed_melodies() {
+        assert_eq!(parse("no sections").unwrap_err(), RtttlError::MissingSection);
+        assert_eq!(parse("x:d=4").unwrap_err(), RtttlError::MissingSection);
+        assert_eq!(parse("x:d=3:c").unwrap_err(), RtttlError::InvalidSetting);
+        assert_eq!(parse("x:q=3:c").unwrap_err(), RtttlError::InvalidSetting);
+        assert_eq!(parse("x::x").unwrap_err(), RtttlError::InvalidNote);
+        assert_eq!(parse("x::c,3c").unwrap_err(), RtttlError::InvalidNote);
+        assert_eq!(parse("x::c9").unwrap_err(), RtttlError::InvalidNote);
+
+        // Iteration ends after an error
+        let text = b"x::z,c";
+        let read = |i: usize| text[i];
+        let mut parser = RtttlParser::new(text.len(), read).unwrap();
+        assert_eq!(parser.next_note(read), Some(Err(RtttlError::InvalidNote)));
+        assert_eq!(parser.next_note(read), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! Fixed-point PID controller
+//!
+//! [`Pid`] computes a control output from the error between a setpoint and a
+//! measurement, using integer arithmetic only. Gains are fixed-point numbers
+//! in units of 1/256 ([`GAIN_ONE`]), so a proportional gain of 1.5 is
+//! `3 * GAIN_ONE / 2 = 384`.
+//!
+//! The controller has no notion of time: call [`Pid::update`] at a fixed
+//! interval and tune the integral and derivative gains for that interval.
+//! The same controller works for speed control (measurement in encoder
+//! counts per interval) and position control (measurement in counts):
+//!
+//! ```ignore
+//! use ossidata_core::pid::{Pid, PidGains, GAIN_ONE};
+//!
+//! let mut pid = Pid::new(PidGains::new(2 * GAIN_ONE, GAIN_ONE / 4, 0), -255, 255);
+//! // Every 10 ms
+//! let speed = pid.update(target_counts, encoder.position());
+//! motor.set_speed(speed as i16);
+//! ```
+//!
+//! The integral term is clamped to the output limits so it cannot wind up
+//! while the output is saturated, and the derivative acts on the measurement
+//! rather than the error so setpoint changes do not cause output spikes.
+
+/// Fixed-point fraction bits of the gains
+pub const GAIN_SHIFT: u32 = 8;
+
+/// A gain of 1.0
+pub const GAIN_ONE: i32 = 1 << GAIN_SHIFT;
+
+/// Proportional, integral and derivative gains in units of 1/256
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PidGains {
+    /// Output per unit of error
+    pub kp: i32,
+    /// Output per unit of error accumulated each update
+    pub ki: i32,
+    /// Output per unit of measurement change between updates
+    pub kd: i32,
+}
+
+impl PidGains {
+    /// Create a set of gains in units of 1/256
+    pub const fn new(kp: i32, ki: i32, kd: i32) -> Self {
+        Self { kp, ki, kd }
+    }
+}
+
+/// PID controller with output limits and anti-windup
+#[derive(Debug, Clone, Copy)]
+pub struct Pid {
+    gains: PidGains,
+    min: i32,
+    max: i32,
+    /// Integral term in fixed-point output units
+    integral: i32,
+    last_measurement: Option<i32>,
+}
+
+impl Pid {
+    /// Create a controller whose output stays within `min..=max`
+    pub const fn new(gains: PidGains, min: i32, max: i32) -> Self {
+        Self { gains, min, max, integral: 0, last_measurement: None }
+    }
+
+    /// Current gains
+    pub fn gains(&self) -> PidGains {
+        self.gains
+    }
+
+    /// Change the gains, keeping the accumulated integral term
+    pub fn set_gains(&mut self, gains: PidGains) {
+        self.gains = gains;
+    }
+
+    /// Change the output limits
+    pub fn set_output_limits(&mut self, min: i32, max: i32) {
+        self.min = min;
+        self.max = max;
+        self.integral = self.clamp_integral(self.integral);
+    }
+
+    /// Clear the integral term and derivative history
+    ///
+    /// Call this after the loop was paused, e.g. when re-enabling a motor.
+    pub fn reset(&mut self) {
+        self.integral = 0;
+        self.last_measurement = None;
+    }
+
+    /// Compute the output for the current setpoint and measurement
+    pub fn update(&mut self, setpoint: i32, measurement: i32) -> i32 {
+        let error = setpoint.saturating_sub(measurement);
+
+        let integral = self.integral.saturating_add(self.gains.ki.saturating_mul(error));
+        self.integral = self.clamp_integral(integral);
+
+        let change = measurement.saturating_sub(self.last_measurement.unwrap_or(measurement));
+        self.last_measurement = Some(measurement);
+
+        let output = self
+            .gains
+            .kp
+            .saturating_mul(error)
+            .saturating_add(self.integral)
+            .saturating_sub(self.gains.kd.saturating_mul(change));
+        (output / GAIN_ONE).clamp(self.min, self.max)
+    }
+
+    fn clamp_integral(&self, integral: i32) -> i32 {
+        let min = self.min.saturating_mul(GAIN_ONE);
+        let max = self.max.saturating_mul(GAIN_ONE);
+        integral.clamp(min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proportional_only() {
+        let mut pid = Pid::new(PidGains::new(3 * GAIN_ONE / 2, 0, 0), -255, 255);
+        assert_eq!(pid.update(100, 0), 150);
+        assert_eq!(pid.update(0, 100), -150);
+        assert_eq!(pid.update(1000, 0), 255);
+        assert_eq!(pid.update(0, 1000), -255);
+    }
+
+    #[test]
+    fn integral_accumulates_and_does_not_wind_up() {
+        let mut pid = Pid::new(PidGains::new(0, GAIN_ONE / 2, 0), -100, 100);
+        assert_eq!(pid.update(10, 0), 5);
+        assert_eq!(pid.update(10, 0), 10);
+
+        // Saturate for a long time, then reverse: the output follows at once
+        for _ in 0..1000 {
+            assert_eq!(pid.update(1000, 0), 100);
+        }
+        assert_eq!(pid.update(-10, 0), 95);
+
+        pid.reset();
+        assert_eq!(pid.update(0, 0), 0);
+    }
+
+    #[test]
+    fn derivative_acts_on_measurement() {
+        let mut pid = Pid::new(PidGains::new(0, 0, 4 * GAIN_ONE), -255, 255);
+        // No kick on the first update or on setpoint changes
+        assert_eq!(pid.update(50, 10), 0);
+        assert_eq!(pid.update(80, 10), 0);
+        // Opposes the measurement moving
+        assert_eq!(pid.update(80, 15), -20);
+        assert_eq!(pid.update(80, 10), 20);
+    }
+
+    #[test]
+    fn narrowing_limits_clamps_integral() {
+        let mut pid = Pid::new(PidGains::new(0, GAIN_ONE, 0), -255, 255);
+        for _ in 0..10 {
+            pid.update(100, 0);
+        }
+        pid.set_output_limits(-50, 50);
+        assert_eq!(pid.update(0, 0), 50);
+        assert_eq!(pid.gains(), PidGains::new(0, GAIN_ONE, 0));
+    }
+}
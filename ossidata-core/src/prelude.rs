@@ -3,4 +3,5 @@
 //! This module re-exports commonly used types and traits.
 
 pub use crate::gpio::{Pin, mode};
+pub use crate::time::Clock;
 pub use embedded_hal::digital::{OutputPin, InputPin};
\ No newline at end of file
@@ -0,0 +1,147 @@
+//! Quadrature encoder decoding
+//!
+//! [`QuadratureDecoder`] turns the A/B signals of an incremental encoder into
+//! a signed position. It counts every edge of both channels (4x decoding), so
+//! an encoder with 12 lines per revolution yields 48 counts per revolution.
+//!
+//! The decoder does not touch any hardware. Feed it the level of both
+//! channels whenever either of them changes, typically from a pin change
+//! interrupt:
+//!
+//! ```ignore
+//! use ossidata_core::quadrature::QuadratureDecoder;
+//!
+//! let mut decoder = QuadratureDecoder::new(read_a(), read_b());
+//! // On every edge of A or B
+//! decoder.update(read_a(), read_b());
+//! let position = decoder.position();
+//! ```
+
+/// Position change for each `(previous << 2) | current` state pair
+///
+/// A state is `(A << 1) | B`. A leading B (00 → 10 → 11 → 01) counts up.
+/// Transitions where both channels changed at once are ambiguous and count
+/// as zero here; they are reported through [`QuadratureDecoder::errors`].
+const TRANSITIONS: [i8; 16] = [
+    //  to 00  01  10  11
+    0, -1, 1, 0, // from 00
+    1, 0, 0, -1, // from 01
+    -1, 0, 0, 1, // from 10
+    0, 1, -1, 0, // from 11
+];
+
+/// 4x quadrature decoder with position tracking
+#[derive(Debug, Clone, Copy)]
+pub struct QuadratureDecoder {
+    state: u8,
+    position: i32,
+    errors: u16,
+}
+
+impl QuadratureDecoder {
+    /// Create a decoder at position 0 from the current channel levels
+    pub const fn new(a: bool, b: bool) -> Self {
+        Self { state: Self::encode(a, b), position: 0, errors: 0 }
+    }
+
+    const fn encode(a: bool, b: bool) -> u8 {
+        ((a as u8) << 1) | b as u8
+    }
+
+    /// Process the current channel levels, returning the position change
+    ///
+    /// Returns 0 if nothing changed or if both channels changed at once,
+    /// which means an edge was missed.
+    pub fn update(&mut self, a: bool, b: bool) -> i8 {
+        let state = Self::encode(a, b);
+        if state ^ self.state == 0b11 {
+            self.errors = self.errors.saturating_add(1);
+        }
+        let delta = TRANSITIONS[((self.state << 2) | state) as usize];
+        self.state = state;
+        self.position = self.position.wrapping_add(delta as i32);
+        delta
+    }
+
+    /// Current position in counts
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Overwrite the current position
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
+    /// Number of missed edges since creation or the last [`Self::clear_errors`]
+    ///
+    /// A non-zero count means the signals change faster than they are
+    /// sampled, so the position is no longer exact.
+    pub fn errors(&self) -> u16 {
+        self.errors
+    }
+
+    /// Reset the missed edge count
+    pub fn clear_errors(&mut self) {
+        self.errors = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Channel levels for one full cycle with A leading B
+    const FORWARD: [(bool, bool); 4] = [(true, false), (true, true), (false, true), (false, false)];
+
+    /// Channel levels for one full cycle with B leading A
+    const BACKWARD: [(bool, bool); 4] = [(false, true), (true, true), (true, false), (false, false)];
+
+    #[test]
+    fn counts_every_edge_forward() {
+        let mut decoder = QuadratureDecoder::new(false, false);
+        for _ in 0..3 {
+            for &(a, b) in &FORWARD {
+                assert_eq!(decoder.update(a, b), 1);
+            }
+        }
+        assert_eq!(decoder.position(), 12);
+        assert_eq!(decoder.errors(), 0);
+    }
+
+    #[test]
+    fn counts_backward_and_reverses_mid_cycle() {
+        let mut decoder = QuadratureDecoder::new(false, false);
+        for &(a, b) in &BACKWARD {
+            assert_eq!(decoder.update(a, b), -1);
+        }
+        assert_eq!(decoder.position(), -4);
+
+        // Jitter on one channel around an edge cancels out
+        decoder.update(true, false);
+        decoder.update(false, false);
+        decoder.update(true, false);
+        assert_eq!(decoder.position(), -3);
+    }
+
+    #[test]
+    fn repeated_state_is_ignored() {
+        let mut decoder = QuadratureDecoder::new(true, true);
+        assert_eq!(decoder.update(true, true), 0);
+        assert_eq!(decoder.position(), 0);
+        assert_eq!(decoder.errors(), 0);
+    }
+
+    #[test]
+    fn reports_missed_edges() {
+        let mut decoder = QuadratureDecoder::new(false, false);
+        assert_eq!(decoder.update(true, true), 0);
+        assert_eq!(decoder.errors(), 1);
+        decoder.clear_errors();
+        assert_eq!(decoder.errors(), 0);
+
+        decoder.set_position(100);
+        assert_eq!(decoder.update(false, true), 1);
+        assert_eq!(decoder.position(), 101);
+    }
+}
@@ -0,0 +1,549 @@
+//! Stepper motor speed ramps
+//!
+//! [`StepperRamp`] plans the step timing of a stepper motor with a
+//! trapezoidal speed profile: it accelerates from rest, cruises at the
+//! maximum speed and decelerates to stop exactly on the target position.
+//! It follows the algorithm of the AccelStepper library (David Austin,
+//! "Generate stepper-motor speed profiles in real time"), but uses integer
+//! arithmetic only so it is cheap enough to run in a timer interrupt: a
+//! step costs at most one 32-bit division while the speed changes and none
+//! while cruising.
+//!
+//! The ramp does not touch any hardware. The caller takes a step whenever
+//! the interval from [`StepperRamp::step_interval_us`] has elapsed:
+//!
+//! ```ignore
+//! use ossidata_core::stepper::StepperRamp;
+//!
+//! let mut ramp = StepperRamp::new(1000, 500);
+//! ramp.move_to(2000);
+//! while let Some(direction) = ramp.next_step() {
+//!     pulse(direction);
+//!     wait_us(ramp.step_interval_us());
+//! }
+//! ```
+
+/// Fixed-point fraction bits of step intervals (1/256 us)
+const INTERVAL_SHIFT: u32 = 8;
+
+/// One second in fixed-point interval units
+const SECOND: u32 = 1_000_000 << INTERVAL_SHIFT;
+
+/// Highest supported step rate in steps per second
+///
+/// Keeps the squared speed within `u32` for the stopping distance.
+pub const MAX_STEP_RATE: u32 = 20_000;
+
+/// Direction of a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards higher positions
+    Forward,
+    /// Towards lower positions
+    Backward,
+}
+
+impl Direction {
+    const fn delta(self) -> i32 {
+        match self {
+            Direction::Forward => 1,
+            Direction::Backward => -1,
+        }
+    }
+}
+
+/// Trapezoidal speed profile and position tracking for one stepper motor
+#[derive(Debug, Clone, Copy)]
+pub struct StepperRamp {
+    position: i32,
+    target: i32,
+    max_speed: u32,
+    acceleration: u32,
+    direction: Direction,
+    // Step counter of the current ramp, which is also the number of steps
+    // needed to stop: positive while accelerating (held while cruising),
+    // negative while decelerating
+    n: i32,
+    // Step intervals in 1/256 us: first step, current, at maximum speed
+    c0: u32,
+    cn: u32,
+    cmin: u32,
+    running: bool,
+    // Constant speed mode started by `run_speed`
+    constant: bool,
+}
+
+impl StepperRamp {
+    /// Create a ramp at position 0
+    ///
+    /// `max_speed` is in steps per second (up to [`MAX_STEP_RATE`]) and
+    /// `acceleration` in steps per second².
+    pub fn new(max_speed: u32, acceleration: u32) -> Self {
+        let mut ramp = StepperRamp {
+            position: 0,
+            target: 0,
+            max_speed: 0,
+            acceleration: 0,
+            direction: Direction::Forward,
+            n: 0,
+            c0: 0,
+            cn: 0,
+            cmin: 0,
+            running: false,
+            constant: false,
+        };
+        ramp.set_max_speed(max_speed);
+        ramp.set_acceleration(acceleration);
+        ramp
+    }
+
+    /// Current position in steps
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Target position in steps
+    pub fn target(&self) -> i32 {
+        self.target
+    }
+
+    /// Steps left to the target position
+    pub fn distance_to_go(&self) -> i32 {
+        self.target.wrapping_sub(self.position)
+    }
+
+    /// Check if the motor is moving (a step is pending)
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Direction of the current or last motion
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Current speed in steps per second, negative when moving backward
+    pub fn speed(&self) -> i32 {
+        if !self.running {
+            return 0;
+        }
+        (SECOND / self.cn) as i32 * self.direction.delta()
+    }
+
+    /// Maximum speed in steps per second
+    pub fn max_speed(&self) -> u32 {
+        self.max_speed
+    }
+
+    /// Acceleration in steps per second²
+    pub fn acceleration(&self) -> u32 {
+        self.acceleration
+    }
+
+    /// Interval until the next step in microseconds (0 when stopped)
+    pub fn step_interval_us(&self) -> u32 {
+        if !self.running {
+            return 0;
+        }
+        (self.cn + (1 << (INTERVAL_SHIFT - 1))) >> INTERVAL_SHIFT
+    }
+
+    /// Set the maximum speed in steps per second
+    ///
+    /// Clamped to 1..=[`MAX_STEP_RATE`]. Takes effect on the next step.
+    pub fn set_max_speed(&mut self, max_speed: u32) {
+        let max_speed = max_speed.clamp(1, MAX_STEP_RATE);
+        if max_speed == self.max_speed {
+            return;
+        }
+        self.max_speed = max_speed;
+        self.cmin = SECOND / max_speed;
+
+        // Recalculate the ramp position from the current speed, slowing down
+        // right away if it is above the new maximum
+        if self.n > 0 && self.running && !self.constant {
+            self.cn = self.cn.max(self.cmin);
+            self.n = self.steps_to_stop_at_speed() as i32;
+        }
+    }
+
+    /// Set the acceleration in steps per second²
+    ///
+    /// Clamped to at least 1. Takes effect on the next step.
+    pub fn set_acceleration(&mut self, acceleration: u32) {
+        let acceleration = acceleration.max(1);
+        if acceleration == self.acceleration {
+            return;
+        }
+
+        // Keep the current speed on the new ramp
+        if self.acceleration != 0 {
+            self.n = (self.n as i64 * self.acceleration as i64 / acceleration as i64) as i32;
+        }
+
+        // First step interval: 0.676 * sqrt(2 / a) seconds (equation 15 with
+        // Austin's correction for the first step)
+        let c0_us = isqrt(2_000_000_000_000 / acceleration as u64) as u64 * 676 / 1000;
+        self.c0 = (c0_us << INTERVAL_SHIFT).min(i32::MAX as u64 / 2) as u32;
+        self.acceleration = acceleration;
+    }
+
+    /// Move to an absolute position, accelerating and decelerating as needed
+    pub fn move_to(&mut self, target: i32) {
+        if self.constant {
+            // Continue from the current speed as if it had been ramped up to
+            self.constant = false;
+            self.n = self.steps_to_stop_at_speed() as i32;
+        }
+        self.target = target;
+
+        // A moving ramp picks up the new target on its next step
+        if !self.running {
+            self.compute_new_speed();
+        }
+    }
+
+    /// Move relative to the current position
+    pub fn move_by(&mut self, steps: i32) {
+        self.move_to(self.position.wrapping_add(steps));
+    }
+
+    /// Run continuously at a constant speed in steps per second
+    ///
+    /// The sign selects the direction; the speed is limited to the maximum
+    /// speed and there is no acceleration. Use [`stop`](Self::stop) to
+    /// decelerate to a halt, or a speed of 0 to stop immediately.
+    pub fn run_speed(&mut self, speed: i32) {
+        if speed == 0 {
+            self.halt();
+            self.target = self.position;
+            return;
+        }
+
+        let rate = speed.unsigned_abs().min(self.max_speed);
+        self.direction = if speed > 0 { Direction::Forward } else { Direction::Backward };
+        self.cn = SECOND / rate;
+        self.n = 0;
+        self.constant = true;
+        self.running = true;
+    }
+
+    /// Decelerate to a stop as quickly as the acceleration allows
+    ///
+    /// The target is moved to the position where the motor will come to rest.
+    pub fn stop(&mut self) {
+        if !self.running {
+            return;
+        }
+
+        if self.constant {
+            self.constant = false;
+            self.n = self.steps_to_stop_at_speed() as i32;
+        }
+        let steps = self.steps_to_stop() as i32;
+        self.target = self.position.wrapping_add(steps * self.direction.delta());
+    }
+
+    /// Stop immediately without deceleration
+    pub fn halt(&mut self) {
+        self.running = false;
+        self.constant = false;
+        self.n = 0;
+        self.cn = 0;
+    }
+
+    /// Set the current position, stopping any motion
+    pub fn set_position(&mut self, position: i32) {
+        self.halt();
+        self.position = position;
+        self.target = position;
+    }
+
+    /// Take the pending step
+    ///
+    /// Call this when the previous step interval has elapsed (right away for
+    /// the first step after a move starts). Updates the position and returns
+    /// the direction to step in, or `None` if the motor is stopped. The time
+    /// to wait before the next call is then given by
+    /// [`step_interval_us`](Self::step_interval_us).
+    pub fn next_step(&mut self) -> Option<Direction> {
+        if !self.running {
+            return None;
+        }
+
+        let direction = self.direction;
+        self.position = self.position.wrapping_add(direction.delta());
+        if !self.constant {
+            self.compute_new_speed();
+        }
+        Some(direction)
+    }
+
+    /// Steps needed to stop, tracked by the ramp counter
+    fn steps_to_stop(&self) -> u32 {
+        if !self.running {
+            return 0;
+        }
+        self.n.unsigned_abs()
+    }
+
+    /// Steps needed to stop from the current speed: v² / 2a
+    ///
+    /// Only used where the speed did not come from the ramp (constant speed
+    /// mode, a new maximum speed), as it costs two divisions.
+    fn steps_to_stop_at_speed(&self) -> u32 {
+        if !self.running || self.cn == 0 {
+            return 0;
+        }
+        let speed = SECOND / self.cn;
+        speed * speed / (2 * self.acceleration)
+    }
+
+    /// Work out the interval to the next step (AccelStepper `computeNewSpeed`)
+    fn compute_new_speed(&mut self) {
+        let distance = self.distance_to_go();
+        let steps_to_stop = self.steps_to_stop();
+
+        if distance == 0 && steps_to_stop <= 1 {
+            // On target and slow enough to stop here
+            self.halt();
+            return;
+        }
+
+        if distance != 0 {
+            let towards = if distance > 0 { Direction::Forward } else { Direction::Backward };
+            let remaining = distance.unsigned_abs();
+
+            if self.n > 0 {
+                // Start decelerating when the target is within the stopping
+                // distance or behind us
+                if steps_to_stop >= remaining || self.direction != towards {
+                    self.n = -(steps_to_stop as i32);
+                }
+            } else if self.n < 0 && steps_to_stop < remaining && self.direction == towards {
+                // Target moved further away: accelerate again
+                self.n = -self.n;
+            }
+        }
+
+        if self.n == 0 || !self.running {
+            // Start from rest (or reverse after decelerating to zero)
+            self.cn = self.c0;
+            self.n = 0;
+            self.direction = if distance > 0 { Direction::Forward } else { Direction::Backward };
+        } else if self.n > 0 && self.cn <= self.cmin {
+            // Cruising: hold the counter at the stopping distance
+            return;
+        } else {
+            // Equation 13: cn = cn-1 - 2 cn-1 / (4n + 1). A negative n
+            // lengthens the interval while decelerating.
+            let delta = (2 * self.cn as i32) / (4 * self.n + 1);
+            let cn = (self.cn as i32 - delta) as u32;
+            if self.n > 0 && cn <= self.cmin {
+                // Reached the maximum speed after n steps
+                self.cn = self.cmin;
+                return;
+            }
+            self.cn = cn;
+        }
+
+        self.n += 1;
+        self.running = true;
+    }
+}
+
+/// Integer square root (floor)
+fn isqrt(value: u64) -> u32 {
+    if value < 2 {
+        return value as u32;
+    }
+
+    // Newton's method from an initial guess above the root
+    let mut x = 1u64 << ((64 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x as u32;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec::Vec;
+    use super::*;
+
+    /// Run a ramp to completion, returning the step intervals in microseconds
+    fn run(ramp: &mut StepperRamp, limit: usize) -> Vec<u32> {
+        let mut intervals = Vec::new();
+        while ramp.next_step().is_some() {
+            intervals.push(ramp.step_interval_us());
+            assert!(intervals.len() <= limit, "ramp did not stop");
+        }
+        intervals
+    }
+
+    #[test]
+    fn isqrt_floors() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(1), 1);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(2_000_000_000_000), 1_414_213);
+        assert_eq!(isqrt(u64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn first_interval_follows_acceleration() {
+        let mut ramp = StepperRamp::new(1000, 1000);
+        ramp.move_to(100);
+        assert!(ramp.is_running());
+        // 0.676 * sqrt(2 / 1000) s = 30.23 ms
+        assert_eq!(ramp.step_interval_us(), 30_231);
+    }
+
+    #[test]
+    fn reaches_target_exactly() {
+        let mut ramp = StepperRamp::new(800, 400);
+        ramp.move_to(1000);
+        let intervals = run(&mut ramp, 2000);
+
+        assert_eq!(ramp.position(), 1000);
+        assert_eq!(ramp.distance_to_go(), 0);
+        assert!(!ramp.is_running());
+        assert_eq!(ramp.speed(), 0);
+        // One interval per step, the last one ends the move
+        assert_eq!(intervals.len(), 1000);
+    }
+
+    #[test]
+    fn trapezoid_accelerates_cruises_and_decelerates() {
+        let mut ramp = StepperRamp::new(1000, 500);
+        ramp.move_to(5000);
+        let intervals = run(&mut ramp, 10_000);
+
+        // Never faster than the maximum speed (1 ms per step)
+        let fastest = *intervals.iter().filter(|&&i| i > 0).min().unwrap();
+        assert_eq!(fastest, 1000);
+
+        // Accelerating: intervals shrink; decelerating: they grow again
+        assert!(intervals[0] > intervals[10]);
+        assert!(intervals[10] > intervals[100]);
+        let cruise = intervals.iter().filter(|&&i| i == 1000).count();
+        assert!(cruise > 2900, "cruised for {cruise} steps");
+
+        // v² / 2a = 1000 steps to reach full speed, and the same to stop
+        let accelerating = intervals.iter().position(|&i| i == 1000).unwrap();
+        assert!((950..=1050).contains(&accelerating), "{accelerating}");
+        let decelerating = intervals.len() - 1 - intervals.iter().rposition(|&i| i == 1000).unwrap();
+        assert!((950..=1050).contains(&decelerating), "{decelerating}");
+    }
+
+    #[test]
+    fn moves_backward() {
+        let mut ramp = StepperRamp::new(500, 1000);
+        ramp.move_by(-300);
+        assert_eq!(ramp.direction(), Direction::Backward);
+        assert_eq!(ramp.next_step(), Some(Direction::Backward));
+        assert!(ramp.speed() < 0);
+        run(&mut ramp, 1000);
+        assert_eq!(ramp.position(), -300);
+    }
+
+    #[test]
+    fn reversing_decelerates_first() {
+        let mut ramp = StepperRamp::new(1000, 1000);
+        ramp.move_to(10_000);
+        for _ in 0..600 {
+            ramp.next_step();
+        }
+        let speed = ramp.speed();
+        assert!(speed > 900);
+
+        // The motor overshoots by the stopping distance before turning back
+        ramp.move_to(0);
+        let mut furthest = ramp.position();
+        while ramp.next_step().is_some() {
+            furthest = furthest.max(ramp.position());
+        }
+        assert!(furthest > 600 + 400, "turned at {furthest}");
+        assert_eq!(ramp.position(), 0);
+    }
+
+    #[test]
+    fn stop_decelerates_within_stopping_distance() {
+        let mut ramp = StepperRamp::new(2000, 1000);
+        ramp.move_to(100_000);
+        for _ in 0..3000 {
+            ramp.next_step();
+        }
+        assert_eq!(ramp.speed(), 2000);
+
+        // v² / 2a = 2000 steps, give or take the error of the ramp
+        ramp.stop();
+        let stopped_at = ramp.target();
+        assert!((4950..=5050).contains(&stopped_at), "{stopped_at}");
+        run(&mut ramp, 5000);
+        assert_eq!(ramp.position(), stopped_at);
+    }
+
+    #[test]
+    fn run_speed_is_constant_until_stopped() {
+        let mut ramp = StepperRamp::new(1000, 2000);
+        ramp.run_speed(-250);
+        for _ in 0..100 {
+            assert_eq!(ramp.next_step(), Some(Direction::Backward));
+            assert_eq!(ramp.step_interval_us(), 4000);
+        }
+        assert_eq!(ramp.position(), -100);
+        assert_eq!(ramp.speed(), -250);
+
+        // Limited to the maximum speed
+        ramp.run_speed(5000);
+        assert_eq!(ramp.speed(), 1000);
+
+        // 1000² / (2 * 2000) = 250 steps to stop
+        ramp.stop();
+        assert_eq!(ramp.target(), -100 + 250);
+        run(&mut ramp, 1000);
+        assert_eq!(ramp.position(), 150);
+        assert!(!ramp.is_running());
+
+        ramp.run_speed(100);
+        ramp.run_speed(0);
+        assert!(!ramp.is_running());
+        assert_eq!(ramp.next_step(), None);
+    }
+
+    #[test]
+    fn lowering_max_speed_while_cruising() {
+        let mut ramp = StepperRamp::new(2000, 1000);
+        ramp.move_to(100_000);
+        for _ in 0..3000 {
+            ramp.next_step();
+        }
+        assert_eq!(ramp.speed(), 2000);
+
+        // 1000² / (2 * 1000) = 500 steps to stop from the new speed
+        ramp.set_max_speed(1000);
+        assert_eq!(ramp.speed(), 1000);
+        ramp.stop();
+        assert_eq!(ramp.target(), 3000 + 500);
+        let intervals = run(&mut ramp, 1000);
+        assert!(intervals.iter().all(|&i| i == 0 || i >= 1000));
+        assert_eq!(ramp.position(), 3500);
+    }
+
+    #[test]
+    fn set_position_stops() {
+        let mut ramp = StepperRamp::new(1000, 1000);
+        ramp.move_to(50);
+        ramp.next_step();
+        ramp.set_position(-7);
+        assert!(!ramp.is_running());
+        assert_eq!(ramp.position(), -7);
+        assert_eq!(ramp.distance_to_go(), 0);
+    }
+}
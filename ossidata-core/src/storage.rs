@@ -0,0 +1,722 @@
+//! Persistent storage abstractions
+//!
+//! [`Storage`] is implemented by board crates for their non-volatile memory
+//! (e.g. the AVR EEPROM). [`KvStore`] builds a small key-value store on top of
+//! any `Storage`.
+//!
+//! # Key-value store layout
+//!
+//! The store's region is split into two equal banks. Each bank starts with a
+//! header (magic byte, 16-bit sequence number, CRC) followed by an append-only
+//! log of records:
+//!
+//! ```text
+//! [key: u8][len: u8][data: len bytes][crc16: 2 bytes]
+//! ```
+//!
+//! Setting a key appends a new record instead of overwriting the old one, so
+//! writes are spread over the whole bank. When the active bank is full, the
+//! latest value of every key is copied to the other bank, and that bank's header
+//! is written last with the next sequence number. The bank with the newest valid
+//! header is the active one.
+//!
+//! A power loss at any point leaves either the old or the new state:
+//! - A torn record fails its CRC and is ignored, so the key keeps its previous value
+//! - A torn compaction never writes the new header, so the old bank stays active
+
+/// Byte-addressable non-volatile memory
+pub trait Storage {
+    /// Error returned by the backend
+    type Error;
+
+    /// Size of the storage in bytes
+    fn capacity(&self) -> u16;
+
+    /// Read `buffer.len()` bytes starting at `address`
+    fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Write `data` starting at `address`
+    ///
+    /// Implementations should skip bytes that already hold the right value,
+    /// as `KvStore` relies on this to avoid needless wear.
+    fn write(&mut self, address: u16, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Error for accesses outside of a storage backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange;
+
+/// In-memory storage, e.g. for testing on the host
+impl<const N: usize> Storage for [u8; N] {
+    type Error = OutOfRange;
+
+    fn capacity(&self) -> u16 {
+        N as u16
+    }
+
+    fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), OutOfRange> {
+        let start = address as usize;
+        let source = self.get(start..start + buffer.len()).ok_or(OutOfRange)?;
+        buffer.copy_from_slice(source);
+        Ok(())
+    }
+
+    fn write(&mut self, address: u16, data: &[u8]) -> Result<(), OutOfRange> {
+        let start = address as usize;
+        let target = self.get_mut(start..start + data.len()).ok_or(OutOfRange)?;
+        target.copy_from_slice(data);
+        Ok(())
+    }
+}
+
+/// CRC-16/CCITT-FALSE (polynomial 0x1021)
+///
+/// Pass `0xFFFF` as `crc` for a new checksum, or a previous result to continue it.
+pub fn crc16(crc: u16, data: &[u8]) -> u16 {
+    let mut crc = crc;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Errors from [`KvStore`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KvError<E> {
+    /// The storage backend failed
+    Storage(E),
+    /// The region is too small, odd-sized or outside the storage
+    InvalidRegion,
+    /// Key 0xFF is reserved
+    InvalidKey,
+    /// The value is longer than `MAX_VALUE_LEN` or the caller's buffer
+    TooLarge,
+    /// A typed read found a value of a different size
+    SizeMismatch,
+    /// The live data does not fit in a bank even after compaction
+    Full,
+}
+
+impl<E> From<E> for KvError<E> {
+    fn from(error: E) -> Self {
+        KvError::Storage(error)
+    }
+}
+
+/// Fixed-size values that can be stored with [`KvStore::get`] and [`KvStore::set`]
+///
+/// Implemented for the integer types, `f32`, `f64`, `bool` and byte arrays.
+/// Integers are stored little-endian.
+pub trait KvValue: Sized {
+    /// Serialized representation
+    type Bytes: AsRef<[u8]> + AsMut<[u8]>;
+
+    /// A zeroed `Bytes`, used as the read buffer
+    const ZEROED: Self::Bytes;
+
+    /// Serialize the value
+    fn to_bytes(&self) -> Self::Bytes;
+
+    /// Deserialize a value
+    fn from_bytes(bytes: Self::Bytes) -> Self;
+}
+
+macro_rules! impl_kv_value {
+    ($($ty:ty),*) => {$(
+        impl KvValue for $ty {
+            type Bytes = [u8; core::mem::size_of::<$ty>()];
+            const ZEROED: Self::Bytes = [0; core::mem::size_of::<$ty>()];
+
+            fn to_bytes(&self) -> Self::Bytes {
+                self.to_le_bytes()
+            }
+
+            fn from_bytes(bytes: Self::Bytes) -> Self {
+                <$ty>::from_le_bytes(bytes)
+            }
+        }
+    )*};
+}
+
+impl_kv_value!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl KvValue for bool {
+    type Bytes = [u8; 1];
+    const ZEROED: [u8; 1] = [0];
+
+    fn to_bytes(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+
+    fn from_bytes(bytes: [u8; 1]) -> Self {
+        bytes[0] != 0
+    }
+}
+
+impl<const N: usize> KvValue for [u8; N] {
+    type Bytes = [u8; N];
+    const ZEROED: [u8; N] = [0; N];
+
+    fn to_bytes(&self) -> [u8; N] {
+        *self
+    }
+
+    fn from_bytes(bytes: [u8; N]) -> Self {
+        bytes
+    }
+}
+
+// Bank header: magic, sequence (LE), crc16 (LE)
+const BANK_MAGIC: u8 = 0x4B;
+const HEADER_LEN: u16 = 5;
+
+// Record framing: key + len before the data, crc16 after it
+const RECORD_OVERHEAD: u16 = 4;
+
+// Erased EEPROM reads as 0xFF, which marks free space
+const ERASED: u8 = 0xFF;
+
+// A record with this length deletes its key
+const TOMBSTONE: u8 = 0xFF;
+
+// Chunk size for copying and erasing (keeps stack usage small)
+const CHUNK: usize = 16;
+
+/// Smallest region accepted by [`KvStore::mount`]
+pub const MIN_REGION_LEN: u16 = 2 * 32;
+
+/// Longest value that can be stored under one key
+pub const MAX_VALUE_LEN: usize = 254;
+
+/// A record found while scanning a bank
+#[derive(Clone, Copy)]
+struct Record {
+    offset: u16,
+    key: u8,
+    len: u8,
+    valid: bool,
+}
+
+impl Record {
+    fn data_len(&self) -> u16 {
+        if self.len == TOMBSTONE { 0 } else { self.len as u16 }
+    }
+
+    fn size(&self) -> u16 {
+        RECORD_OVERHEAD + self.data_len()
+    }
+
+    fn is_tombstone(&self) -> bool {
+        self.len == TOMBSTONE
+    }
+}
+
+/// Wear-leveled, power-loss-safe key-value store
+///
+/// Keys are `u8` (0x00-0xFE), values are up to [`MAX_VALUE_LEN`] bytes. See the
+/// [module documentation](self) for the on-storage format.
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::storage::KvStore;
+///
+/// const BOOT_COUNT: u8 = 0;
+///
+/// // Use the first 512 bytes of the EEPROM
+/// let mut store = KvStore::mount(eeprom, 0, 512)?;
+///
+/// let boots: u32 = store.get(BOOT_COUNT)?.unwrap_or(0);
+/// store.set(BOOT_COUNT, &(boots + 1))?;
+/// ```
+pub struct KvStore<S: Storage> {
+    storage: S,
+    start: u16,
+    bank_len: u16,
+    active: u8,
+    sequence: u16,
+    // Offset of the first free byte in the active bank
+    write_offset: u16,
+}
+
+impl<S: Storage> KvStore<S> {
+    /// Open the store in `len` bytes of `storage` starting at `start`
+    ///
+    /// An unformatted region (no valid bank header) is formatted, so the first
+    /// mount starts with an empty store.
+    pub fn mount(storage: S, start: u16, len: u16) -> Result<Self, KvError<S::Error>> {
+        if len < MIN_REGION_LEN || len % 2 != 0 || start as u32 + len as u32 > storage.capacity() as u32 {
+            return Err(KvError::InvalidRegion);
+        }
+
+        let mut store = Self {
+            storage,
+            start,
+            bank_len: len / 2,
+            active: 0,
+            sequence: 0,
+            write_offset: HEADER_LEN,
+        };
+
+        match (store.read_header(0)?, store.read_header(1)?) {
+            (Some(a), Some(b)) => {
+                // The newer header wins (sequence numbers wrap)
+                if b.wrapping_sub(a) < 0x8000 {
+                    store.active = 1;
+                    store.sequence = b;
+                } else {
+                    store.sequence = a;
+                }
+            }
+            (Some(a), None) => store.sequence = a,
+            (None, Some(b)) => {
+                store.active = 1;
+                store.sequence = b;
+            }
+            (None, None) => {
+                store.erase_bank(0)?;
+                store.write_header(0, 0)?;
+            }
+        }
+
+        store.write_offset = store.find_end()?;
+        Ok(store)
+    }
+
+    /// Erase all keys
+    pub fn format(&mut self) -> Result<(), KvError<S::Error>> {
+        let target = 1 - self.active;
+        self.erase_bank(target)?;
+        self.switch_to(target, HEADER_LEN)
+    }
+
+    /// Release the storage backend
+    pub fn release(self) -> S {
+        self.storage
+    }
+
+    /// Read the raw value of `key` into `buffer`
+    ///
+    /// Returns the value length, or `None` if the key is not set.
+    pub fn get_bytes(&mut self, key: u8, buffer: &mut [u8]) -> Result<Option<usize>, KvError<S::Error>> {
+        let record = match self.find_latest(key)? {
+            Some(record) if !record.is_tombstone() => record,
+            _ => return Ok(None),
+        };
+
+        let len = record.len as usize;
+        if len > buffer.len() {
+            return Err(KvError::TooLarge);
+        }
+
+        let address = self.bank_address(self.active) + record.offset + 2;
+        self.storage.read(address, &mut buffer[..len])?;
+        Ok(Some(len))
+    }
+
+    /// Store a raw value under `key`
+    ///
+    /// Nothing is written if the key already holds exactly this value.
+    pub fn set_bytes(&mut self, key: u8, value: &[u8]) -> Result<(), KvError<S::Error>> {
+        if key == ERASED {
+            return Err(KvError::InvalidKey);
+        }
+        if value.len() > MAX_VALUE_LEN {
+            return Err(KvError::TooLarge);
+        }
+
+        if let Some(record) = self.find_latest(key)? {
+            if !record.is_tombstone() && record.len as usize == value.len() && self.data_equals(record, value)? {
+                return Ok(());
+            }
+        }
+
+        self.append(key, value.len() as u8, value)
+    }
+
+    /// Read a typed value
+    pub fn get<T: KvValue>(&mut self, key: u8) -> Result<Option<T>, KvError<S::Error>> {
+        let mut bytes = T::ZEROED;
+        let expected = bytes.as_ref().len();
+
+        match self.get_bytes(key, bytes.as_mut()) {
+            Ok(Some(len)) if len == expected => Ok(Some(T::from_bytes(bytes))),
+            Ok(Some(_)) | Err(KvError::TooLarge) => Err(KvError::SizeMismatch),
+            Ok(None) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Store a typed value
+    pub fn set<T: KvValue>(&mut self, key: u8, value: &T) -> Result<(), KvError<S::Error>> {
+        self.set_bytes(key, value.to_bytes().as_ref())
+    }
+
+    /// Check whether `key` has a value
+    pub fn contains(&mut self, key: u8) -> Result<bool, KvError<S::Error>> {
+        Ok(matches!(self.find_latest(key)?, Some(record) if !record.is_tombstone()))
+    }
+
+    /// Delete `key`
+    pub fn remove(&mut self, key: u8) -> Result<(), KvError<S::Error>> {
+        if self.contains(key)? {
+            self.append(key, TOMBSTONE, &[])?;
+        }
+        Ok(())
+    }
+
+    /// Bytes left in the active bank before the next compaction
+    pub fn free_space(&self) -> u16 {
+        self.bank_len - self.write_offset
+    }
+
+    /// Copy the live values to the other bank, reclaiming space
+    ///
+    /// This happens automatically when a write does not fit.
+    pub fn compact(&mut self) -> Result<(), KvError<S::Error>> {
+        let source = self.active;
+        let target = 1 - source;
+
+        // Clearing the target also invalidates its old header, so a
+        // half-finished compaction is never mistaken for a valid bank
+        self.erase_bank(target)?;
+
+        let mut target_offset = HEADER_LEN;
+        let mut offset = HEADER_LEN;
+        while let Some(record) = self.record_at(source, offset)? {
+            offset += record.size();
+
+            if !record.valid || record.is_tombstone() || self.superseded(record)? {
+                continue;
+            }
+
+            self.copy_record(record, target, target_offset)?;
+            target_offset += record.size();
+        }
+
+        self.switch_to(target, target_offset)
+    }
+
+    fn append(&mut self, key: u8, len: u8, data: &[u8]) -> Result<(), KvError<S::Error>> {
+        let size = RECORD_OVERHEAD + data.len() as u16;
+
+        if self.write_offset + size > self.bank_len {
+            self.compact()?;
+            if self.write_offset + size > self.bank_len {
+                return Err(KvError::Full);
+            }
+        }
+
+        let crc = crc16(crc16(0xFFFF, &[key, len]), data);
+        let address = self.bank_address(self.active) + self.write_offset;
+
+        // Written in order, so a torn record always fails its CRC
+        self.storage.write(address, &[key, len])?;
+        self.storage.write(address + 2, data)?;
+        self.storage.write(address + 2 + data.len() as u16, &crc.to_le_bytes())?;
+
+        self.write_offset += size;
+        Ok(())
+    }
+
+    fn switch_to(&mut self, bank: u8, write_offset: u16) -> Result<(), KvError<S::Error>> {
+        let sequence = self.sequence.wrapping_add(1);
+
+        // Commit point: from here on the new bank is the active one
+        self.write_header(bank, sequence)?;
+
+        self.active = bank;
+        self.sequence = sequence;
+        self.write_offset = write_offset;
+        Ok(())
+    }
+
+    fn bank_address(&self, bank: u8) -> u16 {
+        self.start + bank as u16 * self.bank_len
+    }
+
+    fn read_header(&mut self, bank: u8) -> Result<Option<u16>, KvError<S::Error>> {
+        let mut header = [0u8; HEADER_LEN as usize];
+        self.storage.read(self.bank_address(bank), &mut header)?;
+
+        let crc = u16::from_le_bytes([header[3], header[4]]);
+        if header[0] != BANK_MAGIC || crc16(0xFFFF, &header[..3]) != crc {
+            return Ok(None);
+        }
+        Ok(Some(u16::from_le_bytes([header[1], header[2]])))
+    }
+
+    fn write_header(&mut self, bank: u8, sequence: u16) -> Result<(), KvError<S::Error>> {
+        let [lo, hi] = sequence.to_le_bytes();
+        let crc = crc16(0xFFFF, &[BANK_MAGIC, lo, hi]).to_le_bytes();
+        self.storage.write(self.bank_address(bank), &[BANK_MAGIC, lo, hi, crc[0], crc[1]])?;
+        Ok(())
+    }
+
+    fn erase_bank(&mut self, bank: u8) -> Result<(), KvError<S::Error>> {
+        let erased = [ERASED; CHUNK];
+        let base = self.bank_address(bank);
+        let mut offset = 0;
+
+        while offset < self.bank_len {
+            let len = (self.bank_len - offset).min(CHUNK as u16);
+            self.storage.write(base + offset, &erased[..len as usize])?;
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Parse the record at `offset`, or None at the end of the log
+    fn record_at(&mut self, bank: u8, offset: u16) -> Result<Option<Record>, KvError<S::Error>> {
+        if offset + RECORD_OVERHEAD > self.bank_len {
+            return Ok(None);
+        }
+
+        let address = self.bank_address(bank) + offset;
+        let mut head = [0u8; 2];
+        self.storage.read(address, &mut head)?;
+
+        let [key, len] = head;
+        if key == ERASED {
+            return Ok(None);
+        }
+
+        let mut record = Record { offset, key, len, valid: false };
+        if offset + record.size() > self.bank_len {
+            // Garbage length from a torn write at the end of the bank
+            return Ok(None);
+        }
+
+        // Verify the CRC over key, len and data
+        let mut crc = crc16(0xFFFF, &head);
+        let mut chunk = [0u8; CHUNK];
+        let mut done = 0;
+        while done < record.data_len() {
+            let n = (record.data_len() - done).min(CHUNK as u16);
+            self.storage.read(address + 2 + done, &mut chunk[..n as usize])?;
+            crc = crc16(crc, &chunk[..n as usize]);
+            done += n;
+        }
+
+        let mut stored = [0u8; 2];
+        self.storage.read(address + 2 + record.data_len(), &mut stored)?;
+        record.valid = u16::from_le_bytes(stored) == crc;
+
+        Ok(Some(record))
+    }
+
+    fn find_end(&mut self) -> Result<u16, KvError<S::Error>> {
+        let mut offset = HEADER_LEN;
+        while let Some(record) = self.record_at(self.active, offset)? {
+            offset += record.size();
+        }
+
+        // The log ended on something other than erased space (a record whose
+        // torn length runs past the bank), so the rest of the bank is unusable
+        if offset < self.bank_len {
+            let mut key = [0u8; 1];
+            self.storage.read(self.bank_address(self.active) + offset, &mut key)?;
+            if key[0] != ERASED {
+                return Ok(self.bank_len);
+            }
+        }
+        Ok(offset)
+    }
+
+    fn find_latest(&mut self, key: u8) -> Result<Option<Record>, KvError<S::Error>> {
+        let mut latest = None;
+        let mut offset = HEADER_LEN;
+
+        while let Some(record) = self.record_at(self.active, offset)? {
+            if record.valid && record.key == key {
+                latest = Some(record);
+            }
+            offset += record.size();
+        }
+        Ok(latest)
+    }
+
+    /// Check whether a later valid record for the same key exists
+    fn superseded(&mut self, record: Record) -> Result<bool, KvError<S::Error>> {
+        let mut offset = record.offset + record.size();
+        while let Some(later) = self.record_at(self.active, offset)? {
+            if later.valid && later.key == record.key {
+                return Ok(true);
+            }
+            offset += later.size();
+        }
+        Ok(false)
+    }
+
+    fn data_equals(&mut self, record: Record, value: &[u8]) -> Result<bool, KvError<S::Error>> {
+        let address = self.bank_address(self.active) + record.offset + 2;
+        let mut chunk = [0u8; CHUNK];
+
+        for (i, expected) in value.chunks(CHUNK).enumerate() {
+            let actual = &mut chunk[..expected.len()];
+            self.storage.read(address + (i * CHUNK) as u16, actual)?;
+            if actual != expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn copy_record(&mut self, record: Record, bank: u8, offset: u16) -> Result<(), KvError<S::Error>> {
+        let from = self.bank_address(self.active) + record.offset;
+        let to = self.bank_address(bank) + offset;
+        let mut chunk = [0u8; CHUNK];
+        let mut done = 0;
+
+        while done < record.size() {
+            let n = (record.size() - done).min(CHUNK as u16);
+            self.storage.read(from + done, &mut chunk[..n as usize])?;
+            self.storage.write(to + done, &chunk[..n as usize])?;
+            done += n;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Mem = [u8; 1024];
+
+    fn blank() -> Mem {
+        [ERASED; 1024]
+    }
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(0xFFFF, b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn set_get_roundtrip_across_mounts() {
+        let mut store = KvStore::mount(blank(), 0, 1024).unwrap();
+        store.set(1, &0xDEAD_BEEFu32).unwrap();
+        store.set(2, &-5i16).unwrap();
+        store.set_bytes(3, b"hello").unwrap();
+        store.set(1, &7u32).unwrap();
+
+        let mut store = KvStore::mount(store.release(), 0, 1024).unwrap();
+        assert_eq!(store.get::<u32>(1).unwrap(), Some(7));
+        assert_eq!(store.get::<i16>(2).unwrap(), Some(-5));
+        assert_eq!(store.get::<u8>(9).unwrap(), None);
+
+        let mut buffer = [0u8; 8];
+        assert_eq!(store.get_bytes(3, &mut buffer).unwrap(), Some(5));
+        assert_eq!(&buffer[..5], b"hello");
+        assert_eq!(store.get::<u16>(1), Err(KvError::SizeMismatch));
+    }
+
+    #[test]
+    fn remove_survives_remount() {
+        let mut store = KvStore::mount(blank(), 0, 1024).unwrap();
+        store.set(4, &true).unwrap();
+        store.remove(4).unwrap();
+        assert!(!store.contains(4).unwrap());
+
+        let mut store = KvStore::mount(store.release(), 0, 1024).unwrap();
+        assert_eq!(store.get::<bool>(4).unwrap(), None);
+    }
+
+    #[test]
+    fn unchanged_value_is_not_rewritten() {
+        let mut store = KvStore::mount(blank(), 0, 1024).unwrap();
+        store.set(1, &42u8).unwrap();
+        let free = store.free_space();
+        store.set(1, &42u8).unwrap();
+        assert_eq!(store.free_space(), free);
+    }
+
+    #[test]
+    fn compaction_keeps_latest_values_and_spreads_wear() {
+        let mut store = KvStore::mount(blank(), 0, 1024).unwrap();
+
+        // Enough updates to go around both banks many times
+        for i in 0..2000u32 {
+            store.set(0, &i).unwrap();
+            store.set(1, &(i * 3)).unwrap();
+        }
+        store.set_bytes(2, &[0xAB; 40]).unwrap();
+
+        let mut store = KvStore::mount(store.release(), 0, 1024).unwrap();
+        assert_eq!(store.get::<u32>(0).unwrap(), Some(1999));
+        assert_eq!(store.get::<u32>(1).unwrap(), Some(5997));
+        assert_eq!(store.get::<[u8; 40]>(2).unwrap(), Some([0xAB; 40]));
+    }
+
+    #[test]
+    fn torn_record_keeps_previous_value() {
+        let mut store = KvStore::mount(blank(), 0, 1024).unwrap();
+        store.set(5, &100u16).unwrap();
+        let torn_offset = HEADER_LEN + 4 + 2;
+        store.set(5, &200u16).unwrap();
+        let mut memory = store.release();
+
+        // Simulate power loss before the CRC of the second record was written
+        let crc_address = torn_offset as usize + 2 + 2;
+        memory[crc_address] = ERASED;
+        memory[crc_address + 1] = ERASED;
+
+        let mut store = KvStore::mount(memory, 0, 1024).unwrap();
+        assert_eq!(store.get::<u16>(5).unwrap(), Some(100));
+
+        // New writes go after the torn record and win
+        store.set(5, &300u16).unwrap();
+        let mut store = KvStore::mount(store.release(), 0, 1024).unwrap();
+        assert_eq!(store.get::<u16>(5).unwrap(), Some(300));
+    }
+
+    #[test]
+    fn torn_compaction_keeps_old_bank() {
+        let mut store = KvStore::mount(blank(), 0, 512).unwrap();
+        store.set(1, &11u32).unwrap();
+        let mut memory = store.release();
+
+        // Partially copied data in bank 1 without a header
+        memory[256 + HEADER_LEN as usize] = 1;
+        memory[256 + HEADER_LEN as usize + 1] = 4;
+
+        let mut store = KvStore::mount(memory, 0, 512).unwrap();
+        assert_eq!(store.get::<u32>(1).unwrap(), Some(11));
+    }
+
+    #[test]
+    fn region_offset_leaves_other_memory_alone() {
+        let mut store = KvStore::mount(blank(), 512, 256).unwrap();
+        for i in 0..500u16 {
+            store.set(3, &i).unwrap();
+        }
+        let memory = store.release();
+        assert!(memory[..512].iter().all(|&b| b == ERASED));
+        assert!(memory[768..].iter().all(|&b| b == ERASED));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(matches!(KvStore::mount(blank(), 1000, 100), Err(KvError::InvalidRegion)));
+        assert!(matches!(KvStore::mount(blank(), 0, 63), Err(KvError::InvalidRegion)));
+
+        let mut store = KvStore::mount(blank(), 0, 128).unwrap();
+        assert_eq!(store.set(0xFF, &1u8), Err(KvError::InvalidKey));
+        assert_eq!(store.set_bytes(1, &[0; 255]), Err(KvError::TooLarge));
+        assert_eq!(store.set_bytes(1, &[0; 100]), Err(KvError::Full));
+    }
+
+    #[test]
+    fn format_clears_everything() {
+        let mut store = KvStore::mount(blank(), 0, 256).unwrap();
+        store.set(1, &1u8).unwrap();
+        store.format().unwrap();
+        assert_eq!(store.get::<u8>(1).unwrap(), None);
+
+        let mut store = KvStore::mount(store.release(), 0, 256).unwrap();
+        assert_eq!(store.get::<u8>(1).unwrap(), None);
+    }
+}
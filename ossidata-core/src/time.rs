@@ -0,0 +1,81 @@
+//! Monotonic time abstractions
+//!
+//! Board crates expose a free-running 64-bit microsecond counter through the
+//! [`Clock`] trait. The [`Instant`] and [`Duration`] types are `fugit` types,
+//! so they interoperate with any driver or scheduler built on `fugit`.
+//!
+//! A 64-bit microsecond counter wraps after roughly 584,000 years, so
+//! comparisons and subtractions between instants are always meaningful.
+
+/// Tick rate of [`Instant`] and [`Duration`] (1 tick = 1 microsecond)
+pub const TICK_HZ: u32 = 1_000_000;
+
+/// A point in time, in microseconds since the clock started
+pub type Instant = fugit::TimerInstantU64<TICK_HZ>;
+
+/// A span of time with microsecond resolution
+pub type Duration = fugit::TimerDurationU64<TICK_HZ>;
+
+/// A monotonic clock that never wraps in practice
+///
+/// # Example
+/// ```ignore
+/// use ossidata_core::time::{Clock, Duration};
+///
+/// fn timed_out<C: Clock>(clock: &C, start: ossidata_core::time::Instant) -> bool {
+///     clock.elapsed_since(start) >= Duration::millis(500)
+/// }
+/// ```
+pub trait Clock {
+    /// Get the current instant
+    fn now(&self) -> Instant;
+
+    /// Time elapsed since an earlier instant
+    ///
+    /// Returns zero if `earlier` is in the future.
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        elapsed_between(earlier, self.now())
+    }
+}
+
+/// Time elapsed between two instants, saturating to zero if `later` is before `earlier`
+pub fn elapsed_between(earlier: Instant, later: Instant) -> Duration {
+    later
+        .checked_duration_since(earlier)
+        .unwrap_or(Duration::from_ticks(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> Instant {
+            Instant::from_ticks(self.0)
+        }
+    }
+
+    #[test]
+    fn elapsed_forward() {
+        let earlier = Instant::from_ticks(1_000);
+        let later = Instant::from_ticks(251_000);
+        assert_eq!(elapsed_between(earlier, later), Duration::millis(250));
+        assert_eq!(elapsed_between(earlier, earlier), Duration::from_ticks(0));
+    }
+
+    #[test]
+    fn elapsed_reversed_saturates_to_zero() {
+        let earlier = Instant::from_ticks(5_000);
+        let later = Instant::from_ticks(4_999);
+        assert_eq!(elapsed_between(earlier, later), Duration::from_ticks(0));
+    }
+
+    #[test]
+    fn clock_elapsed_since() {
+        let clock = FixedClock(2_000_000);
+        assert_eq!(clock.elapsed_since(Instant::from_ticks(500_000)), Duration::micros(1_500_000));
+        assert_eq!(clock.elapsed_since(Instant::from_ticks(3_000_000)), Duration::from_ticks(0));
+    }
+}